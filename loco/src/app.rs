@@ -183,6 +183,12 @@ pub trait Hooks {
     /// [`Processor`] and [`AppContext`].
     fn connect_workers<'a>(p: &'a mut Processor, ctx: &'a AppContext);
 
+    /// Registers periodic jobs, which only run with a queue, see
+    /// `sidekiq::periodic`.
+    async fn schedule_workers(_p: &mut Processor, _ctx: &AppContext) -> Result<()> {
+        Ok(())
+    }
+
     // Runs migrations on the database.
     async fn migrate(db: &PgPool) -> Result<()>;
 
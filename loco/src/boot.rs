@@ -184,7 +184,7 @@ pub async fn run_app<H: Hooks>(mode: &StartMode, app_context: AppContext) -> Res
             })
         }
         StartMode::ServerAndWorker => {
-            let processor = create_processor::<H>(&app_context)?;
+            let processor = create_processor::<H>(&app_context).await?;
             let app = H::before_routes(&app_context).await?;
             let app = H::routes(&app_context).to_router(app_context.clone(), app)?;
             let mut router = H::after_routes(app, &app_context).await?;
@@ -198,7 +198,7 @@ pub async fn run_app<H: Hooks>(mode: &StartMode, app_context: AppContext) -> Res
             })
         }
         StartMode::WorkerOnly => {
-            let processor = create_processor::<H>(&app_context)?;
+            let processor = create_processor::<H>(&app_context).await?;
             Ok(BootResult {
                 app_context,
                 router: None,
@@ -208,7 +208,7 @@ pub async fn run_app<H: Hooks>(mode: &StartMode, app_context: AppContext) -> Res
     }
 }
 /// Creates and configures a [`Processor`] for handling worker tasks.
-fn create_processor<H: Hooks>(app_context: &AppContext) -> Result<Processor> {
+async fn create_processor<H: Hooks>(app_context: &AppContext) -> Result<Processor> {
     let queues = worker::get_queues(&app_context.config.workers.queues);
     trace!(
         queues = ?queues,
@@ -230,6 +230,7 @@ fn create_processor<H: Hooks>(app_context: &AppContext) -> Result<Processor> {
 
     p.register(MailerWorker::build(app_context));
     H::connect_workers(&mut p, app_context);
+    H::schedule_workers(&mut p, app_context).await?;
 
     trace!("done registering workers and queues");
     Ok(p)
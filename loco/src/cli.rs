@@ -63,7 +63,12 @@ enum Commands {
         port: Option<i32>,
     },
     /// Describe all application endpoints
-    Routes {},
+    Routes {
+        /// print the endpoints as an OpenAPI document
+        #[cfg(feature = "openapi")]
+        #[arg(long, action)]
+        openapi: bool,
+    },
 
     /// Display the app version
     Version {},
@@ -148,7 +153,13 @@ pub async fn main<H: Hooks>() -> eyre::Result<()> {
             start::<H>(boot_result, serve_params).await?;
             H::cleanup(&app_context).await?;
         }
-        Commands::Routes {} => {
+        #[cfg(feature = "openapi")]
+        Commands::Routes { openapi: true } => {
+            let app_context = create_context::<H>(&environment, None).await?;
+            let document = crate::controller::openapi::document::<H>(&app_context);
+            println!("{}", serde_json::to_string_pretty(&document)?);
+        }
+        Commands::Routes { .. } => {
             let app_context = create_context::<H>(&environment, None).await?;
             show_list_endpoints::<H>(&app_context);
         }
@@ -51,6 +51,8 @@ pub struct ListRoutes {
     pub uri: String,
     pub actions: Vec<axum::http::Method>,
     pub method: axum::routing::MethodRouter<AppContext>,
+    #[cfg(feature = "openapi")]
+    pub doc: Option<super::openapi::Operation>,
 }
 
 impl fmt::Display for ListRoutes {
@@ -124,6 +126,8 @@ impl AppRoutes {
                         uri,
                         actions: controller.actions.clone(),
                         method: controller.method.clone(),
+                        #[cfg(feature = "openapi")]
+                        doc: controller.doc.clone(),
                     }
                 })
             })
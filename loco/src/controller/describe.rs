@@ -17,9 +17,9 @@ pub fn method_action(method: &MethodRouter<AppContext>) -> Vec<http::Method> {
     let method_str = format!("{method:?}");
 
     DESCRIBE_METHOD_ACTION
-        .captures(&method_str)
-        .and_then(|captures| captures.get(1).map(|m| m.as_str().to_lowercase()))
-        .and_then(|method_name| match method_name.as_str() {
+        .captures_iter(&method_str)
+        .filter_map(|captures| captures.get(1).map(|m| m.as_str().to_lowercase()))
+        .filter_map(|method_name| match method_name.as_str() {
             "get" => Some(http::Method::GET),
             "post" => Some(http::Method::POST),
             "put" => Some(http::Method::PUT),
@@ -34,6 +34,5 @@ pub fn method_action(method: &MethodRouter<AppContext>) -> Vec<http::Method> {
                 None
             }
         })
-        .into_iter()
         .collect::<Vec<_>>()
 }
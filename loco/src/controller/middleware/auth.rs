@@ -19,20 +19,20 @@
 //!     format::json(TestResponse{ id: auth.claims.id})
 //! }
 //! ```
-use std::collections::HashMap;
+use std::{collections::HashMap, marker::PhantomData};
 
 use async_trait::async_trait;
 use axum::{
     extract::{FromRef, FromRequestParts, Query},
-    http::{request::Parts, HeaderMap},
+    http::{request::Parts, HeaderMap, StatusCode},
 };
 use axum_extra::extract::cookie;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    app::AppContext, auth, config::JWT as JWTConfig, errors::Error, model::Authenticable,
-    Result as LocoResult,
+    app::AppContext, auth, config::JWT as JWTConfig, controller::ErrorDetail, errors::Error,
+    model::Authenticable, Result as LocoResult,
 };
 
 // ---------------------------------------
@@ -198,20 +198,39 @@ pub fn extract_token_from_query(name: &str, parts: &Parts) -> LocoResult<String>
 // API Token Auth / Extractor
 //
 // ---------------------------------------
+
+/// A permission an API key must be granted to be accepted by [`ApiToken`].
+///
+/// The unit type accepts any valid key.
+pub trait Scope: Send + Sync {
+    /// Whether a key granted `scopes` may be used for this scope.
+    fn is_granted(scopes: &[String]) -> bool;
+}
+
+impl Scope for () {
+    fn is_granted(_scopes: &[String]) -> bool {
+        true
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 // Represents the data structure for the API token.
-pub struct ApiToken<T: Authenticable> {
+pub struct ApiToken<T: Authenticable, R: Scope = ()> {
     pub user: T,
+    pub scopes: Vec<String>,
+    #[serde(skip)]
+    scope: PhantomData<R>,
 }
 
 #[async_trait]
 // Implementing the `FromRequestParts` trait for `ApiToken` to enable extracting
 // it from the request.
-impl<S, T> FromRequestParts<S> for ApiToken<T>
+impl<S, T, R> FromRequestParts<S> for ApiToken<T, R>
 where
     AppContext: FromRef<S>,
     S: Send + Sync,
     T: Authenticable,
+    R: Scope,
 {
     type Rejection = Error;
 
@@ -219,17 +238,30 @@ where
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Error> {
         // Extract API key from the request header.
         let api_key = extract_token_from_header(&parts.headers)?;
-        let api_key = Uuid::parse_str(&api_key)?;
 
         // Convert the state reference to the application context.
         let state: AppContext = AppContext::from_ref(state);
 
         // Retrieve user information based on the API key from the database.
-        let user = T::find_by_api_key(&state.db, api_key)
+        let grant = T::find_by_api_key(&state.db, &api_key)
             .await
             .map_err(|e| Error::Unauthorized(e.to_string()))?;
 
-        Ok(Self { user })
+        if !R::is_granted(&grant.scopes) {
+            return Err(Error::CustomError(
+                StatusCode::FORBIDDEN,
+                ErrorDetail::new(
+                    "insufficient_scope",
+                    "The API key is not allowed to access this resource",
+                ),
+            ));
+        }
+
+        Ok(Self {
+            user: grant.user,
+            scopes: grant.scopes,
+            scope: PhantomData,
+        })
     }
 }
 
@@ -8,7 +8,7 @@ use colored::Colorize;
 pub use routes::Routes;
 use serde::Serialize;
 
-use crate::{errors::Error, Result};
+use crate::{errors::Error, model::ModelError, Result};
 
 mod app_routes;
 mod backtrace;
@@ -19,6 +19,8 @@ pub mod format;
 
 mod health;
 pub mod middleware;
+#[cfg(feature = "openapi")]
+pub mod openapi;
 mod ping;
 mod routes;
 pub mod views;
@@ -67,6 +69,7 @@ pub const fn not_found<T>() -> Result<T> {
     Err(Error::NotFound)
 }
 #[derive(Debug, Serialize)]
+#[cfg_attr(feature = "openapi", derive(schemars::JsonSchema))]
 /// Structure representing details about an error.
 pub struct ErrorDetail {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -147,6 +150,18 @@ impl IntoResponse for Error {
                     ),
                 )
             }
+            Self::Model(ModelError::EntityNotFound) => (
+                StatusCode::NOT_FOUND,
+                ErrorDetail::new("not_found", "Resource was not found"),
+            ),
+            Self::BadRequest(err) => (
+                StatusCode::BAD_REQUEST,
+                ErrorDetail::new("bad_request".to_string(), err),
+            ),
+            Self::JsonRejection(err) => (
+                err.status(),
+                ErrorDetail::new("bad_request".to_string(), err.body_text()),
+            ),
             Self::CustomError(status_code, data) => (status_code, data),
             Self::WithBacktrace { inner, backtrace } => {
                 println!("\n{}", inner.to_string().red().underline());
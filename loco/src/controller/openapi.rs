@@ -0,0 +1,444 @@
+//! Generates an [OpenAPI 3.1](https://spec.openapis.org/oas/v3.1.0) document
+//! from the application route table.
+//!
+//! Every route collected by [`AppRoutes`](super::AppRoutes) becomes an
+//! operation. Routes can be enriched with a summary, request, query and
+//! response schemas by attaching an [`Operation`] with [`Routes::doc`]. The
+//! schemas are derived from the serde types used by the handlers through
+//! [`schemars::JsonSchema`].
+//!
+//! # Example
+//!
+//! ```rust
+//! use loco_rs::{controller::openapi::Operation, prelude::*};
+//! use schemars::JsonSchema;
+//! use serde::Serialize;
+//!
+//! #[derive(Serialize, JsonSchema)]
+//! struct Health {
+//!     pub ok: bool,
+//! }
+//!
+//! async fn ping() -> Result<Response> {
+//!     format::json(Health { ok: true })
+//! }
+//!
+//! Routes::new()
+//!     .add("/_ping", get(ping))
+//!     .doc(Operation::new("Ping the server").response::<Health>());
+//! ```
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::State,
+    http::{Method, StatusCode},
+    response::Response,
+    routing::get,
+};
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{Schema, SchemaObject, SingleOrVec},
+    visit::{visit_schema_object, Visitor},
+    JsonSchema,
+};
+use serde_json::{json, Map, Value};
+
+use super::{format, routes::Routes, ErrorDetail, ListRoutes};
+use crate::{
+    app::{AppContext, Hooks},
+    boot::list_endpoints,
+    Result,
+};
+
+const OPENAPI_VERSION: &str = "3.1.0";
+const SCHEMAS_PATH: &str = "#/components/schemas/";
+const BEARER_SCHEME: &str = "bearer";
+
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+fn subschema<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    gen.subschema_for::<T>()
+}
+
+fn inline_schema<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    T::json_schema(gen)
+}
+
+/// Documentation attached to a single route.
+#[derive(Clone, Default)]
+pub struct Operation {
+    summary: Option<String>,
+    tags: Vec<String>,
+    request: Option<SchemaFn>,
+    query: Option<SchemaFn>,
+    response: Option<SchemaFn>,
+    status: Option<StatusCode>,
+    bearer_auth: bool,
+}
+
+impl Operation {
+    /// Create an operation with a short summary.
+    #[must_use]
+    pub fn new(summary: &str) -> Self {
+        Self {
+            summary: Some(summary.to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// Group the operation under a tag.
+    #[must_use]
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
+    }
+
+    /// The JSON request body accepted by the handler.
+    #[must_use]
+    pub fn request<T: JsonSchema>(mut self) -> Self {
+        self.request = Some(subschema::<T>);
+        self
+    }
+
+    /// The query string accepted by the handler. Each property of `T` is
+    /// documented as a query parameter.
+    #[must_use]
+    pub fn query<T: JsonSchema>(mut self) -> Self {
+        self.query = Some(inline_schema::<T>);
+        self
+    }
+
+    /// The JSON response body returned by the handler.
+    #[must_use]
+    pub fn response<T: JsonSchema>(mut self) -> Self {
+        self.response = Some(subschema::<T>);
+        self
+    }
+
+    /// The status code of a successful response, `200 OK` by default.
+    #[must_use]
+    pub const fn status(mut self, status: StatusCode) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Mark the operation as requiring an `Authorization: Bearer` header.
+    #[must_use]
+    pub const fn bearer_auth(mut self) -> Self {
+        self.bearer_auth = true;
+        self
+    }
+}
+
+/// Convert an axum route (`/sets/:id`) to an `OpenAPI` path template
+/// (`/sets/{id}`), returning the path parameter names.
+fn path_template(uri: &str) -> (String, Vec<String>) {
+    let mut params = vec![];
+    let path = uri
+        .split('/')
+        .map(|segment| {
+            segment
+                .strip_prefix(':')
+                .or_else(|| segment.strip_prefix('*'))
+                .map_or_else(
+                    || segment.to_string(),
+                    |name| {
+                        params.push(name.to_string());
+                        format!("{{{name}}}")
+                    },
+                )
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    (path, params)
+}
+
+fn operation_id(method: &Method, path: &str) -> String {
+    let path = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            segment
+                .trim_start_matches('{')
+                .trim_end_matches('}')
+                .replace(|c: char| !c.is_ascii_alphanumeric(), "_")
+        })
+        .collect::<Vec<_>>()
+        .join("_");
+    format!("{}_{path}", method.as_str().to_lowercase())
+}
+
+fn to_value(schema: &Schema) -> Value {
+    serde_json::to_value(schema).unwrap_or(Value::Bool(true))
+}
+
+fn json_content(schema: &Schema) -> Value {
+    json!({ "application/json": { "schema": to_value(schema) } })
+}
+
+fn query_parameters(schema: &Schema) -> Vec<Value> {
+    let Schema::Object(SchemaObject {
+        object: Some(object),
+        ..
+    }) = schema
+    else {
+        return vec![];
+    };
+
+    object
+        .properties
+        .iter()
+        .map(|(name, property)| {
+            let mut parameter = json!({
+                "name": name,
+                "in": "query",
+                "required": object.required.contains(name),
+                "schema": to_value(property),
+            });
+            if let Schema::Object(SchemaObject {
+                metadata: Some(metadata),
+                ..
+            }) = property
+            {
+                if let Some(description) = &metadata.description {
+                    parameter["description"] = json!(description);
+                }
+            }
+            parameter
+        })
+        .collect()
+}
+
+fn operation(
+    gen: &mut SchemaGenerator,
+    method: &Method,
+    path: &str,
+    path_params: &[String],
+    doc: Option<&Operation>,
+    error_schema: &Schema,
+) -> Value {
+    let mut op = Map::new();
+    op.insert("operationId".to_string(), json!(operation_id(method, path)));
+
+    let mut parameters = path_params
+        .iter()
+        .map(|name| {
+            json!({
+                "name": name,
+                "in": "path",
+                "required": true,
+                "schema": { "type": "string" },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let status = doc.and_then(|doc| doc.status).unwrap_or(StatusCode::OK);
+    let mut success = json!({
+        "description": status.canonical_reason().unwrap_or("OK"),
+    });
+
+    if let Some(doc) = doc {
+        if let Some(summary) = &doc.summary {
+            op.insert("summary".to_string(), json!(summary));
+        }
+        if !doc.tags.is_empty() {
+            op.insert("tags".to_string(), json!(doc.tags));
+        }
+        if let Some(query) = doc.query {
+            parameters.extend(query_parameters(&query(gen)));
+        }
+        if let Some(request) = doc.request {
+            op.insert(
+                "requestBody".to_string(),
+                json!({ "required": true, "content": json_content(&request(gen)) }),
+            );
+        }
+        if let Some(response) = doc.response {
+            success["content"] = json_content(&response(gen));
+        }
+        if doc.bearer_auth {
+            op.insert("security".to_string(), json!([{ BEARER_SCHEME: [] }]));
+        }
+    }
+
+    if !parameters.is_empty() {
+        op.insert("parameters".to_string(), Value::Array(parameters));
+    }
+    op.insert(
+        "responses".to_string(),
+        json!({
+            status.as_str(): success,
+            "default": {
+                "description": "Error",
+                "content": json_content(error_schema),
+            },
+        }),
+    );
+
+    Value::Object(op)
+}
+
+/// Rewrites tuple schemas to JSON Schema 2020-12, where the positional
+/// schemas moved from `items` to `prefixItems`, and `additionalItems` to
+/// `items`.
+#[derive(Debug, Clone)]
+struct PrefixItems;
+
+impl Visitor for PrefixItems {
+    fn visit_schema_object(&mut self, schema: &mut SchemaObject) {
+        visit_schema_object(self, schema);
+
+        let Some(array) = schema.array.as_deref_mut() else {
+            return;
+        };
+        if let Some(SingleOrVec::Vec(items)) = array.items.take() {
+            let additional_items = array.additional_items.take();
+            array.items = additional_items.map(SingleOrVec::Single);
+            schema
+                .extensions
+                .insert("prefixItems".to_string(), json!(items));
+        }
+    }
+}
+
+/// Schema settings for JSON Schema 2020-12, the dialect of `OpenAPI` 3.1.
+/// `schemars` only knows up to 2019-09, which differs in tuples.
+fn draft2020_12() -> SchemaSettings {
+    let mut settings = SchemaSettings::draft2019_09();
+    settings.meta_schema = Some("https://json-schema.org/draft/2020-12/schema".to_string());
+    settings.visitors.push(Box::new(PrefixItems));
+    settings
+}
+
+/// Build an `OpenAPI` document from a list of routes.
+#[must_use]
+pub fn document_from_routes(title: &str, version: &str, routes: &[ListRoutes]) -> Value {
+    let mut settings = draft2020_12();
+    settings.definitions_path = SCHEMAS_PATH.to_string();
+    settings.meta_schema = None;
+    let mut gen = settings.into_generator();
+    let error_schema = gen.subschema_for::<ErrorDetail>();
+
+    let mut paths: BTreeMap<String, Map<String, Value>> = BTreeMap::new();
+    for route in routes {
+        let (path, path_params) = path_template(&route.uri);
+        for method in &route.actions {
+            let op = operation(
+                &mut gen,
+                method,
+                &path,
+                &path_params,
+                route.doc.as_ref(),
+                &error_schema,
+            );
+            paths
+                .entry(path.clone())
+                .or_default()
+                .insert(method.as_str().to_lowercase(), op);
+        }
+    }
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": { "title": title, "version": version },
+        "paths": paths,
+        "components": {
+            "schemas": gen.take_definitions(),
+            "securitySchemes": {
+                BEARER_SCHEME: { "type": "http", "scheme": "bearer" },
+            },
+        },
+    })
+}
+
+/// Build the `OpenAPI` document of the application.
+#[must_use]
+pub fn document<H: Hooks>(ctx: &AppContext) -> Value {
+    document_from_routes(H::app_name(), &H::app_version(), &list_endpoints::<H>(ctx))
+}
+
+async fn openapi<H: Hooks>(State(ctx): State<AppContext>) -> Result<Response> {
+    format::json(document::<H>(&ctx))
+}
+
+/// Serves the application document at `/_openapi.json`.
+pub fn routes<H: Hooks + 'static>() -> Routes {
+    Routes::new().add("/_openapi.json", get(openapi::<H>))
+}
+
+#[cfg(test)]
+mod tests {
+    use insta::assert_snapshot;
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Item {
+        name: String,
+        count: Option<i32>,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Page {
+        /// Page number, starting at 1
+        page: i64,
+    }
+
+    async fn handler() -> Result<Response> {
+        format::empty()
+    }
+
+    #[test]
+    fn can_convert_path_template() {
+        assert_eq!(
+            path_template("/sets/:id/tracks/:number"),
+            (
+                "/sets/{id}/tracks/{number}".to_string(),
+                vec!["id".to_string(), "number".to_string()]
+            )
+        );
+        assert_eq!(operation_id(&Method::GET, "/sets/{id}"), "get_sets_id");
+    }
+
+    #[test]
+    fn can_write_tuples_as_prefix_items() {
+        let schema = draft2020_12()
+            .into_generator()
+            .into_root_schema_for::<(String, i32)>();
+        let schema = serde_json::to_value(schema).unwrap();
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert_eq!(schema["prefixItems"][0]["type"], "string");
+        assert_eq!(schema["prefixItems"][1]["type"], "integer");
+        assert!(schema.get("items").is_none());
+    }
+
+    #[test]
+    fn can_generate_document() {
+        let routes = super::super::AppRoutes::empty()
+            .add_route(
+                Routes::new()
+                    .prefix("items")
+                    .add("/", get(handler))
+                    .doc(Operation::new("List items").query::<Page>().tag("items"))
+                    .add("/", axum::routing::post(handler))
+                    .doc(
+                        Operation::new("Create an item")
+                            .request::<Item>()
+                            .response::<Item>()
+                            .status(StatusCode::CREATED)
+                            .bearer_auth(),
+                    )
+                    .add("/:id", axum::routing::delete(handler)),
+            )
+            .collect();
+
+        let document = document_from_routes("test", "0.1.0", &routes);
+        assert_snapshot!(serde_json::to_string_pretty(&document).unwrap());
+    }
+}
@@ -4,6 +4,8 @@ use axum::{extract::Request, response::IntoResponse, routing::Route};
 use tower::{Layer, Service};
 
 use super::describe;
+#[cfg(feature = "openapi")]
+use super::openapi::Operation;
 use crate::app::AppContext;
 #[derive(Clone, Default)]
 pub struct Routes {
@@ -17,6 +19,8 @@ pub struct Handler {
     pub uri: String,
     pub method: axum::routing::MethodRouter<AppContext>,
     pub actions: Vec<axum::http::Method>,
+    #[cfg(feature = "openapi")]
+    pub doc: Option<Operation>,
 }
 
 impl Routes {
@@ -83,10 +87,35 @@ impl Routes {
             uri: uri.to_owned(),
             actions: describe::method_action(&method),
             method,
+            #[cfg(feature = "openapi")]
+            doc: None,
         });
         self
     }
 
+    /// Attach `OpenAPI` documentation to the most recently added route.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use loco_rs::{controller::openapi::Operation, prelude::*};
+    ///
+    /// async fn ping() -> Result<Response> {
+    ///     format::json("Ok")
+    /// }
+    /// Routes::new()
+    ///     .add("/_ping", get(ping))
+    ///     .doc(Operation::new("Ping the server"));
+    /// ```
+    #[cfg(feature = "openapi")]
+    #[must_use]
+    pub fn doc(mut self, doc: Operation) -> Self {
+        if let Some(handler) = self.handlers.last_mut() {
+            handler.doc = Some(doc);
+        }
+        self
+    }
+
     /// Set a prefix for the routes. this prefix will be a prefix for all the
     /// routes.
     ///
@@ -150,6 +179,8 @@ impl Routes {
                     uri: handler.uri.clone(),
                     actions: handler.actions.clone(),
                     method: handler.method.clone().layer(layer.clone()),
+                    #[cfg(feature = "openapi")]
+                    doc: handler.doc.clone(),
                 })
                 .collect(),
         }
@@ -4,8 +4,12 @@
 
 use eyre::Report;
 use lettre::{
-    message::MultiPart, transport::smtp::authentication::Credentials, AsyncTransport, Message,
-    Tokio1Executor, Transport,
+    message::{
+        header::{HeaderName, HeaderValue},
+        MultiPart,
+    },
+    transport::smtp::authentication::Credentials,
+    AsyncTransport, Message, Tokio1Executor, Transport,
 };
 use secrecy::ExposeSecret;
 
@@ -121,13 +125,19 @@ impl EmailSender {
             builder = builder.reply_to(reply_to.parse()?);
         }
 
-        let msg = builder
+        let mut msg = builder
             .subject(email.subject.clone())
             .multipart(content)
             .map_err(|error| {
                 tracing::error!(err.msg = %error, err.detail = ?error, "email_building_error");
                 Report::msg("error building email message")
             })?;
+        for (name, value) in &email.headers {
+            let name = HeaderName::new_from_ascii(name.clone())
+                .map_err(|_| Report::msg(format!("invalid email header name: {name}")))?;
+            msg.headers_mut()
+                .insert_raw(HeaderValue::new(name, value.clone()));
+        }
 
         match &self.transport {
             EmailTransport::Smtp(xp) => {
@@ -174,6 +184,10 @@ mod tests {
             html: html.to_string(),
             bcc: None,
             cc: None,
+            headers: vec![(
+                "List-Unsubscribe".to_string(),
+                "<https://example.com/u>".to_string(),
+            )],
         };
         assert!(sender.mail(&data).await.is_ok());
 
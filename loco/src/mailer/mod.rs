@@ -27,6 +27,7 @@ pub struct Args {
     pub locals: serde_json::Value,
     pub bcc: Option<String>,
     pub cc: Option<String>,
+    pub headers: Vec<(String, String)>,
 }
 
 /// The structure representing an email details.
@@ -48,6 +49,9 @@ pub struct Email {
     pub bcc: Option<String>,
     /// CC header to message
     pub cc: Option<String>,
+    /// Extra headers to message, e.g. `List-Unsubscribe`
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
 }
 
 /// The options struct for configuring the email sender.
@@ -100,6 +104,7 @@ pub trait Mailer {
                 html: content.html,
                 bcc: args.bcc.clone(),
                 cc: args.cc.clone(),
+                headers: args.headers.clone(),
             },
         )
         .await
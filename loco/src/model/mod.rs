@@ -44,8 +44,16 @@ pub enum ModelError {
 #[allow(clippy::module_name_repetitions)]
 pub type ModelResult<T, E = ModelError> = std::result::Result<T, E>;
 
+/// The owner of an API key together with the scopes granted to the key.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ApiKeyGrant<T> {
+    pub user: T,
+    pub scopes: Vec<String>,
+}
+
 #[async_trait]
 pub trait Authenticable: Clone {
-    async fn find_by_api_key(db: &PgPool, api_key: Uuid) -> ModelResult<Self>;
+    /// Finds the owner of a valid (known, unexpired and unrevoked) API key.
+    async fn find_by_api_key(db: &PgPool, api_key: &str) -> ModelResult<ApiKeyGrant<Self>>;
     async fn find_by_claims_key(db: &PgPool, claims_key: Uuid) -> ModelResult<Self>;
 }
@@ -20,7 +20,7 @@ pub use crate::{
     errors::Error,
     mailer,
     mailer::Mailer,
-    model::{Authenticable, ModelError, ModelResult},
+    model::{ApiKeyGrant, Authenticable, ModelError, ModelResult},
     validation::{self, Validatable},
     validator::Validate,
     worker::{self, AppWorker},
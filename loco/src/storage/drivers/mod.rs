@@ -1,7 +1,8 @@
-use std::path::Path;
+use std::{ops::Range, path::Path};
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use futures_util::stream::BoxStream;
 #[cfg(feature = "storage_aws_s3")]
 pub mod aws;
 #[cfg(feature = "storage_azure")]
@@ -33,6 +34,20 @@ pub trait StoreDriver: Sync + Send {
     /// Returns a `StorageResult` with the result of the upload operation.
     async fn upload(&self, path: &Path, content: &Bytes) -> StorageResult<UploadResponse>;
 
+    /// Uploads the chunks of a stream to the specified path in the object
+    /// store without holding the whole content in memory. Nothing is stored
+    /// when the stream yields an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `StorageResult` with the result of the upload operation, or
+    /// the first error of the stream.
+    async fn upload_stream(
+        &self,
+        path: &Path,
+        stream: BoxStream<'_, StorageResult<Bytes>>,
+    ) -> StorageResult<UploadResponse>;
+
     /// Retrieves the content from the specified path in the object store.
     ///
     /// # Errors
@@ -40,6 +55,14 @@ pub trait StoreDriver: Sync + Send {
     /// Returns a `StorageResult` with the result of the retrieval operation.
     async fn get(&self, path: &Path) -> StorageResult<GetResponse>;
 
+    /// Retrieves a byte range of the content from the specified path in the
+    /// object store.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `StorageResult` with the result of the retrieval operation.
+    async fn get_range(&self, path: &Path, range: Range<usize>) -> StorageResult<GetResponse>;
+
     /// Deletes the content at the specified path in the object store.
     ///
     /// # Errors
@@ -4,10 +4,11 @@
 //! Loco framework is initialized. The primary purpose of this driver is to
 //! simplify the user workflow by avoiding the need for feature flags or
 //! optional storage driver configurations.
-use std::path::Path;
+use std::{ops::Range, path::Path};
 
 use async_trait::async_trait;
 use bytes::Bytes;
+use futures_util::stream::BoxStream;
 
 use super::{GetResponse, StorageResult, StoreDriver, UploadResponse};
 use crate::storage::StorageError;
@@ -34,6 +35,22 @@ impl StoreDriver for NullStorage {
         ))
     }
 
+    /// Uploads the chunks of a stream to the specified path in the object
+    /// store.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `StorageResult` with the result of the upload operation.
+    async fn upload_stream(
+        &self,
+        _path: &Path,
+        _stream: BoxStream<'_, StorageResult<Bytes>>,
+    ) -> StorageResult<UploadResponse> {
+        Err(StorageError::Any(
+            "Operation not supported by null storage".into(),
+        ))
+    }
+
     /// Retrieves the content from the specified path in the object store.
     ///
     /// # Errors
@@ -45,6 +62,18 @@ impl StoreDriver for NullStorage {
         ))
     }
 
+    /// Retrieves a byte range of the content from the specified path in the
+    /// object store.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `StorageResult` with the result of the retrieval operation.
+    async fn get_range(&self, _path: &Path, _range: Range<usize>) -> StorageResult<GetResponse> {
+        Err(StorageError::Any(
+            "Operation not supported by null storage".into(),
+        ))
+    }
+
     /// Deletes the content at the specified path in the object store.
     ///
     /// # Errors
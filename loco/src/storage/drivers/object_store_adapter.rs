@@ -1,12 +1,16 @@
-use std::path::Path;
+use std::{ops::Range, path::Path};
 
 use async_trait::async_trait;
 use bytes::Bytes;
-use object_store::ObjectStore;
+use futures_util::{stream::BoxStream, StreamExt};
+use object_store::{GetOptions, ObjectStore, WriteMultipart};
 
 use super::{GetResponse, StoreDriver, UploadResponse};
 use crate::storage::StorageResult;
 
+/// Parts of a streamed upload sent to the store at the same time.
+const MAX_CONCURRENT_PARTS: usize = 8;
+
 pub struct ObjectStoreAdapter {
     object_store_impl: Box<dyn object_store::ObjectStore>,
 }
@@ -39,6 +43,40 @@ impl StoreDriver for ObjectStoreAdapter {
         })
     }
 
+    /// Uploads the chunks of a stream to the specified path in the object
+    /// store as a multipart upload, which is aborted when the stream yields an
+    /// error.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `StorageResult` with the result of the upload operation, or
+    /// the first error of the stream.
+    async fn upload_stream(
+        &self,
+        path: &Path,
+        mut stream: BoxStream<'_, StorageResult<Bytes>>,
+    ) -> StorageResult<UploadResponse> {
+        let path = object_store::path::Path::from(path.display().to_string());
+        let mut upload = WriteMultipart::new(self.object_store_impl.put_multipart(&path).await?);
+        while let Some(chunk) = stream.next().await {
+            match chunk {
+                Ok(chunk) => {
+                    upload.wait_for_capacity(MAX_CONCURRENT_PARTS).await?;
+                    upload.put(chunk);
+                }
+                Err(err) => {
+                    upload.abort().await?;
+                    return Err(err);
+                }
+            }
+        }
+        let res = upload.finish().await?;
+        Ok(UploadResponse {
+            e_tag: res.e_tag,
+            version: res.version,
+        })
+    }
+
     /// Retrieves the content from the specified path in the object store.
     ///
     /// # Errors
@@ -49,6 +87,21 @@ impl StoreDriver for ObjectStoreAdapter {
         Ok(self.object_store_impl.get(&path).await?)
     }
 
+    /// Retrieves a byte range of the content from the specified path in the
+    /// object store.
+    ///
+    /// # Errors
+    ///
+    /// Returns a `StorageResult` with the result of the retrieval operation.
+    async fn get_range(&self, path: &Path, range: Range<usize>) -> StorageResult<GetResponse> {
+        let path = object_store::path::Path::from(path.display().to_string());
+        let options = GetOptions {
+            range: Some(range.into()),
+            ..GetOptions::default()
+        };
+        Ok(self.object_store_impl.get_opts(&path, options).await?)
+    }
+
     /// Deletes the content at the specified path in the object store.
     ///
     /// # Errors
@@ -1,6 +1,8 @@
+use std::time::Duration;
+
 use async_trait::async_trait;
 pub use bb8::Pool;
-pub use sidekiq::{Processor, RedisConnectionManager, Result, Worker};
+pub use sidekiq::{periodic, Processor, RedisConnectionManager, Result, Worker};
 use tracing::error;
 
 use super::{app::AppContext, config::WorkerMode};
@@ -54,4 +56,34 @@ where
         }
         Ok(())
     }
+
+    /// Like [`AppWorker::perform_later`], but the job runs once `delay` has
+    /// passed. In `ForegroundBlocking` mode there is nothing to wait for, so
+    /// the job runs right away.
+    async fn perform_after(ctx: &AppContext, delay: Duration, args: T) -> Result<()> {
+        match &ctx.config.workers.mode {
+            WorkerMode::BackgroundQueue => {
+                if let Some(queue) = &ctx.queue {
+                    <Self as Worker<T>>::perform_in(queue, delay, args).await?;
+                } else {
+                    error!(
+                        error.msg =
+                            "worker mode requested but no queue connection supplied, skipping job",
+                        "worker_error"
+                    );
+                }
+            }
+            WorkerMode::ForegroundBlocking => {
+                Self::build(ctx).perform(args).await?;
+            }
+            WorkerMode::BackgroundAsync => {
+                let dx = ctx.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    Self::build(&dx).perform(args).await
+                });
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS setlist_songs_setlist_id_idx;
+ALTER TABLE setlist_songs DROP CONSTRAINT fk_setlist;
+ALTER TABLE setlist_songs ADD CONSTRAINT fk_setlist FOREIGN KEY (setlist_id) REFERENCES sets (id);
+ALTER TABLE sets ALTER COLUMN venue SET NOT NULL;
@@ -0,0 +1,4 @@
+ALTER TABLE sets ALTER COLUMN venue DROP NOT NULL;
+ALTER TABLE setlist_songs DROP CONSTRAINT fk_setlist;
+ALTER TABLE setlist_songs ADD CONSTRAINT fk_setlist FOREIGN KEY (setlist_id) REFERENCES sets (id) ON DELETE CASCADE;
+CREATE INDEX IF NOT EXISTS setlist_songs_setlist_id_idx ON setlist_songs (setlist_id);
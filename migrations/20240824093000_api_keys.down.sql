@@ -0,0 +1,4 @@
+ALTER TABLE users ADD COLUMN api_key uuid UNIQUE;
+UPDATE users SET api_key = gen_random_uuid();
+ALTER TABLE users ALTER COLUMN api_key SET NOT NULL;
+DROP TABLE IF EXISTS api_keys;
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS api_keys (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name text NOT NULL,
+    prefix text NOT NULL,
+    key_hash text NOT NULL UNIQUE,
+    scopes text [] NOT NULL,
+    last_used_at timestamp,
+    expires_at timestamp
+);
+
+CREATE INDEX IF NOT EXISTS api_keys_user_id_idx ON api_keys (user_id);
+
+-- Existing keys keep working: they are hashed the same way as new keys.
+INSERT INTO api_keys (id, user_id, name, prefix, key_hash, scopes)
+SELECT gen_random_uuid(), id, 'Default key', left(api_key::text, 8),
+    encode(sha256(convert_to(api_key::text, 'UTF8')), 'hex'), ARRAY ['admin']
+FROM users;
+
+ALTER TABLE users DROP COLUMN api_key;
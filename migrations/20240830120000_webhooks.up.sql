@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS webhooks (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    url text NOT NULL,
+    secret text NOT NULL,
+    events text [] NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS webhooks_user_id_idx ON webhooks (user_id);
+
+CREATE TABLE IF NOT EXISTS webhook_deliveries (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    webhook_id uuid NOT NULL REFERENCES webhooks (id) ON DELETE CASCADE,
+    event text NOT NULL,
+    payload jsonb NOT NULL,
+    status text NOT NULL DEFAULT 'pending',
+    attempts integer NOT NULL DEFAULT 0,
+    response_status integer,
+    error text,
+    next_attempt_at timestamp,
+    delivered_at timestamp
+);
+
+CREATE INDEX IF NOT EXISTS webhook_deliveries_webhook_id_idx
+ON webhook_deliveries (webhook_id, created_at);
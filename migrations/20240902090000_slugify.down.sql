@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS sets_updated_at_idx;
+DROP INDEX IF EXISTS sets_venue_slug_idx;
+DROP FUNCTION IF EXISTS slugify;
@@ -0,0 +1,9 @@
+-- Slugs of DJ and venue names, see `utils::slugify`.
+CREATE OR REPLACE FUNCTION slugify(value text) RETURNS text
+LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+AS $$
+    SELECT trim(BOTH '-' FROM regexp_replace(lower(value), '[^a-z0-9]+', '-', 'g'))
+$$;
+
+CREATE INDEX IF NOT EXISTS sets_venue_slug_idx ON sets (slugify(venue));
+CREATE INDEX IF NOT EXISTS sets_updated_at_idx ON sets (updated_at);
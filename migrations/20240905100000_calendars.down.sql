@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS sets_start_time_idx;
+DROP TABLE IF EXISTS dj_follows;
+ALTER TABLE users DROP COLUMN calendar_token;
@@ -0,0 +1,12 @@
+ALTER TABLE users ADD COLUMN calendar_token uuid UNIQUE;
+
+CREATE TABLE IF NOT EXISTS dj_follows (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- see `utils::slugify`
+    dj_slug text NOT NULL,
+    dj_name text NOT NULL,
+    PRIMARY KEY (user_id, dj_slug)
+);
+
+CREATE INDEX IF NOT EXISTS sets_start_time_idx ON sets (start_time);
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS sets_creator_id_idx;
+DROP TABLE IF EXISTS user_follows;
+DROP TABLE IF EXISTS venue_follows;
@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS venue_follows (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- see `utils::slugify`
+    venue_slug text NOT NULL,
+    venue_name text NOT NULL,
+    PRIMARY KEY (user_id, venue_slug)
+);
+
+CREATE TABLE IF NOT EXISTS user_follows (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    follower_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    followee_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    PRIMARY KEY (follower_id, followee_id),
+    CHECK (follower_id <> followee_id)
+);
+
+CREATE INDEX IF NOT EXISTS user_follows_followee_id_idx ON user_follows (followee_id);
+CREATE INDEX IF NOT EXISTS sets_creator_id_idx ON sets (creator_id);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS id_requests;
+DROP TABLE IF EXISTS edit_proposals;
+DROP TABLE IF EXISTS email_preferences;
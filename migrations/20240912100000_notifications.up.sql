@@ -0,0 +1,34 @@
+CREATE TABLE IF NOT EXISTS email_preferences (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id uuid PRIMARY KEY REFERENCES users (id) ON DELETE CASCADE,
+    followed_sets boolean NOT NULL DEFAULT TRUE,
+    edit_proposals boolean NOT NULL DEFAULT TRUE,
+    identified_tracks boolean NOT NULL DEFAULT TRUE,
+    -- opt-in
+    weekly_digest boolean NOT NULL DEFAULT FALSE,
+    -- authenticates the unsubscribe links of emails
+    unsubscribe_token uuid NOT NULL UNIQUE,
+    last_digest_at timestamp
+);
+
+CREATE TABLE IF NOT EXISTS edit_proposals (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    proposer_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    message text NOT NULL,
+    status text NOT NULL DEFAULT 'open'
+);
+
+CREATE INDEX IF NOT EXISTS edit_proposals_set_id_idx ON edit_proposals (set_id);
+
+-- users waiting for an unidentified track to be identified
+CREATE TABLE IF NOT EXISTS id_requests (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    track_number integer NOT NULL,
+    PRIMARY KEY (set_id, track_number, user_id)
+);
@@ -0,0 +1,18 @@
+-- the in-app notification center, read_at is NULL while unread
+CREATE TABLE IF NOT EXISTS notifications (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    kind text NOT NULL,
+    -- what happened, e.g. "alice proposed an edit to Jeff Mills at Tresor"
+    summary text NOT NULL,
+    -- where the notification links to
+    path text NOT NULL,
+    read_at timestamp
+);
+
+CREATE INDEX IF NOT EXISTS notifications_user_id_created_at_idx ON notifications (user_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS notifications_unread_idx ON notifications (user_id)
+WHERE
+    read_at IS NULL;
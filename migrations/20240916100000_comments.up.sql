@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS comments (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    author_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- replies are one level deep, so the parent never has a parent itself
+    parent_id uuid REFERENCES comments (id) ON DELETE CASCADE,
+    -- the tracklist entry discussed, NULL for the whole set
+    track_number integer,
+    -- Markdown
+    body text NOT NULL,
+    -- deleted comments stay as placeholders while they have replies
+    deleted_at timestamp
+);
+
+CREATE INDEX IF NOT EXISTS comments_set_id_idx ON comments (set_id);
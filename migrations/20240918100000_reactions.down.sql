@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS attendances;
+DROP TABLE IF EXISTS set_ratings;
+DROP TABLE IF EXISTS set_likes;
@@ -0,0 +1,29 @@
+CREATE TABLE IF NOT EXISTS set_likes (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, set_id)
+);
+
+CREATE INDEX IF NOT EXISTS set_likes_set_id_idx ON set_likes (set_id);
+
+CREATE TABLE IF NOT EXISTS set_ratings (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    rating smallint NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    PRIMARY KEY (user_id, set_id)
+);
+
+CREATE INDEX IF NOT EXISTS set_ratings_set_id_idx ON set_ratings (set_id);
+
+-- "I was there"
+CREATE TABLE IF NOT EXISTS attendances (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, set_id)
+);
+
+CREATE INDEX IF NOT EXISTS attendances_set_id_idx ON attendances (set_id);
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS collections (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    owner_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    name text NOT NULL,
+    description text NOT NULL DEFAULT '',
+    -- private collections are only shown to their owner
+    is_public boolean NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX IF NOT EXISTS collections_owner_id_idx ON collections (owner_id);
+
+CREATE TABLE IF NOT EXISTS collection_sets (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    collection_id uuid NOT NULL REFERENCES collections (id) ON DELETE CASCADE,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    -- order of the set in the collection, from 0
+    position integer NOT NULL,
+    PRIMARY KEY (collection_id, set_id)
+);
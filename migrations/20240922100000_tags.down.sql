@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS set_tags;
+DROP TABLE IF EXISTS tags;
+ALTER TABLE users DROP COLUMN IF EXISTS is_moderator;
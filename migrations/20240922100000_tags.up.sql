@@ -0,0 +1,31 @@
+-- Moderators curate shared data such as tags. There is no UI to appoint
+-- them, set the flag in the database.
+ALTER TABLE users ADD COLUMN IF NOT EXISTS is_moderator boolean NOT NULL DEFAULT false;
+
+CREATE TABLE IF NOT EXISTS tags (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    slug text NOT NULL UNIQUE,
+    name text NOT NULL,
+    -- curated genres are suggested first
+    is_genre boolean NOT NULL DEFAULT false,
+    -- a synonym of another tag, e.g. "dnb" of "drum & bass"
+    merged_into_id uuid REFERENCES tags (id) ON DELETE SET NULL
+);
+
+CREATE TABLE IF NOT EXISTS set_tags (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    tag_id uuid NOT NULL REFERENCES tags (id) ON DELETE CASCADE,
+    PRIMARY KEY (set_id, tag_id)
+);
+
+CREATE INDEX IF NOT EXISTS set_tags_tag_id_idx ON set_tags (tag_id);
+
+INSERT INTO tags (id, slug, name, is_genre)
+SELECT gen_random_uuid(), slugify(name), name, true
+FROM unnest(ARRAY[
+    'Ambient', 'Breaks', 'Disco', 'Drum & Bass', 'Dubstep', 'Electro',
+    'Garage', 'Hardcore', 'House', 'Jungle', 'Minimal', 'Techno', 'Trance'
+]) AS name
+ON CONFLICT (slug) DO NOTHING;
@@ -0,0 +1,9 @@
+-- Links to recordings of a set on SoundCloud, YouTube, Mixcloud or as a file.
+CREATE TABLE IF NOT EXISTS recordings (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    url text NOT NULL,
+    provider text NOT NULL,
+    UNIQUE (set_id, url)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE recordings DROP COLUMN IF EXISTS upload_id;
+DROP TABLE IF EXISTS uploads;
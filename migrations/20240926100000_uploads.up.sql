@@ -0,0 +1,14 @@
+-- Files users upload, such as recordings. The content is kept in storage under
+-- the id, and its size counts towards the quota of the uploader.
+CREATE TABLE IF NOT EXISTS uploads (
+    created_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    id uuid PRIMARY KEY,
+    user_id uuid NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    content_type text NOT NULL,
+    size bigint NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS uploads_user_id_idx ON uploads (user_id);
+
+ALTER TABLE recordings
+    ADD COLUMN IF NOT EXISTS upload_id uuid REFERENCES uploads (id) ON DELETE CASCADE;
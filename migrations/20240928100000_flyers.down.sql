@@ -0,0 +1,2 @@
+ALTER TABLE sets DROP COLUMN IF EXISTS flyer_upload_id;
+ALTER TABLE uploads DROP COLUMN IF EXISTS variants;
@@ -0,0 +1,7 @@
+-- Files derived from an upload, such as resized images or waveform peaks, kept
+-- in storage next to it.
+ALTER TABLE uploads ADD COLUMN IF NOT EXISTS variants text[] NOT NULL DEFAULT '{}';
+
+-- An event flyer or cover image per set.
+ALTER TABLE sets
+    ADD COLUMN IF NOT EXISTS flyer_upload_id uuid REFERENCES uploads (id) ON DELETE SET NULL;
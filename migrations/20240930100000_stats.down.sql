@@ -0,0 +1,7 @@
+DROP MATERIALIZED VIEW IF EXISTS stats_summaries;
+DROP MATERIALIZED VIEW IF EXISTS stats_months;
+DROP MATERIALIZED VIEW IF EXISTS stats_top_venues;
+DROP MATERIALIZED VIEW IF EXISTS stats_top_djs;
+DROP MATERIALIZED VIEW IF EXISTS stats_top_artists;
+DROP MATERIALIZED VIEW IF EXISTS stats_top_tracks;
+DROP VIEW IF EXISTS stats_windows;
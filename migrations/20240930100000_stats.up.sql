@@ -0,0 +1,103 @@
+-- Aggregates shown under /stats, refreshed periodically by `StatsWorker`
+-- rather than computed on each page load. Each is computed for every window
+-- of `stats_windows`, counting sets by when they were played.
+CREATE OR REPLACE VIEW stats_windows (name, since) AS
+VALUES
+    ('month', CURRENT_TIMESTAMP::timestamp - interval '30 days'),
+    ('year', CURRENT_TIMESTAMP::timestamp - interval '1 year'),
+    ('all', '-infinity'::timestamp);
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_top_tracks AS
+SELECT
+    stats_windows.name AS window_name,
+    lower(btrim(track_artist)) AS artist_key,
+    lower(btrim(track_title)) AS title_key,
+    min(btrim(track_artist)) AS artist,
+    min(btrim(track_title)) AS title,
+    count(*) AS plays,
+    count(DISTINCT sets.id) AS sets
+FROM stats_windows
+JOIN sets ON sets.start_time >= stats_windows.since
+    AND sets.start_time <= CURRENT_TIMESTAMP
+JOIN setlist_songs ON setlist_songs.setlist_id = sets.id
+-- unidentified tracks, see `songs::Model::is_identified`
+WHERE upper(btrim(track_artist)) NOT IN ('', 'ID') AND upper(btrim(track_title)) NOT IN ('', 'ID')
+GROUP BY 1, 2, 3;
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_top_tracks_key
+    ON stats_top_tracks (window_name, artist_key, title_key);
+CREATE INDEX IF NOT EXISTS stats_top_tracks_plays_idx
+    ON stats_top_tracks (window_name, plays DESC);
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_top_artists AS
+SELECT
+    stats_windows.name AS window_name,
+    lower(btrim(track_artist)) AS artist_key,
+    min(btrim(track_artist)) AS artist,
+    count(*) AS plays,
+    count(DISTINCT sets.id) AS sets
+FROM stats_windows
+JOIN sets ON sets.start_time >= stats_windows.since
+    AND sets.start_time <= CURRENT_TIMESTAMP
+JOIN setlist_songs ON setlist_songs.setlist_id = sets.id
+WHERE upper(btrim(track_artist)) NOT IN ('', 'ID')
+GROUP BY 1, 2;
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_top_artists_key
+    ON stats_top_artists (window_name, artist_key);
+CREATE INDEX IF NOT EXISTS stats_top_artists_plays_idx
+    ON stats_top_artists (window_name, plays DESC);
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_top_djs AS
+SELECT
+    stats_windows.name AS window_name,
+    slugify(dj_name) AS slug,
+    min(dj_name) AS name,
+    count(*) AS sets
+FROM stats_windows
+JOIN sets ON sets.start_time >= stats_windows.since
+    AND sets.start_time <= CURRENT_TIMESTAMP
+CROSS JOIN UNNEST(sets.dj_names) AS dj_name
+WHERE slugify(dj_name) <> ''
+GROUP BY 1, 2;
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_top_djs_key ON stats_top_djs (window_name, slug);
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_top_venues AS
+SELECT
+    stats_windows.name AS window_name,
+    slugify(venue) AS slug,
+    min(venue) AS name,
+    min(city) AS city,
+    count(*) AS sets
+FROM stats_windows
+JOIN sets ON sets.start_time >= stats_windows.since
+    AND sets.start_time <= CURRENT_TIMESTAMP
+WHERE slugify(venue) <> ''
+GROUP BY 1, 2;
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_top_venues_key ON stats_top_venues (window_name, slug);
+
+-- sets and their average length by the month they were played in, of all time
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_months AS
+SELECT
+    date_trunc('month', start_time)::date AS month,
+    count(*) AS sets,
+    avg(duration_seconds)::double precision AS average_duration_seconds
+FROM sets
+WHERE start_time <= CURRENT_TIMESTAMP
+GROUP BY 1;
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_months_key ON stats_months (month);
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS stats_summaries AS
+SELECT
+    stats_windows.name AS window_name,
+    count(sets.id) AS sets,
+    avg(sets.duration_seconds)::double precision AS average_duration_seconds
+FROM stats_windows
+LEFT JOIN sets ON sets.start_time >= stats_windows.since
+    AND sets.start_time <= CURRENT_TIMESTAMP
+GROUP BY 1;
+
+CREATE UNIQUE INDEX IF NOT EXISTS stats_summaries_key ON stats_summaries (window_name);
@@ -0,0 +1,2 @@
+DROP MATERIALIZED VIEW IF EXISTS track_transitions;
+DROP INDEX IF EXISTS setlist_songs_track_slugs_idx;
@@ -0,0 +1,61 @@
+-- Tracks are not stored on their own, they are told apart by the slugs of
+-- their artist and title, see `utils::slugify`.
+CREATE INDEX IF NOT EXISTS setlist_songs_track_slugs_idx
+    ON setlist_songs (slugify(track_artist), slugify(track_title));
+
+-- How often one identified track was played right after another, refreshed
+-- by `StatsWorker` along with the statistics.
+CREATE MATERIALIZED VIEW IF NOT EXISTS track_transitions AS
+WITH plays AS (
+    SELECT
+        setlist_id,
+        track_number,
+        slugify(track_artist) AS artist_slug,
+        slugify(track_title) AS title_slug,
+        btrim(track_artist) AS artist,
+        btrim(track_title) AS title,
+        -- see `songs::Model::is_identified`
+        upper(btrim(track_artist)) NOT IN ('', 'ID')
+            AND upper(btrim(track_title)) NOT IN ('', 'ID')
+            AND slugify(track_artist) <> ''
+            AND slugify(track_title) <> '' AS identified
+    FROM setlist_songs
+    WHERE track_number IS NOT NULL
+),
+pairs AS (
+    SELECT
+        setlist_id,
+        artist_slug,
+        title_slug,
+        artist,
+        title,
+        identified,
+        lead(artist_slug) OVER tracklist AS next_artist_slug,
+        lead(title_slug) OVER tracklist AS next_title_slug,
+        lead(artist) OVER tracklist AS next_artist,
+        lead(title) OVER tracklist AS next_title,
+        lead(identified) OVER tracklist AS next_identified
+    FROM plays
+    WINDOW tracklist AS (PARTITION BY setlist_id ORDER BY track_number)
+)
+SELECT
+    artist_slug AS from_artist_slug,
+    title_slug AS from_title_slug,
+    next_artist_slug AS to_artist_slug,
+    next_title_slug AS to_title_slug,
+    min(artist) AS from_artist,
+    min(title) AS from_title,
+    min(next_artist) AS to_artist,
+    min(next_title) AS to_title,
+    count(*) AS transitions,
+    count(DISTINCT setlist_id) AS sets
+FROM pairs
+WHERE identified AND next_identified
+    AND (artist_slug, title_slug) <> (next_artist_slug, next_title_slug)
+GROUP BY 1, 2, 3, 4;
+
+CREATE UNIQUE INDEX IF NOT EXISTS track_transitions_key
+    ON track_transitions (from_artist_slug, from_title_slug, to_artist_slug, to_title_slug);
+CREATE INDEX IF NOT EXISTS track_transitions_to_idx
+    ON track_transitions (to_artist_slug, to_title_slug);
+CREATE INDEX IF NOT EXISTS track_transitions_count_idx ON track_transitions (transitions DESC);
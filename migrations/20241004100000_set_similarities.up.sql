@@ -0,0 +1,19 @@
+-- How alike two sets are, from the tracks, DJs and tags they share. Kept up
+-- to date by `SimilarityWorker` whenever a set changes, with a row for each
+-- direction so the sets like one are found by `set_id` alone.
+CREATE TABLE IF NOT EXISTS set_similarities (
+    computed_at timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    similar_set_id uuid NOT NULL REFERENCES sets (id) ON DELETE CASCADE,
+    -- from 0 to 1, see `set_similarities::score`
+    score double precision NOT NULL,
+    shared_tracks bigint NOT NULL,
+    shared_djs bigint NOT NULL,
+    shared_tags bigint NOT NULL,
+    PRIMARY KEY (set_id, similar_set_id)
+);
+
+CREATE INDEX IF NOT EXISTS set_similarities_score_idx
+    ON set_similarities (set_id, score DESC);
+CREATE INDEX IF NOT EXISTS set_similarities_similar_set_id_idx
+    ON set_similarities (similar_set_id);
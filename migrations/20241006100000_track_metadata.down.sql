@@ -0,0 +1,6 @@
+ALTER TABLE setlist_songs
+    DROP COLUMN IF EXISTS track_bpm,
+    DROP COLUMN IF EXISTS track_key,
+    DROP COLUMN IF EXISTS track_label,
+    DROP COLUMN IF EXISTS track_release_year,
+    DROP COLUMN IF EXISTS track_remixer;
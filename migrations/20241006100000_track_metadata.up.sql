@@ -0,0 +1,8 @@
+-- What DJs note about the tracks they play. Keys are stored in the usual
+-- notation, see `musical_keys::MusicalKey`.
+ALTER TABLE setlist_songs
+    ADD COLUMN IF NOT EXISTS track_bpm double precision,
+    ADD COLUMN IF NOT EXISTS track_key text,
+    ADD COLUMN IF NOT EXISTS track_label text,
+    ADD COLUMN IF NOT EXISTS track_release_year integer,
+    ADD COLUMN IF NOT EXISTS track_remixer text;
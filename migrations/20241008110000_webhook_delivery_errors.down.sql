@@ -0,0 +1,2 @@
+-- The response bodies are gone for good.
+SELECT 1;
@@ -0,0 +1,4 @@
+-- Deliveries no longer keep what receivers responded with, only the status.
+UPDATE webhook_deliveries
+SET error = 'The receiver responded with HTTP ' || response_status
+WHERE response_status IS NOT NULL AND error IS NOT NULL;
@@ -0,0 +1 @@
+DROP INDEX IF EXISTS sets_event_name_slug_idx;
@@ -0,0 +1,2 @@
+-- event pages look sets up by the slug of their event name
+CREATE INDEX IF NOT EXISTS sets_event_name_slug_idx ON sets (slugify(event_name));
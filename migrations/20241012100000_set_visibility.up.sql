@@ -0,0 +1,4 @@
+-- unlisted sets are only found through their link, private sets only by
+-- their creator
+ALTER TABLE sets ADD COLUMN IF NOT EXISTS visibility text NOT NULL DEFAULT 'public'
+    CHECK (visibility IN ('public', 'unlisted', 'private'));
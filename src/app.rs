@@ -1,17 +1,21 @@
+use std::collections::BTreeMap;
+
 use async_trait::async_trait;
 use axum::{
     extract::OriginalUri,
     http::{Method, StatusCode},
     Router,
 };
+use eyre::Report;
 use loco_rs::{
     app::{AppContext, Hooks, Initializer},
     boot::{create_app, BootResult, StartMode},
     cache,
-    controller::AppRoutes,
+    controller::{openapi, AppRoutes},
     environment::Environment,
     prelude::*,
-    worker::Processor,
+    storage::{self, strategies::single::SingleStrategy, Storage},
+    worker::{periodic, Processor},
     Result,
 };
 use sqlx::PgPool;
@@ -20,8 +24,17 @@ use crate::{
     controllers,
     initializers::{self, minijinja_view_engine::MiniJinjaView},
     models::users,
+    settings::Settings,
     utils::get_username,
     views,
+    workers::{
+        digest::{self, DigestWorker, DigestWorkerArgs},
+        images::ImageWorker,
+        similarity::{self, SimilarityWorker, SimilarityWorkerArgs},
+        stats::{self, StatsWorker, StatsWorkerArgs},
+        waveform::WaveformWorker,
+        webhook::WebhookWorker,
+    },
 };
 
 pub struct App;
@@ -58,9 +71,27 @@ impl Hooks for App {
     fn routes(_ctx: &AppContext) -> AppRoutes {
         AppRoutes::with_default_routes()
             .add_route(controllers::sets::routes())
+            .add_route(controllers::comments::routes())
+            .add_route(controllers::reactions::routes())
+            .add_route(controllers::recordings::routes())
+            .add_route(controllers::uploads::routes())
+            .add_route(controllers::flyers::routes())
+            .add_route(controllers::collections::routes())
+            .add_route(controllers::tags::routes())
             .add_route(controllers::auth::routes())
             .add_route(controllers::index::routes())
             .add_route(controllers::user::routes())
+            .add_route(controllers::settings::routes())
+            .add_route(controllers::feeds::routes())
+            .add_route(controllers::embeds::routes())
+            .add_route(controllers::sitemaps::routes())
+            .add_route(controllers::stats::routes())
+            .add_route(controllers::tracks::routes())
+            .add_route(controllers::follows::routes())
+            .add_route(controllers::notifications::routes())
+            .add_route(controllers::unsubscribe::routes())
+            .add_routes(controllers::api::routes())
+            .add_route(openapi::routes::<Self>())
     }
 
     async fn before_routes(_ctx: &AppContext) -> Result<Router<AppContext>> {
@@ -84,14 +115,51 @@ impl Hooks for App {
         Ok(router)
     }
 
-    fn connect_workers<'a>(_p: &'a mut Processor, _ctx: &'a AppContext) {
-        // p.register(DownloadWorker::build(ctx));
+    fn connect_workers<'a>(p: &'a mut Processor, ctx: &'a AppContext) {
+        p.register(WebhookWorker::build(ctx));
+        p.register(WaveformWorker::build(ctx));
+        p.register(ImageWorker::build(ctx));
+        p.register(SimilarityWorker::build(ctx));
+    }
+
+    async fn schedule_workers(p: &mut Processor, ctx: &AppContext) -> Result<()> {
+        periodic::builder(digest::SCHEDULE)
+            .and_then(|builder| builder.name("weekly digest").args(DigestWorkerArgs {}))
+            .map_err(Report::new)?
+            .register(p, DigestWorker::build(ctx))
+            .await
+            .map_err(Report::new)?;
+        periodic::builder(stats::SCHEDULE)
+            .and_then(|builder| builder.name("statistics").args(StatsWorkerArgs {}))
+            .map_err(Report::new)?
+            .register(p, StatsWorker::build(ctx))
+            .await
+            .map_err(Report::new)?;
+        periodic::builder(similarity::SCHEDULE)
+            .and_then(|builder| {
+                builder
+                    .name("set similarities")
+                    .args(SimilarityWorkerArgs { set_id: None })
+            })
+            .map_err(Report::new)?
+            .register(p, SimilarityWorker::build(ctx))
+            .await
+            .map_err(Report::new)?;
+        Ok(())
     }
 
     async fn after_context(ctx: AppContext) -> Result<AppContext> {
+        let uploads = Settings::from_config(&ctx.config)?.uploads;
+        tokio::fs::create_dir_all(&uploads.path).await?;
+        let stores = BTreeMap::from([(
+            controllers::uploads::STORE.to_string(),
+            storage::drivers::local::new_with_prefix(&uploads.path)?,
+        )]);
+        let strategy = SingleStrategy::new(controllers::uploads::STORE);
         Ok(AppContext {
             // TODO switch to redis?
             cache: cache::Cache::new(cache::drivers::inmem::new()).into(),
+            storage: Storage::new(stores, Box::new(strategy)).into(),
             ..ctx
         })
     }
@@ -0,0 +1,56 @@
+use axum::debug_handler;
+use loco_rs::{controller::openapi::Operation, prelude::*};
+
+use super::PageQuery;
+use crate::{
+    models::{api_keys::scopes, djs, sets, users},
+    views::{self, api::Page},
+};
+
+#[debug_handler]
+async fn list(
+    _auth: auth::ApiToken<users::Model, scopes::Read>,
+    page: PageQuery,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let items = djs::Model::list_page(&ctx.db, page.limit(), page.offset()).await?;
+    let total = djs::Model::count(&ctx.db).await?;
+    views::api::page(items, page.pagination(total))
+}
+
+#[debug_handler]
+async fn sets(
+    _auth: auth::ApiToken<users::Model, scopes::Read>,
+    Path(name): Path<String>,
+    page: PageQuery,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let total = sets::Model::count_by_dj_name(&ctx.db, &name).await?;
+    if total == 0 {
+        return not_found();
+    }
+    let items =
+        sets::Model::list_page_by_dj_name(&ctx.db, &name, page.limit(), page.offset()).await?;
+    views::api::page(items, page.pagination(total))
+}
+
+fn doc(summary: &str) -> Operation {
+    Operation::new(summary).tag("djs").bearer_auth()
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/v1/djs")
+        .add("/", get(list))
+        .doc(
+            doc("List DJs")
+                .query::<PageQuery>()
+                .response::<Page<djs::Model>>(),
+        )
+        .add("/:name/sets", get(sets))
+        .doc(
+            doc("List the sets played by a DJ")
+                .query::<PageQuery>()
+                .response::<Page<sets::Model>>(),
+        )
+}
@@ -0,0 +1,113 @@
+use axum::{debug_handler, http::StatusCode};
+use loco_rs::{
+    controller::{bad_request, openapi::Operation},
+    prelude::*,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        api_keys::{self, scopes, ApiScope},
+        users,
+    },
+    utils::model_error,
+    views::{
+        self,
+        api::{ApiKeyResponse, NewApiKeyResponse},
+    },
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(rename = "CreateApiKey")]
+pub struct CreateParams {
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    /// Days until the key expires, keys without one never do
+    #[schemars(range(min = 1, max = 3650))]
+    pub expires_in_days: Option<i64>,
+}
+
+#[debug_handler]
+async fn list(
+    auth: auth::ApiToken<users::Model, scopes::Admin>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let items = api_keys::Model::list_by_user_id(&ctx.db, auth.user.id).await?;
+    views::api::api_keys(items)
+}
+
+#[debug_handler]
+async fn add(
+    auth: auth::ApiToken<users::Model, scopes::Admin>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreateParams>,
+) -> Result<Response> {
+    let expires_at = match params.expires_in_days {
+        Some(days) => match api_keys::expires_in_days(days) {
+            Some(expires_at) => Some(expires_at),
+            None => {
+                return bad_request(format!(
+                    "`expires_in_days` must be between 1 and {}",
+                    api_keys::MAX_LIFETIME_DAYS
+                ))
+            }
+        },
+        None => None,
+    };
+    let (item, key) = api_keys::Model::create(
+        &ctx.db,
+        auth.user.id,
+        &params.name,
+        &params.scopes,
+        expires_at,
+    )
+    .await
+    .map_err(model_error)?;
+    views::api::new_api_key(item, key, StatusCode::CREATED)
+}
+
+#[debug_handler]
+async fn rotate(
+    auth: auth::ApiToken<users::Model, scopes::Admin>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = api_keys::Model::find_by_id_and_user_id(&ctx.db, id, auth.user.id).await?;
+    let (item, key) = item.rotate(&ctx.db).await?;
+    views::api::new_api_key(item, key, StatusCode::OK)
+}
+
+#[debug_handler]
+async fn remove(
+    auth: auth::ApiToken<users::Model, scopes::Admin>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = api_keys::Model::find_by_id_and_user_id(&ctx.db, id, auth.user.id).await?;
+    item.revoke(&ctx.db).await?;
+    format::empty()
+}
+
+fn doc(summary: &str) -> Operation {
+    Operation::new(summary).tag("keys").bearer_auth()
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/v1/keys")
+        .add("/", get(list))
+        .doc(doc("List your API keys").response::<Vec<ApiKeyResponse>>())
+        .add("/", post(add))
+        .doc(
+            doc("Create an API key")
+                .request::<CreateParams>()
+                .response::<NewApiKeyResponse>()
+                .status(StatusCode::CREATED),
+        )
+        .add("/:id/rotate", post(rotate))
+        .doc(doc("Replace an API key by a new one").response::<NewApiKeyResponse>())
+        .add("/:id", delete(remove))
+        .doc(doc("Revoke an API key"))
+}
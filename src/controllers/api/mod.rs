@@ -0,0 +1,100 @@
+//! Versioned JSON API, authenticated with `Authorization: Bearer <api_key>`.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::request::Parts,
+};
+use loco_rs::prelude::*;
+use schemars::JsonSchema;
+
+use crate::views::api::Pagination;
+
+pub mod djs;
+pub mod keys;
+pub mod sets;
+pub mod transitions;
+pub mod users;
+pub mod webhooks;
+
+const DEFAULT_PER_PAGE: i64 = 25;
+const MAX_PER_PAGE: i64 = 100;
+
+/// `?page=&per_page=` query parameters of paginated endpoints. Pages start at
+/// 1 and `per_page` is capped at [`MAX_PER_PAGE`].
+#[derive(Debug, Clone, Copy, JsonSchema)]
+pub struct PageQuery {
+    /// Page number, starting at 1
+    #[schemars(range(min = 1), default = "default_page")]
+    pub page: i64,
+    /// Number of items per page, at most 100
+    #[schemars(range(min = 1, max = 100), default = "default_per_page")]
+    pub per_page: i64,
+}
+
+const fn default_page() -> i64 {
+    1
+}
+
+const fn default_per_page() -> i64 {
+    DEFAULT_PER_PAGE
+}
+
+impl PageQuery {
+    #[must_use]
+    pub const fn limit(&self) -> i64 {
+        self.per_page
+    }
+
+    /// Saturates for pages far past the last, which are simply empty.
+    #[must_use]
+    pub const fn offset(&self) -> i64 {
+        (self.page - 1).saturating_mul(self.per_page)
+    }
+
+    #[must_use]
+    pub const fn pagination(&self, total_items: i64) -> Pagination {
+        Pagination::new(self.page, self.per_page, total_items)
+    }
+}
+
+fn parse_page_param(params: &HashMap<String, String>, name: &str, default: i64) -> Result<i64> {
+    params.get(name).map_or(Ok(default), |value| {
+        value
+            .parse::<i64>()
+            .ok()
+            .filter(|value| *value >= 1)
+            .ok_or_else(|| Error::BadRequest(format!("`{name}` must be a positive integer")))
+    })
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for PageQuery
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self> {
+        let Query(params) = Query::<HashMap<String, String>>::try_from_uri(&parts.uri)
+            .map_err(|err| Error::BadRequest(err.body_text()))?;
+
+        Ok(Self {
+            page: parse_page_param(&params, "page", default_page())?,
+            per_page: parse_page_param(&params, "per_page", default_per_page())?.min(MAX_PER_PAGE),
+        })
+    }
+}
+
+pub fn routes() -> Vec<Routes> {
+    vec![
+        sets::routes(),
+        djs::routes(),
+        users::routes(),
+        transitions::routes(),
+        keys::routes(),
+        webhooks::routes(),
+    ]
+}
@@ -0,0 +1,159 @@
+use axum::{debug_handler, http::StatusCode};
+use loco_rs::{
+    controller::{bad_request, openapi::Operation},
+    prelude::*,
+};
+use uuid::Uuid;
+
+use super::PageQuery;
+use crate::{
+    controllers::sets::{load_owned_item, load_visible_item, Params, Song},
+    models::{api_keys::scopes, sets, songs, users},
+    services,
+    views::{
+        self,
+        api::{Page, SetResponse},
+    },
+};
+
+fn validate(params: &Params) -> Result<()> {
+    if params.dj_names.iter().all(|name| name.trim().is_empty()) {
+        return bad_request("`dj_names` must contain at least one name");
+    }
+    params.validate_tags()
+}
+
+#[debug_handler]
+async fn list(
+    _auth: auth::ApiToken<users::Model, scopes::Read>,
+    page: PageQuery,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let items = sets::Model::list_page(&ctx.db, page.limit(), page.offset()).await?;
+    let total = sets::Model::count(&ctx.db).await?;
+    views::api::page(items, page.pagination(total))
+}
+
+#[debug_handler]
+async fn show(
+    auth: auth::ApiToken<users::Model, scopes::Read>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&auth.user)).await?;
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, id).await?;
+    views::api::set(item, tracklist)
+}
+
+#[debug_handler]
+async fn add(
+    auth: auth::ApiToken<users::Model, scopes::WriteSets>,
+    State(ctx): State<AppContext>,
+    Json(mut params): Json<Params>,
+) -> Result<Response> {
+    validate(&params)?;
+    let mut item = sets::Model {
+        id: Uuid::now_v7(),
+        creator_id: auth.user.id,
+        ..Default::default()
+    };
+    let tags = params.tags.take();
+    let tracklist = params.update(&mut item);
+    let (item, tracklist) =
+        services::sets::create(&ctx, &item, &tracklist, tags.as_deref()).await?;
+    views::api::created_set(item, tracklist)
+}
+
+#[debug_handler]
+async fn update(
+    auth: auth::ApiToken<users::Model, scopes::WriteSets>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+    Json(mut params): Json<Params>,
+) -> Result<Response> {
+    validate(&params)?;
+    let mut item = load_owned_item(&ctx, id, &auth.user).await?;
+    let tags = params.tags.take();
+    let tracklist = params.update(&mut item);
+    let (item, tracklist) =
+        services::sets::update(&ctx, &item, &tracklist, tags.as_deref()).await?;
+    views::api::set(item, tracklist)
+}
+
+#[debug_handler]
+async fn remove(
+    auth: auth::ApiToken<users::Model, scopes::WriteSets>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_owned_item(&ctx, id, &auth.user).await?;
+    services::sets::delete(&ctx, &item).await?;
+    format::empty()
+}
+
+#[debug_handler]
+async fn tracklist(
+    auth: auth::ApiToken<users::Model, scopes::Read>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&auth.user)).await?;
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    views::api::tracklist(tracklist)
+}
+
+#[debug_handler]
+async fn replace_tracklist(
+    auth: auth::ApiToken<users::Model, scopes::WriteSets>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+    Json(setlist): Json<Vec<Song>>,
+) -> Result<Response> {
+    let item = load_owned_item(&ctx, id, &auth.user).await?;
+    let tracklist = setlist
+        .into_iter()
+        .map(|song| songs::Model::from_song_and_setlist_id(song, item.id))
+        .collect::<Vec<_>>();
+    let tracklist = services::sets::replace_tracklist(&ctx, &item, &tracklist).await?;
+    views::api::tracklist(tracklist)
+}
+
+fn doc(summary: &str) -> Operation {
+    Operation::new(summary).tag("sets").bearer_auth()
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/v1/sets")
+        .add("/", get(list))
+        .doc(
+            doc("List sets")
+                .query::<PageQuery>()
+                .response::<Page<sets::Model>>(),
+        )
+        .add("/", post(add))
+        .doc(
+            doc("Create a set")
+                .request::<Params>()
+                .response::<SetResponse>()
+                .status(StatusCode::CREATED),
+        )
+        .add("/:id", get(show))
+        .doc(doc("Get a set with its tracklist").response::<SetResponse>())
+        .add("/:id", put(update))
+        .doc(
+            doc("Update a set")
+                .request::<Params>()
+                .response::<SetResponse>(),
+        )
+        .add("/:id", delete(remove))
+        .doc(doc("Delete a set"))
+        .add("/:id/tracklist", get(tracklist))
+        .doc(doc("Get the tracklist of a set").response::<Vec<Song>>())
+        .add("/:id/tracklist", put(replace_tracklist))
+        .doc(
+            doc("Replace the tracklist of a set")
+                .request::<Vec<Song>>()
+                .response::<Vec<Song>>(),
+        )
+}
@@ -0,0 +1,59 @@
+use axum::debug_handler;
+use loco_rs::{controller::openapi::Operation, prelude::*};
+
+use super::PageQuery;
+use crate::{
+    models::{api_keys::scopes, transitions, users},
+    views::{
+        self,
+        api::{Page, TrackTransitions},
+    },
+};
+
+/// How many tracks played before and after a track are listed.
+const NEIGHBOURS: i64 = 25;
+
+/// The whole transition graph, one edge per pair of tracks played one right
+/// after the other.
+#[debug_handler]
+async fn list(
+    _auth: auth::ApiToken<users::Model, scopes::Read>,
+    page: PageQuery,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let items = transitions::list_page(&ctx.db, page.limit(), page.offset()).await?;
+    let total = transitions::count(&ctx.db).await?;
+    views::api::page(items, page.pagination(total))
+}
+
+#[debug_handler]
+async fn track(
+    _auth: auth::ApiToken<users::Model, scopes::Read>,
+    Path((artist, title)): Path<(String, String)>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let track = transitions::find_track(&ctx.db, &artist, &title).await?;
+    let before = transitions::list_before(&ctx.db, &artist, &title, NEIGHBOURS).await?;
+    let after = transitions::list_after(&ctx.db, &artist, &title, NEIGHBOURS).await?;
+    views::api::track_transitions(track, before, after)
+}
+
+fn doc(summary: &str) -> Operation {
+    Operation::new(summary).tag("transitions").bearer_auth()
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/v1/transitions")
+        .add("/", get(list))
+        .doc(
+            doc("List transitions between tracks, the most common first")
+                .query::<PageQuery>()
+                .response::<Page<transitions::Transition>>(),
+        )
+        .add("/:artist_slug/:title_slug", get(track))
+        .doc(
+            doc("Get the tracks most often played before and after a track")
+                .response::<TrackTransitions>(),
+        )
+}
@@ -0,0 +1,59 @@
+use axum::debug_handler;
+use loco_rs::{controller::openapi::Operation, prelude::*};
+
+use super::PageQuery;
+use crate::{
+    models::{api_keys::scopes, sets, users},
+    views::{
+        self,
+        api::{Page, UserResponse},
+    },
+};
+
+#[debug_handler(state = AppContext)]
+async fn me(auth: auth::ApiToken<users::Model, scopes::Read>) -> Result<Response> {
+    views::api::user(auth.user)
+}
+
+#[debug_handler]
+async fn show(
+    _auth: auth::ApiToken<users::Model, scopes::Read>,
+    Path(username): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = users::Model::find_by_username(&ctx.db, &username).await?;
+    views::api::user(user)
+}
+
+#[debug_handler]
+async fn sets(
+    _auth: auth::ApiToken<users::Model, scopes::Read>,
+    Path(username): Path<String>,
+    page: PageQuery,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = users::Model::find_by_username(&ctx.db, &username).await?;
+    let items =
+        sets::Model::list_page_by_creator_id(&ctx.db, user.id, page.limit(), page.offset()).await?;
+    let total = sets::Model::count_by_creator_id(&ctx.db, user.id).await?;
+    views::api::page(items, page.pagination(total))
+}
+
+fn doc(summary: &str) -> Operation {
+    Operation::new(summary).tag("users").bearer_auth()
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/v1/users")
+        .add("/me", get(me))
+        .doc(doc("Get the authenticated user").response::<UserResponse>())
+        .add("/:username", get(show))
+        .doc(doc("Get a user").response::<UserResponse>())
+        .add("/:username/sets", get(sets))
+        .doc(
+            doc("List the sets created by a user")
+                .query::<PageQuery>()
+                .response::<Page<sets::Model>>(),
+        )
+}
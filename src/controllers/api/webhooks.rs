@@ -0,0 +1,87 @@
+use axum::{debug_handler, http::StatusCode};
+use loco_rs::{controller::openapi::Operation, prelude::*};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        api_keys::scopes,
+        users,
+        webhooks::{self, WebhookEvent},
+    },
+    settings::Settings,
+    utils::model_error,
+    views::{
+        self,
+        api::{NewWebhookResponse, WebhookResponse},
+    },
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[schemars(rename = "CreateWebhook")]
+pub struct CreateParams {
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+}
+
+#[debug_handler]
+async fn list(
+    auth: auth::ApiToken<users::Model, scopes::Admin>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let items = webhooks::Model::list_by_user_id(&ctx.db, auth.user.id).await?;
+    views::api::webhooks(items)
+}
+
+#[debug_handler]
+async fn add(
+    auth: auth::ApiToken<users::Model, scopes::Admin>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreateParams>,
+) -> Result<Response> {
+    let allow_private = Settings::from_config(&ctx.config)?
+        .webhooks
+        .allow_private_addresses;
+    let item = webhooks::Model::create(
+        &ctx.db,
+        auth.user.id,
+        &params.url,
+        &params.events,
+        allow_private,
+    )
+    .await
+    .map_err(model_error)?;
+    views::api::created_webhook(item)
+}
+
+#[debug_handler]
+async fn remove(
+    auth: auth::ApiToken<users::Model, scopes::Admin>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = webhooks::Model::find_by_id_and_user_id(&ctx.db, id, auth.user.id).await?;
+    item.delete(&ctx.db).await?;
+    format::empty()
+}
+
+fn doc(summary: &str) -> Operation {
+    Operation::new(summary).tag("webhooks").bearer_auth()
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("api/v1/webhooks")
+        .add("/", get(list))
+        .doc(doc("List your webhooks").response::<Vec<WebhookResponse>>())
+        .add("/", post(add))
+        .doc(
+            doc("Subscribe a webhook to set events")
+                .request::<CreateParams>()
+                .response::<NewWebhookResponse>()
+                .status(StatusCode::CREATED),
+        )
+        .add("/:id", delete(remove))
+        .doc(doc("Delete a webhook"))
+}
@@ -2,7 +2,8 @@ use std::time::SystemTime;
 
 use ::cookie::CookieBuilder;
 use axum::{debug_handler, extract::Query, http::uri::PathAndQuery};
-use loco_rs::prelude::*;
+use loco_rs::{controller::openapi::Operation, prelude::*};
+use schemars::JsonSchema;
 use secrecy::Secret;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -15,20 +16,21 @@ use crate::{
     views,
 };
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct VerifyParams {
     pub token: Uuid,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct ForgotParams {
     pub email: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct ResetParams {
     pub token: Uuid,
     #[serde(serialize_with = "loco_rs::utils::stars")]
+    #[schemars(with = "String")]
     pub password: Secret<String>,
 }
 
@@ -198,11 +200,17 @@ pub async fn registration_success_page(
 
 pub fn routes() -> Routes {
     Routes::new()
-        .add("/login", get(login_page).post(login))
+        .add("/login", get(login_page))
+        .add("/login", post(login))
+        .doc(Operation::new("Log in").request::<LoginParams>())
         .add("/register", post(register))
+        .doc(Operation::new("Register a new user").request::<RegisterParams>())
         .add("/register/success", get(registration_success_page))
         .add("/verify_email", get(verify))
+        .doc(Operation::new("Verify an email address").query::<VerifyParams>())
         .add("/forgot_password", post(forgot))
+        .doc(Operation::new("Send a password reset email").request::<ForgotParams>())
         .add("/reset_password", post(reset))
+        .doc(Operation::new("Reset a password").request::<ResetParams>())
         .add("/logout", post(logout))
 }
@@ -0,0 +1,217 @@
+use auth::JWTWithUser;
+use axum::{debug_handler, http::uri::PathAndQuery};
+use loco_rs::prelude::*;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    controllers::{sets::load_visible_item, sitemaps},
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{collections, users},
+    utils::{forbidden, hx_redirect, hx_redirect_to, model_error},
+    views,
+};
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    #[default]
+    Private,
+    Public,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub visibility: Visibility,
+}
+
+impl Params {
+    fn to_model(&self) -> collections::Params<'_> {
+        collections::Params {
+            name: &self.name,
+            description: &self.description,
+            is_public: matches!(self.visibility, Visibility::Public),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddSetParams {
+    pub collection_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MoveParams {
+    /// Places to move the set by, negative towards the start.
+    pub offset: i32,
+}
+
+fn redirect_to(item: &collections::Model) -> Result<Response> {
+    hx_redirect_to(format!("/collections/{}", item.id))
+}
+
+/// Finds one of the user's collections, refusing access to anyone else's.
+async fn load_own(
+    ctx: &AppContext,
+    user: &users::Model,
+    id: Uuid,
+) -> Result<Option<collections::Model>> {
+    let item = collections::Model::find_by_id(&ctx.db, id).await?;
+    Ok((item.owner_id == user.id).then_some(item))
+}
+
+#[debug_handler]
+async fn index(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let user = &jwt_user.user;
+    let items = collections::Model::list_by_owner_id(&ctx.db, user.id, true).await?;
+    views::collections::index(&v, &items, &user.username)
+}
+
+#[debug_handler]
+async fn add(
+    jwt_user: JWTWithUser<users::Model>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    let item = collections::Model::create(&ctx.db, jwt_user.user.id, &params.to_model())
+        .await
+        .map_err(model_error)?;
+    sitemaps::invalidate(&ctx).await;
+    redirect_to(&item)
+}
+
+/// Public collections are shown to everyone, private ones only to their
+/// owner.
+#[debug_handler]
+async fn show(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let own_user = jwt_user.as_ref().map(|jwt_user| &jwt_user.user);
+    let user_name = own_user
+        .map(|user| user.username.as_str())
+        .unwrap_or_default();
+    let user_id = own_user.map(|user| user.id);
+    let item = match collections::Model::find_by_id(&ctx.db, id).await {
+        Ok(item) if item.is_visible_to(user_id) => item,
+        _ => return views::index::not_found(&v, user_name),
+    };
+    let owner = users::Model::find_by_id(&ctx.db, item.owner_id).await?;
+    let sets = item.list_sets(&ctx.db).await?;
+    views::collections::show(
+        &v,
+        &item,
+        &owner.username,
+        &sets,
+        user_id == Some(item.owner_id),
+        user_name,
+    )
+}
+
+#[debug_handler]
+async fn update(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    let Some(mut item) = load_own(&ctx, &jwt_user.user, id).await? else {
+        return forbidden();
+    };
+    item.update(&ctx.db, &params.to_model())
+        .await
+        .map_err(model_error)?;
+    sitemaps::invalidate(&ctx).await;
+    redirect_to(&item)
+}
+
+#[debug_handler]
+async fn remove(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(item) = load_own(&ctx, &jwt_user.user, id).await? else {
+        return forbidden();
+    };
+    item.delete(&ctx.db).await?;
+    sitemaps::invalidate(&ctx).await;
+    hx_redirect(&PathAndQuery::from_static("/collections"))
+}
+
+/// Adds a set to one of the user's collections, from the page of the set.
+#[debug_handler]
+async fn add_set(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(set_id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<AddSetParams>,
+) -> Result<Response> {
+    let set = load_visible_item(&ctx, set_id, Some(&jwt_user.user)).await?;
+    let Some(mut item) = load_own(&ctx, &jwt_user.user, params.collection_id).await? else {
+        return forbidden();
+    };
+    item.add_set(&ctx.db, set.id).await?;
+    sitemaps::invalidate(&ctx).await;
+    let collections = collections::Model::list_by_owner_id(&ctx.db, jwt_user.user.id, true).await?;
+    views::collections::added(&v, &set, &collections, &item)
+}
+
+#[debug_handler]
+async fn remove_set(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, set_id)): Path<(Uuid, Uuid)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(mut item) = load_own(&ctx, &jwt_user.user, id).await? else {
+        return forbidden();
+    };
+    item.remove_set(&ctx.db, set_id).await?;
+    sitemaps::invalidate(&ctx).await;
+    let sets = item.list_sets(&ctx.db).await?;
+    views::collections::sets(&v, &item, &sets)
+}
+
+#[debug_handler]
+async fn move_set(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, set_id)): Path<(Uuid, Uuid)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<MoveParams>,
+) -> Result<Response> {
+    let Some(mut item) = load_own(&ctx, &jwt_user.user, id).await? else {
+        return forbidden();
+    };
+    item.move_set(&ctx.db, set_id, params.offset).await?;
+    sitemaps::invalidate(&ctx).await;
+    let sets = item.list_sets(&ctx.db).await?;
+    views::collections::sets(&v, &item, &sets)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .add("/collections", get(index))
+        .add("/collections", post(add))
+        .add("/collections/:id", get(show))
+        .add("/collections/:id", put(update))
+        .add("/collections/:id", delete(remove))
+        .add("/collections/:id/sets/:set_id", delete(remove_set))
+        .add("/collections/:id/sets/:set_id/move", post(move_set))
+        .add("/sets/:id/collections", post(add_set))
+}
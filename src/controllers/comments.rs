@@ -0,0 +1,208 @@
+use auth::JWTWithUser;
+use axum::debug_handler;
+use loco_rs::{controller::bad_request, prelude::*};
+use serde::{de, Deserialize, Deserializer};
+use uuid::Uuid;
+
+use crate::{
+    controllers::sets::load_visible_item,
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{
+        comments::{self, NewComment},
+        notifications::{self, NotificationKind},
+        sets, songs, users,
+    },
+    utils::{forbidden, model_error},
+    views::{self, feeds::set_title, sets::Viewer},
+};
+
+/// The track select sends the track number, or an empty string for comments
+/// on the whole set.
+fn track_number<'de, D>(d: D) -> Result<Option<i32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = Option::<String>::deserialize(d)?.unwrap_or_default();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse::<i32>()
+        .map(Some)
+        .map_err(|_| de::Error::custom("track number must be a number"))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateParams {
+    pub body: String,
+    #[serde(default, deserialize_with = "track_number")]
+    pub track_number: Option<i32>,
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateParams {
+    pub body: String,
+}
+
+async fn render_list(
+    v: &MiniJinjaView,
+    ctx: &AppContext,
+    item: &sets::Model,
+    user: Option<&users::Model>,
+) -> Result<Response> {
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    let comments = comments::Model::list_by_set_id(&ctx.db, item.id).await?;
+    let viewer = Viewer {
+        user_name: user.map(|user| user.username.clone()).unwrap_or_default(),
+        user_id: user.map(|user| user.id),
+        is_owner: user.is_some_and(|user| user.id == item.creator_id),
+        ..Default::default()
+    };
+    views::comments::list(v, item, &tracklist, &comments, &viewer)
+}
+
+/// Tells the owner of the set about a new comment, or the author of the
+/// parent comment about a reply.
+async fn notify(
+    ctx: &AppContext,
+    item: &sets::Model,
+    comment: &comments::Model,
+    parent: Option<&comments::Model>,
+) -> Result<()> {
+    let (user_id, kind, summary) = match parent {
+        Some(parent) => (
+            parent.author_id,
+            NotificationKind::Reply,
+            format!(
+                "{} replied to your comment on {}",
+                comment.author_name,
+                set_title(item)
+            ),
+        ),
+        None => (
+            item.creator_id,
+            NotificationKind::Comment,
+            format!("{} commented on {}", comment.author_name, set_title(item)),
+        ),
+    };
+    if user_id != comment.author_id {
+        let path = format!("/sets/{}#comment-{}", item.id, comment.id);
+        notifications::Model::create(&ctx.db, user_id, kind, &summary, &path).await?;
+    }
+    Ok(())
+}
+
+#[debug_handler]
+async fn list(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = jwt_user.as_ref().map(|jwt_user| &jwt_user.user);
+    let item = load_visible_item(&ctx, id, user).await?;
+    render_list(&v, &ctx, &item, user).await
+}
+
+#[debug_handler]
+async fn add(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreateParams>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    let parent = match params.parent_id {
+        Some(parent_id) => {
+            Some(comments::Model::find_by_id_and_set_id(&ctx.db, parent_id, item.id).await?)
+        }
+        None => None,
+    };
+    if let Some(track_number) = params.track_number {
+        let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+        if !tracklist
+            .iter()
+            .any(|song| song.track_number == Some(track_number))
+        {
+            return bad_request("This set has no such track");
+        }
+    }
+
+    let comment = comments::Model::create(
+        &ctx.db,
+        &NewComment {
+            set_id: item.id,
+            author_id: jwt_user.user.id,
+            parent: parent.as_ref(),
+            track_number: params.track_number,
+            body: &params.body,
+        },
+    )
+    .await
+    .map_err(model_error)?;
+    notify(&ctx, &item, &comment, parent.as_ref()).await?;
+    render_list(&v, &ctx, &item, Some(&jwt_user.user)).await
+}
+
+#[debug_handler]
+async fn edit(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let comment = comments::Model::find_by_id_and_set_id(&ctx.db, comment_id, id).await?;
+    if comment.author_id != jwt_user.user.id || comment.deleted_at.is_some() {
+        return forbidden();
+    }
+    views::comments::edit(&v, &comment)
+}
+
+#[debug_handler]
+async fn update(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<UpdateParams>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    let mut comment = comments::Model::find_by_id_and_set_id(&ctx.db, comment_id, item.id).await?;
+    if comment.author_id != jwt_user.user.id || comment.deleted_at.is_some() {
+        return forbidden();
+    }
+    comment
+        .update_body(&ctx.db, &params.body)
+        .await
+        .map_err(model_error)?;
+    render_list(&v, &ctx, &item, Some(&jwt_user.user)).await
+}
+
+/// Authors delete their comments, and owners of a set any comment on it.
+#[debug_handler]
+async fn remove(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, comment_id)): Path<(Uuid, Uuid)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    let mut comment = comments::Model::find_by_id_and_set_id(&ctx.db, comment_id, item.id).await?;
+    let user_id = jwt_user.user.id;
+    if comment.author_id != user_id && item.creator_id != user_id {
+        return forbidden();
+    }
+    comment.delete(&ctx.db).await?;
+    render_list(&v, &ctx, &item, Some(&jwt_user.user)).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("sets")
+        .add("/:id/comments", get(list))
+        .add("/:id/comments", post(add))
+        .add("/:id/comments/:comment_id", put(update))
+        .add("/:id/comments/:comment_id", delete(remove))
+        .add("/:id/comments/:comment_id/edit", get(edit))
+}
@@ -0,0 +1,77 @@
+use axum::{debug_handler, extract::Query, http::StatusCode};
+use loco_rs::{controller::ErrorDetail, prelude::*};
+use reqwest::Url;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    controllers::sets::load_visible_item, initializers::minijinja_view_engine::MiniJinjaView,
+    models::songs, views,
+};
+
+#[debug_handler]
+async fn set(
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, None).await?;
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    views::embeds::set(&v, &item, &tracklist)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OEmbedQuery {
+    pub url: String,
+    pub maxwidth: Option<u32>,
+    pub maxheight: Option<u32>,
+    pub format: Option<String>,
+}
+
+/// The set a URL of this site points to, either its page or its embed.
+fn set_id(base_url: &str, url: &str) -> Option<Uuid> {
+    let url = Url::parse(url).ok()?;
+    if url.host_str() != Url::parse(base_url).ok()?.host_str() {
+        return None;
+    }
+    let segments = url.path_segments()?.collect::<Vec<_>>();
+    match segments.as_slice() {
+        ["sets", id] | ["embed", "sets", id] => Uuid::parse_str(id).ok(),
+        _ => None,
+    }
+}
+
+/// Tells consumers like blogging platforms how to embed a set from its URL.
+#[debug_handler]
+async fn oembed(
+    Query(query): Query<OEmbedQuery>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    if query
+        .format
+        .as_deref()
+        .is_some_and(|format| format != "json")
+    {
+        return Err(Error::CustomError(
+            StatusCode::NOT_IMPLEMENTED,
+            ErrorDetail::new("unsupported_format", "Only JSON is supported"),
+        ));
+    }
+    let base_url = ctx.config.server.full_url();
+    let id = set_id(&base_url, &query.url).ok_or(Error::NotFound)?;
+    let item = load_visible_item(&ctx, id, None).await?;
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    views::embeds::oembed(
+        &base_url,
+        &item,
+        tracklist.len(),
+        query.maxwidth,
+        query.maxheight,
+    )
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .add("/embed/sets/:id", get(set))
+        .add("/oembed", get(oembed))
+}
@@ -0,0 +1,342 @@
+use auth::JWTWithUser;
+use axum::{debug_handler, http::HeaderMap};
+use chrono::Utc;
+use loco_rs::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{collections, dj_follows, djs, sets, songs, users, venue_follows},
+    utils::slugify,
+    views::{self, feeds::Feed, follows::Follow, sets::Group},
+};
+
+/// How many sets a feed lists.
+const FEED_LENGTH: i64 = 50;
+
+async fn render(
+    v: &MiniJinjaView,
+    ctx: &AppContext,
+    headers: &HeaderMap,
+    feed: &Feed,
+    items: Vec<sets::Model>,
+) -> Result<Response> {
+    let ids = items.iter().map(|item| item.id).collect::<Vec<_>>();
+    let tracklists = songs::Model::list_by_setlist_ids(&ctx.db, &ids).await?;
+    views::feeds::sets(
+        v,
+        headers,
+        &ctx.config.server.full_url(),
+        feed,
+        items,
+        &tracklists,
+    )
+}
+
+enum Format {
+    /// The page listing the sets.
+    Page,
+    /// Recently updated sets with their tracklists.
+    Atom,
+    /// Upcoming sets.
+    Calendar,
+}
+
+/// `/djs/:slug`, `/venues/:slug` and `/events/:slug` serve the page listing
+/// their sets, or one of its feeds when given an extension.
+fn split_format(segment: &str) -> (&str, Format) {
+    if let Some(slug) = segment.strip_suffix(".atom") {
+        (slug, Format::Atom)
+    } else if let Some(slug) = segment.strip_suffix(".ics") {
+        (slug, Format::Calendar)
+    } else {
+        (segment, Format::Page)
+    }
+}
+
+#[debug_handler]
+async fn all_sets(
+    headers: HeaderMap,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let items = sets::Model::list_recently_updated(&ctx.db, FEED_LENGTH).await?;
+    let feed = Feed {
+        title: "Latest sets".to_string(),
+        path: "/sets.atom".to_string(),
+        alternate: "/sets".to_string(),
+    };
+    render(&v, &ctx, &headers, &feed, items).await
+}
+
+#[debug_handler]
+async fn upcoming_sets(headers: HeaderMap, State(ctx): State<AppContext>) -> Result<Response> {
+    let items = sets::Model::list_upcoming(&ctx.db, Utc::now().naive_utc()).await?;
+    let feed = Feed {
+        title: "Upcoming sets".to_string(),
+        path: "/sets.ics".to_string(),
+        alternate: "/sets".to_string(),
+    };
+    views::feeds::calendar(&headers, &ctx.config.server.full_url(), &feed, &items)
+}
+
+#[debug_handler]
+async fn user_sets(
+    headers: HeaderMap,
+    Path(username): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = users::Model::find_by_username(&ctx.db, &username).await?;
+    let items =
+        sets::Model::list_recently_updated_by_creator_id(&ctx.db, user.id, FEED_LENGTH).await?;
+    let feed = Feed {
+        title: format!("Sets added by {}", user.username),
+        path: format!("/user/{}/sets.atom", user.username),
+        alternate: format!("/user/{}", user.username),
+    };
+    render(&v, &ctx, &headers, &feed, items).await
+}
+
+#[debug_handler]
+async fn dj_sets(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    headers: HeaderMap,
+    Path(segment): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let (slug, format) = split_format(&segment);
+    match format {
+        Format::Page => {
+            let user_name = jwt_user
+                .as_ref()
+                .map(|jwt_user| jwt_user.user.username.as_str())
+                .unwrap_or_default();
+            let Ok(dj_name) = djs::Model::find_name_by_slug(&ctx.db, slug).await else {
+                return views::index::not_found(&v, user_name);
+            };
+            let items = sets::Model::list_by_dj_slug(&ctx.db, slug).await?;
+            let follow = if let Some(jwt_user) = &jwt_user {
+                let follows = dj_follows::Model::list_by_user_id(&ctx.db, jwt_user.user.id).await?;
+                let followed = follows.iter().any(|follow| follow.dj_slug == slug);
+                Some(Follow::dj(&dj_name, followed))
+            } else {
+                None
+            };
+            let group = Group {
+                title: format!("Sets by {dj_name}"),
+                path: format!("/djs/{slug}"),
+                follow,
+                items: &items,
+            };
+            views::sets::group(&v, &group, user_name)
+        }
+        Format::Calendar => {
+            let dj_name = djs::Model::find_name_by_slug(&ctx.db, slug).await?;
+            let items =
+                sets::Model::list_upcoming_by_dj_slug(&ctx.db, slug, Utc::now().naive_utc())
+                    .await?;
+            let feed = Feed {
+                title: format!("Upcoming sets by {dj_name}"),
+                path: format!("/djs/{slug}.ics"),
+                alternate: format!("/djs/{slug}"),
+            };
+            views::feeds::calendar(&headers, &ctx.config.server.full_url(), &feed, &items)
+        }
+        Format::Atom => {
+            let items =
+                sets::Model::list_recently_updated_by_dj_slug(&ctx.db, slug, FEED_LENGTH).await?;
+            let dj_name = items
+                .iter()
+                .flat_map(|item| &item.dj_names)
+                .find(|name| slugify(name) == slug)
+                .ok_or(Error::NotFound)?;
+            let feed = Feed {
+                title: format!("Sets by {dj_name}"),
+                path: format!("/djs/{slug}.atom"),
+                alternate: format!("/djs/{slug}"),
+            };
+            render(&v, &ctx, &headers, &feed, items).await
+        }
+    }
+}
+
+#[debug_handler]
+async fn venue_sets(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    headers: HeaderMap,
+    Path(segment): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let (slug, format) = split_format(&segment);
+    match format {
+        Format::Page => {
+            let user_name = jwt_user
+                .as_ref()
+                .map(|jwt_user| jwt_user.user.username.as_str())
+                .unwrap_or_default();
+            let Ok(venue) = sets::Model::find_venue_by_slug(&ctx.db, slug).await else {
+                return views::index::not_found(&v, user_name);
+            };
+            let items = sets::Model::list_by_venue_slug(&ctx.db, slug).await?;
+            let follow = if let Some(jwt_user) = &jwt_user {
+                let follows =
+                    venue_follows::Model::list_by_user_id(&ctx.db, jwt_user.user.id).await?;
+                let followed = follows.iter().any(|follow| follow.venue_slug == slug);
+                Some(Follow::venue(&venue, followed))
+            } else {
+                None
+            };
+            let group = Group {
+                title: format!("Sets at {venue}"),
+                path: format!("/venues/{slug}"),
+                follow,
+                items: &items,
+            };
+            views::sets::group(&v, &group, user_name)
+        }
+        Format::Calendar => {
+            let venue = sets::Model::find_venue_by_slug(&ctx.db, slug).await?;
+            let items =
+                sets::Model::list_upcoming_by_venue_slug(&ctx.db, slug, Utc::now().naive_utc())
+                    .await?;
+            let feed = Feed {
+                title: format!("Upcoming sets at {venue}"),
+                path: format!("/venues/{slug}.ics"),
+                alternate: format!("/venues/{slug}"),
+            };
+            views::feeds::calendar(&headers, &ctx.config.server.full_url(), &feed, &items)
+        }
+        Format::Atom => {
+            let items =
+                sets::Model::list_recently_updated_by_venue_slug(&ctx.db, slug, FEED_LENGTH)
+                    .await?;
+            let venue = items
+                .iter()
+                .find_map(|item| item.venue.clone())
+                .ok_or(Error::NotFound)?;
+            let feed = Feed {
+                title: format!("Sets at {venue}"),
+                path: format!("/venues/{slug}.atom"),
+                alternate: format!("/venues/{slug}"),
+            };
+            render(&v, &ctx, &headers, &feed, items).await
+        }
+    }
+}
+
+#[debug_handler]
+async fn event_sets(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    headers: HeaderMap,
+    Path(segment): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let (slug, format) = split_format(&segment);
+    match format {
+        Format::Page => {
+            let user_name = jwt_user
+                .as_ref()
+                .map(|jwt_user| jwt_user.user.username.as_str())
+                .unwrap_or_default();
+            let Ok(event_name) = sets::Model::find_event_by_slug(&ctx.db, slug).await else {
+                return views::index::not_found(&v, user_name);
+            };
+            let items = sets::Model::list_by_event_slug(&ctx.db, slug).await?;
+            let group = Group {
+                title: format!("Sets at {event_name}"),
+                path: format!("/events/{slug}"),
+                follow: None,
+                items: &items,
+            };
+            views::sets::group(&v, &group, user_name)
+        }
+        Format::Calendar => {
+            let event_name = sets::Model::find_event_by_slug(&ctx.db, slug).await?;
+            let items =
+                sets::Model::list_upcoming_by_event_slug(&ctx.db, slug, Utc::now().naive_utc())
+                    .await?;
+            let feed = Feed {
+                title: format!("Upcoming sets at {event_name}"),
+                path: format!("/events/{slug}.ics"),
+                alternate: format!("/events/{slug}"),
+            };
+            views::feeds::calendar(&headers, &ctx.config.server.full_url(), &feed, &items)
+        }
+        Format::Atom => {
+            let items =
+                sets::Model::list_recently_updated_by_event_slug(&ctx.db, slug, FEED_LENGTH)
+                    .await?;
+            let event_name = items
+                .iter()
+                .find_map(|item| item.event_name.clone())
+                .ok_or(Error::NotFound)?;
+            let feed = Feed {
+                title: format!("Sets at {event_name}"),
+                path: format!("/events/{slug}.atom"),
+                alternate: format!("/events/{slug}"),
+            };
+            render(&v, &ctx, &headers, &feed, items).await
+        }
+    }
+}
+
+/// Sets of a public collection, in the order its owner picked.
+#[debug_handler]
+async fn collection_sets(
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let collection = collections::Model::find_by_id(&ctx.db, id).await?;
+    if !collection.is_public {
+        return Err(Error::NotFound);
+    }
+    let items = collection.list_sets(&ctx.db).await?;
+    let feed = Feed {
+        title: collection.name,
+        path: format!("/collections/{id}/sets.atom"),
+        alternate: format!("/collections/{id}"),
+    };
+    render(&v, &ctx, &headers, &feed, items).await
+}
+
+/// Upcoming sets of the DJs a user follows. The token in the URL stands in
+/// for a login, as calendar apps subscribe without one.
+#[debug_handler]
+async fn followed_sets(
+    headers: HeaderMap,
+    Path(segment): Path<String>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let token = segment
+        .strip_suffix(".ics")
+        .and_then(|token| Uuid::parse_str(token).ok())
+        .ok_or(Error::NotFound)?;
+    let user = users::Model::find_by_calendar_token(&ctx.db, token).await?;
+    let items =
+        sets::Model::list_upcoming_by_followed_djs(&ctx.db, user.id, Utc::now().naive_utc())
+            .await?;
+    let feed = Feed {
+        title: format!("Upcoming sets followed by {}", user.username),
+        path: format!("/calendar/{token}.ics"),
+        alternate: "/settings/calendar".to_string(),
+    };
+    views::feeds::calendar(&headers, &ctx.config.server.full_url(), &feed, &items)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .add("/sets.atom", get(all_sets))
+        .add("/sets.ics", get(upcoming_sets))
+        .add("/user/:username/sets.atom", get(user_sets))
+        .add("/djs/:slug", get(dj_sets))
+        .add("/venues/:slug", get(venue_sets))
+        .add("/events/:slug", get(event_sets))
+        .add("/collections/:id/sets.atom", get(collection_sets))
+        .add("/calendar/:token", get(followed_sets))
+}
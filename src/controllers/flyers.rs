@@ -0,0 +1,87 @@
+use std::convert::Infallible;
+
+use auth::JWTWithUser;
+use axum::{debug_handler, extract::Multipart};
+use eyre::Report;
+use futures_util::stream;
+use loco_rs::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    controllers::{
+        sets::{load_owned_item, redirect_to},
+        sitemaps, uploads,
+    },
+    models::{sets, users},
+    workers::images::{self, ImageWorker, ImageWorkerArgs},
+};
+
+/// Takes the flyer as the first field of a multipart form. Images are small
+/// enough to check in memory, within the payload limit.
+#[debug_handler]
+async fn upload(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+    mut multipart: Multipart,
+) -> Result<Response> {
+    let item = load_owned_item(&ctx, id, &jwt_user.user).await?;
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|err| Error::BadRequest(err.body_text()))?
+        .ok_or_else(|| Error::BadRequest("Choose an image to upload".to_string()))?;
+    let content = field
+        .bytes()
+        .await
+        .map_err(|err| Error::BadRequest(err.body_text()))?;
+    let Some(content_type) = images::content_type(&content) else {
+        return Err(Error::BadRequest(
+            "Upload a JPEG, PNG or WebP image".to_string(),
+        ));
+    };
+
+    let content = stream::once(async { Ok::<_, Infallible>(content) });
+    let upload = uploads::create(&ctx, &jwt_user.user, content, content_type).await?;
+    let previous = match sets::Model::set_flyer(&ctx.db, item.id, Some(upload.id)).await {
+        Ok(previous) => previous,
+        Err(err) => {
+            uploads::delete(&ctx, upload.id).await?;
+            return Err(err.into());
+        }
+    };
+    if let Some(previous) = previous {
+        uploads::delete(&ctx, previous).await?;
+    }
+    sitemaps::invalidate(&ctx).await;
+    ImageWorker::perform_later(
+        &ctx,
+        ImageWorkerArgs {
+            upload_id: upload.id,
+        },
+    )
+    .await
+    .map_err(Report::new)?;
+    redirect_to(&item)
+}
+
+#[debug_handler]
+async fn remove(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_owned_item(&ctx, id, &jwt_user.user).await?;
+    if let Some(previous) = sets::Model::set_flyer(&ctx.db, item.id, None).await? {
+        uploads::delete(&ctx, previous).await?;
+    }
+    sitemaps::invalidate(&ctx).await;
+    redirect_to(&item)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("sets")
+        .add("/:id/flyer", post(upload))
+        .add("/:id/flyer", delete(remove))
+}
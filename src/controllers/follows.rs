@@ -0,0 +1,94 @@
+use auth::JWTWithUser;
+use axum::debug_handler;
+use loco_rs::prelude::*;
+
+use crate::{
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{dj_follows, djs, sets, user_follows, users, venue_follows},
+    utils::model_error,
+    views::{self, follows::Follow},
+};
+
+#[debug_handler]
+async fn follow_dj(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(slug): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let name = djs::Model::find_name_by_slug(&ctx.db, &slug).await?;
+    dj_follows::Model::follow(&ctx.db, jwt_user.user.id, &name).await?;
+    views::follows::button(&v, &Follow::dj(&name, true))
+}
+
+#[debug_handler]
+async fn unfollow_dj(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(slug): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let name = djs::Model::find_name_by_slug(&ctx.db, &slug).await?;
+    dj_follows::Model::unfollow(&ctx.db, jwt_user.user.id, &slug).await?;
+    views::follows::button(&v, &Follow::dj(&name, false))
+}
+
+#[debug_handler]
+async fn follow_venue(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(slug): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let name = sets::Model::find_venue_by_slug(&ctx.db, &slug).await?;
+    venue_follows::Model::follow(&ctx.db, jwt_user.user.id, &name).await?;
+    views::follows::button(&v, &Follow::venue(&name, true))
+}
+
+#[debug_handler]
+async fn unfollow_venue(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(slug): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let name = sets::Model::find_venue_by_slug(&ctx.db, &slug).await?;
+    venue_follows::Model::unfollow(&ctx.db, jwt_user.user.id, &slug).await?;
+    views::follows::button(&v, &Follow::venue(&name, false))
+}
+
+#[debug_handler]
+async fn follow_user(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(username): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = users::Model::find_by_username(&ctx.db, &username).await?;
+    user_follows::Model::follow(&ctx.db, jwt_user.user.id, user.id)
+        .await
+        .map_err(model_error)?;
+    views::follows::button(&v, &Follow::user(&user.username, true))
+}
+
+#[debug_handler]
+async fn unfollow_user(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(username): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = users::Model::find_by_username(&ctx.db, &username).await?;
+    user_follows::Model::unfollow(&ctx.db, jwt_user.user.id, user.id).await?;
+    views::follows::button(&v, &Follow::user(&user.username, false))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .add("/djs/:slug/follow", post(follow_dj))
+        .add("/djs/:slug/follow", delete(unfollow_dj))
+        .add("/venues/:slug/follow", post(follow_venue))
+        .add("/venues/:slug/follow", delete(unfollow_venue))
+        .add("/user/:username/follow", post(follow_user))
+        .add("/user/:username/follow", delete(unfollow_user))
+}
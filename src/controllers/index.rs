@@ -1,17 +1,57 @@
 use loco_rs::prelude::*;
 
 use crate::{
-    initializers::minijinja_view_engine::MiniJinjaView, models::users, utils::get_username, views,
+    controllers::api::PageQuery,
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{dj_follows, sets, user_follows, users, venue_follows},
+    views::{self, index::Following},
 };
 
+/// How many sets of the feed the landing page shows.
+const LANDING_FEED_LENGTH: i64 = 5;
+
 pub async fn root(
     jwt_user: Option<auth::JWTWithUser<users::Model>>,
     ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
 ) -> Result<impl IntoResponse> {
-    let user_name = get_username(jwt_user).unwrap_or_default();
-    views::index::root(&v, &user_name)
+    let Some(jwt_user) = jwt_user else {
+        return views::index::root(&v, &[], "");
+    };
+    let items =
+        sets::Model::list_page_followed_by(&ctx.db, jwt_user.user.id, LANDING_FEED_LENGTH, 0)
+            .await?;
+    views::index::root(&v, &items, &jwt_user.user.username)
+}
+
+/// Sets from every DJ, venue and user the user follows.
+pub async fn feed(
+    jwt_user: Option<auth::JWTWithUser<users::Model>>,
+    page: PageQuery,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let user = &jwt_user.user;
+    let total = sets::Model::count_followed_by(&ctx.db, user.id).await?;
+    let items =
+        sets::Model::list_page_followed_by(&ctx.db, user.id, page.limit(), page.offset()).await?;
+    let following = Following {
+        djs: dj_follows::Model::list_by_user_id(&ctx.db, user.id).await?,
+        venues: venue_follows::Model::list_by_user_id(&ctx.db, user.id).await?,
+        usernames: user_follows::Model::list_followee_usernames(&ctx.db, user.id).await?,
+    };
+    views::index::feed(
+        &v,
+        &items,
+        &page.pagination(total),
+        &following,
+        &user.username,
+    )
 }
 
 pub fn routes() -> Routes {
-    Routes::new().add("/", get(root))
+    Routes::new().add("/", get(root)).add("/feed", get(feed))
 }
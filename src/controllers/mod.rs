@@ -1,4 +1,21 @@
+pub mod api;
 pub mod auth;
+pub mod collections;
+pub mod comments;
+pub mod embeds;
+pub mod feeds;
+pub mod flyers;
+pub mod follows;
 pub mod index;
+pub mod notifications;
+pub mod reactions;
+pub mod recordings;
 pub mod sets;
+pub mod settings;
+pub mod sitemaps;
+pub mod stats;
+pub mod tags;
+pub mod tracks;
+pub mod unsubscribe;
+pub mod uploads;
 pub mod user;
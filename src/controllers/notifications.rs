@@ -0,0 +1,88 @@
+use auth::JWTWithUser;
+use axum::debug_handler;
+use loco_rs::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    controllers::api::PageQuery,
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{notifications, users},
+    views,
+};
+
+#[debug_handler]
+async fn index(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    page: PageQuery,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let user = &jwt_user.user;
+    let total = notifications::Model::count_by_user_id(&ctx.db, user.id).await?;
+    let items =
+        notifications::Model::list_page_by_user_id(&ctx.db, user.id, page.limit(), page.offset())
+            .await?;
+    views::notifications::index(&v, &items, &page.pagination(total), &user.username)
+}
+
+/// Polled by the bell in the layout.
+#[debug_handler]
+async fn bell(
+    jwt_user: JWTWithUser<users::Model>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let unread = notifications::Model::count_unread(&ctx.db, jwt_user.user.id).await?;
+    views::notifications::bell(&v, unread)
+}
+
+/// Follows a notification, marking it as read on the way.
+#[debug_handler]
+async fn open(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = notifications::Model::mark_read(&ctx.db, jwt_user.user.id, id).await?;
+    format::render().redirect(&item.path)
+}
+
+#[debug_handler]
+async fn mark_read(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = notifications::Model::mark_read(&ctx.db, jwt_user.user.id, id).await?;
+    views::notifications::item(&v, &item)
+}
+
+#[debug_handler]
+async fn mark_all_read(
+    jwt_user: JWTWithUser<users::Model>,
+    page: PageQuery,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user = &jwt_user.user;
+    notifications::Model::mark_all_read(&ctx.db, user.id).await?;
+    let total = notifications::Model::count_by_user_id(&ctx.db, user.id).await?;
+    let items =
+        notifications::Model::list_page_by_user_id(&ctx.db, user.id, page.limit(), page.offset())
+            .await?;
+    views::notifications::list(&v, &items, &page.pagination(total))
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("notifications")
+        .add("/", get(index))
+        .add("/bell", get(bell))
+        .add("/read", post(mark_all_read))
+        .add("/:id", get(open))
+        .add("/:id/read", post(mark_read))
+}
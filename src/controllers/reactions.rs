@@ -0,0 +1,115 @@
+use auth::JWTWithUser;
+use axum::debug_handler;
+use loco_rs::prelude::*;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    controllers::sets::load_visible_item,
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{attendances, set_likes, set_ratings, sets, users},
+    utils::model_error,
+    views,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct RatingParams {
+    pub rating: i16,
+}
+
+async fn render(
+    v: &MiniJinjaView,
+    ctx: &AppContext,
+    item: &sets::Model,
+    user: &users::Model,
+) -> Result<Response> {
+    let stats = item.find_stats(&ctx.db).await?;
+    let reactions = item.find_reactions(&ctx.db, user.id).await?;
+    views::sets::reactions(v, item, &stats, &reactions, &user.username)
+}
+
+#[debug_handler]
+async fn like(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    set_likes::Model::like(&ctx.db, jwt_user.user.id, item.id).await?;
+    render(&v, &ctx, &item, &jwt_user.user).await
+}
+
+#[debug_handler]
+async fn unlike(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    set_likes::Model::unlike(&ctx.db, jwt_user.user.id, item.id).await?;
+    render(&v, &ctx, &item, &jwt_user.user).await
+}
+
+#[debug_handler]
+async fn attend(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    attendances::Model::attend(&ctx.db, jwt_user.user.id, item.id).await?;
+    render(&v, &ctx, &item, &jwt_user.user).await
+}
+
+#[debug_handler]
+async fn unattend(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    attendances::Model::unattend(&ctx.db, jwt_user.user.id, item.id).await?;
+    render(&v, &ctx, &item, &jwt_user.user).await
+}
+
+#[debug_handler]
+async fn rate(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<RatingParams>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    set_ratings::Model::rate(&ctx.db, jwt_user.user.id, item.id, params.rating)
+        .await
+        .map_err(model_error)?;
+    render(&v, &ctx, &item, &jwt_user.user).await
+}
+
+#[debug_handler]
+async fn unrate(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    set_ratings::Model::remove(&ctx.db, jwt_user.user.id, item.id).await?;
+    render(&v, &ctx, &item, &jwt_user.user).await
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("sets")
+        .add("/:id/like", post(like))
+        .add("/:id/like", delete(unlike))
+        .add("/:id/attendance", post(attend))
+        .add("/:id/attendance", delete(unattend))
+        .add("/:id/rating", post(rate))
+        .add("/:id/rating", delete(unrate))
+}
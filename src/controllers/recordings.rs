@@ -0,0 +1,105 @@
+use auth::JWTWithUser;
+use axum::{
+    debug_handler,
+    extract::{DefaultBodyLimit, Multipart},
+};
+use eyre::Report;
+use loco_rs::prelude::*;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    controllers::{
+        sets::{load_owned_item, redirect_to},
+        uploads,
+    },
+    models::{recordings, users},
+    utils::model_error,
+    workers::waveform::{WaveformWorker, WaveformWorkerArgs},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Params {
+    pub url: String,
+}
+
+#[debug_handler]
+async fn add(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<Params>,
+) -> Result<Response> {
+    let item = load_owned_item(&ctx, id, &jwt_user.user).await?;
+    recordings::Model::create(&ctx.db, item.id, &params.url)
+        .await
+        .map_err(model_error)?;
+    redirect_to(&item)
+}
+
+/// Takes the recording as the first field of a multipart form, streamed to
+/// storage rather than buffered, so it is not bound by the payload limit.
+#[debug_handler]
+async fn upload(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+    mut multipart: Multipart,
+) -> Result<Response> {
+    let item = load_owned_item(&ctx, id, &jwt_user.user).await?;
+    recordings::Model::check_room(&ctx.db, item.id)
+        .await
+        .map_err(model_error)?;
+    let field = multipart
+        .next_field()
+        .await
+        .map_err(|err| Error::BadRequest(err.body_text()))?
+        .ok_or_else(|| Error::BadRequest("Choose a file to upload".to_string()))?;
+    let content_type = field.content_type().unwrap_or_default().to_string();
+    if !content_type.starts_with("audio/") {
+        return Err(Error::BadRequest("Upload an audio file".to_string()));
+    }
+
+    let upload = uploads::create(&ctx, &jwt_user.user, field, &content_type).await?;
+    if let Err(err) = recordings::Model::create_upload(&ctx.db, item.id, upload.id).await {
+        uploads::delete(&ctx, upload.id).await?;
+        return Err(err.into());
+    }
+    WaveformWorker::perform_later(
+        &ctx,
+        WaveformWorkerArgs {
+            upload_id: upload.id,
+        },
+    )
+    .await
+    .map_err(Report::new)?;
+    redirect_to(&item)
+}
+
+#[debug_handler]
+async fn remove(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, recording_id)): Path<(Uuid, Uuid)>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_owned_item(&ctx, id, &jwt_user.user).await?;
+    let recording = recordings::Model::find(&ctx.db, item.id, recording_id).await?;
+    if let Some(upload_id) = recording.upload_id {
+        // takes the recording along
+        uploads::delete(&ctx, upload_id).await?;
+    } else {
+        recordings::Model::delete(&ctx.db, item.id, recording.id).await?;
+    }
+    redirect_to(&item)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("sets")
+        .add("/:id/recordings", post(add))
+        .add(
+            "/:id/recordings/upload",
+            post(upload).layer(DefaultBodyLimit::disable()),
+        )
+        .add("/:id/recordings/:recording_id", delete(remove))
+}
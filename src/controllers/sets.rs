@@ -1,17 +1,49 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
 use auth::JWTWithUser;
-use axum::{debug_handler, http::uri::PathAndQuery};
+use axum::{
+    debug_handler,
+    extract::Query,
+    http::{header, uri::PathAndQuery, HeaderMap, HeaderValue},
+};
+use base64::prelude::*;
 use chrono::{NaiveDateTime, Utc};
-use loco_rs::prelude::*;
+use eyre::Report;
+use loco_rs::{
+    controller::{bad_request, openapi::Operation},
+    prelude::*,
+};
+use schemars::JsonSchema;
 use serde::{de, Deserialize, Deserializer, Serialize};
 use uuid::Uuid;
 
 use crate::{
+    controllers::uploads,
     initializers::minijinja_view_engine::MiniJinjaView,
-    models::{sets, users},
-    utils::{get_username, hx_redirect},
-    views,
+    mailers::notifications::NotificationMailer,
+    models::{
+        collections, comments, dj_follows,
+        edit_proposals::{self, ProposalStatus},
+        id_requests,
+        musical_keys::MusicalKey,
+        recordings, set_similarities, sets, songs, tags, users, venue_follows,
+    },
+    services,
+    utils::{forbidden, get_username, hx_redirect, hx_redirect_to, model_error},
+    views::{
+        self,
+        feeds::Validators,
+        sets::{SetPage, TrackSort, Viewer},
+        share,
+    },
+    workers::waveform::Peaks,
 };
 
+/// How many tags the sets list offers as filters.
+const POPULAR_TAGS: i64 = 20;
+/// How many sets like it a set page suggests.
+const SIMILAR_SETS: i64 = 5;
+
 fn html_datetime<'de, D>(d: D) -> Result<NaiveDateTime, D::Error>
 where
     D: Deserializer<'de>,
@@ -27,7 +59,25 @@ where
     NaiveDateTime::parse_from_str(&formatted, "%Y-%m-%dT%H:%M:%S").map_err(de::Error::custom)
 }
 
-#[derive(Serialize, Deserialize)]
+/// Tags come as a list, or from the tag input as a comma separated string.
+fn tag_list<'de, D>(d: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Tags {
+        Separated(String),
+        List(Vec<String>),
+    }
+
+    Ok(Option::<Tags>::deserialize(d)?.map(|tags| match tags {
+        Tags::Separated(tags) => tags.split(',').map(str::to_string).collect(),
+        Tags::List(tags) => tags,
+    }))
+}
+
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Params {
     pub dj_names: Vec<String>,
     pub venue: Option<String>,
@@ -37,10 +87,29 @@ pub struct Params {
     pub start_time: NaiveDateTime,
     pub duration_seconds: Option<i32>,
     pub setlist: Vec<Song>,
+    /// Genres and other tags of the set. Left as they are when missing.
+    #[serde(default, deserialize_with = "tag_list")]
+    pub tags: Option<Vec<String>>,
+    /// Who finds the set. Left as it is when missing, new sets are public.
+    #[serde(default)]
+    pub visibility: Option<sets::Visibility>,
 }
 
 impl Params {
-    fn update(self, item: &mut sets::Model) {
+    /// Checks the tags, before the set is saved without them.
+    ///
+    /// # Errors
+    ///
+    /// When there are too many tags or a name is invalid
+    pub fn validate_tags(&self) -> Result<()> {
+        if let Some(names) = &self.tags {
+            tags::validate_names(names).map_err(|err| Error::BadRequest(err.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Applies the params to `item` and returns the tracklist to store for it.
+    pub fn update(self, item: &mut sets::Model) -> Vec<songs::Model> {
         item.updated_at = Utc::now().naive_utc();
         item.dj_names = self.dj_names;
         item.venue = self.venue;
@@ -48,32 +117,201 @@ impl Params {
         item.event_name = self.event_name;
         item.start_time = self.start_time;
         item.duration_seconds = self.duration_seconds;
+        if let Some(visibility) = self.visibility {
+            item.visibility = visibility.as_str().to_string();
+        }
+        self.setlist
+            .into_iter()
+            .map(|song| songs::Model::from_song_and_setlist_id(song, item.id))
+            .collect()
+    }
+}
+
+/// Details of a track come from the API as numbers or strings, and from the
+/// tracklist editor as strings which are empty when left out.
+fn track_detail<'de, D, T>(d: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let value = match Option::<serde_json::Value>::deserialize(d)? {
+        None | Some(serde_json::Value::Null) => return Ok(None),
+        Some(serde_json::Value::String(value)) => value.trim().to_string(),
+        Some(serde_json::Value::Number(value)) => value.to_string(),
+        Some(value) => return Err(de::Error::custom(format!("unexpected {value}"))),
+    };
+    if value.is_empty() {
+        return Ok(None);
+    }
+    value.parse().map(Some).map_err(de::Error::custom)
+}
+
+fn bpm<'de, D>(d: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let bpm = track_detail::<_, f64>(d)?;
+    match bpm {
+        Some(bpm) if !(1.0..1000.0).contains(&bpm) => {
+            Err(de::Error::custom(format!("{bpm} is not a BPM")))
+        }
+        // DJ software shows at most two decimals
+        bpm => Ok(bpm.map(|bpm| (bpm * 100.0).round() / 100.0)),
+    }
+}
+
+fn release_year<'de, D>(d: D) -> Result<Option<i32>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let year = track_detail::<_, i32>(d)?;
+    match year {
+        Some(year) if !(1000..=9999).contains(&year) => {
+            Err(de::Error::custom(format!("{year} is not a year")))
+        }
+        year => Ok(year),
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct Song {
     pub track_title: String,
     pub track_artist: String,
     pub track_number: Option<i32>,
     pub track_start_time_offset_seconds: Option<i32>,
     pub track_duration_seconds: Option<i32>,
+    #[serde(default, deserialize_with = "bpm")]
+    pub track_bpm: Option<f64>,
+    /// Like "Am", "8A" in Camelot or "1m" in Open Key notation, always
+    /// returned in the first.
+    #[serde(default, deserialize_with = "track_detail")]
+    #[schemars(with = "Option<String>")]
+    pub track_key: Option<MusicalKey>,
+    #[serde(default, deserialize_with = "track_detail")]
+    pub track_label: Option<String>,
+    #[serde(default, deserialize_with = "release_year")]
+    pub track_release_year: Option<i32>,
+    #[serde(default, deserialize_with = "track_detail")]
+    pub track_remixer: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProposalParams {
+    pub message: String,
+}
+
+impl From<songs::Model> for Song {
+    fn from(song: songs::Model) -> Self {
+        Self {
+            track_title: song.track_title,
+            track_artist: song.track_artist,
+            track_number: song.track_number,
+            track_start_time_offset_seconds: song.track_start_time_offset_seconds,
+            track_duration_seconds: song.track_duration_seconds,
+            track_bpm: song.track_bpm,
+            track_key: song.track_key.and_then(|key| key.parse().ok()),
+            track_label: song.track_label,
+            track_release_year: song.track_release_year,
+            track_remixer: song.track_remixer,
+        }
+    }
 }
 
 async fn load_item(ctx: &AppContext, id: Uuid) -> Result<sets::Model> {
     Ok(sets::Model::find_by_id(&ctx.db, id).await?)
 }
 
+/// Reloads the page of a set after a change, which may show up anywhere on it.
+///
+/// # Errors
+///
+/// When the path is not valid
+pub fn redirect_to(item: &sets::Model) -> Result<Response> {
+    hx_redirect_to(format!("/sets/{}", item.id))
+}
+
+/// Finds a set `user` may see, or anyone without a login when `None`. The
+/// private sets of others are not found, so they cannot be told apart from
+/// sets that do not exist.
+///
+/// # Errors
+///
+/// When the set does not exist or is private to someone else
+pub async fn load_visible_item(
+    ctx: &AppContext,
+    id: Uuid,
+    user: Option<&users::Model>,
+) -> Result<sets::Model> {
+    let item = load_item(ctx, id).await?;
+    if item.is_visible_to(user.map(|user| user.id)) {
+        Ok(item)
+    } else {
+        Err(Error::NotFound)
+    }
+}
+
+/// Finds one of the user's sets, refusing to let anyone else change it.
+///
+/// # Errors
+///
+/// When the set does not exist or was added by someone else
+pub async fn load_owned_item(
+    ctx: &AppContext,
+    id: Uuid,
+    user: &users::Model,
+) -> Result<sets::Model> {
+    let item = load_item(ctx, id).await?;
+    if item.creator_id == user.id {
+        Ok(item)
+    } else {
+        forbidden()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListQuery {
+    /// Slug of a tag to filter the sets by.
+    pub tag: Option<String>,
+}
+
 #[debug_handler]
 pub async fn list(
     jwt_user: Option<JWTWithUser<users::Model>>,
+    Query(query): Query<ListQuery>,
     ViewEngine(v): ViewEngine<MiniJinjaView>,
     State(ctx): State<AppContext>,
 ) -> Result<Response> {
     let user_name = get_username(jwt_user).unwrap_or_default();
-    let item = sets::Model::list_all(&ctx.db).await;
+    let tag_filter = match query.tag.as_deref().filter(|slug| !slug.is_empty()) {
+        Some(slug) => match tags::Model::find_by_slug(&ctx.db, slug).await {
+            // synonyms filter by the tag they were merged into
+            Ok(tag) => match tag.merged_into_id {
+                Some(id) => Some(tags::Model::find_by_id(&ctx.db, id).await?),
+                None => Some(tag),
+            },
+            Err(_) => return views::index::not_found(&v, &user_name),
+        },
+        None => None,
+    };
+    let item = match &tag_filter {
+        Some(tag) => sets::Model::list_by_tag_id(&ctx.db, tag.id).await,
+        None => sets::Model::list_all(&ctx.db).await,
+    };
     if let Ok(item) = item {
-        views::sets::list(&v, &item, &user_name)
+        let ids = item.iter().map(|item| item.id).collect::<Vec<_>>();
+        let stats = sets::Model::list_stats(&ctx.db, &ids).await?;
+        let set_tags = tags::Model::list_by_set_ids(&ctx.db, &ids).await?;
+        let popular_tags = tags::Model::list_popular(&ctx.db, POPULAR_TAGS).await?;
+        views::sets::list(
+            &v,
+            &item,
+            &stats,
+            &set_tags,
+            &popular_tags,
+            tag_filter.as_ref(),
+            &user_name,
+        )
     } else {
         views::index::not_found(&v, &user_name)
     }
@@ -95,13 +333,16 @@ pub async fn new(
 
 #[debug_handler]
 pub async fn update(
+    jwt_user: JWTWithUser<users::Model>,
     Path(id): Path<Uuid>,
     State(ctx): State<AppContext>,
-    Json(params): Json<Params>,
+    Json(mut params): Json<Params>,
 ) -> Result<Response> {
-    let mut item = load_item(&ctx, id).await?;
-    params.update(&mut item);
-    item.update(&ctx.db).await?;
+    params.validate_tags()?;
+    let mut item = load_owned_item(&ctx, id, &jwt_user.user).await?;
+    let tags = params.tags.take();
+    let setlist = params.update(&mut item);
+    let (item, _) = services::sets::update(&ctx, &item, &setlist, tags.as_deref()).await?;
     format::json(item)
 }
 
@@ -112,62 +353,267 @@ pub async fn edit(
     ViewEngine(v): ViewEngine<MiniJinjaView>,
     State(ctx): State<AppContext>,
 ) -> Result<Response> {
-    let user_name = get_username(jwt_user).unwrap_or_default();
-    if user_name.is_empty() {
-        views::index::unauthorized(&v)
-    } else {
-        let item = load_item(&ctx, id).await;
-        if let Ok(item) = item {
-            views::sets::edit(&v, &item, &user_name)
-        } else {
-            views::index::not_found(&v, &user_name)
-        }
-    }
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let user_name = &jwt_user.user.username;
+    let Ok(item) = load_visible_item(&ctx, id, Some(&jwt_user.user)).await else {
+        return views::index::not_found(&v, user_name);
+    };
+    let tags = tags::Model::list_by_set_id(&ctx.db, item.id).await?;
+    views::sets::edit(&v, &item, &tags, user_name)
 }
 
 #[debug_handler]
 pub async fn show(
     jwt_user: Option<JWTWithUser<users::Model>>,
     path: Option<Path<Uuid>>,
+    Query(sort): Query<TrackSort>,
     ViewEngine(v): ViewEngine<MiniJinjaView>,
     State(ctx): State<AppContext>,
 ) -> Result<Response> {
-    let user_name = get_username(jwt_user).unwrap_or_default();
-    if let Some(Path(id)) = path {
-        let item = load_item(&ctx, id).await;
-        if let Ok(item) = item {
-            views::sets::show(&v, &item, &user_name)
-        } else {
-            views::index::not_found(&v, &user_name)
+    let user_name = jwt_user
+        .as_ref()
+        .map(|jwt_user| jwt_user.user.username.clone())
+        .unwrap_or_default();
+    let Some(Path(id)) = path else {
+        return views::index::not_found(&v, &user_name);
+    };
+    let user = jwt_user.as_ref().map(|jwt_user| &jwt_user.user);
+    let Ok(item) = load_visible_item(&ctx, id, user).await else {
+        return views::index::not_found(&v, &user_name);
+    };
+
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    let tags = tags::Model::list_by_set_id(&ctx.db, item.id).await?;
+    let recordings = recordings::Model::list_by_set_id(&ctx.db, item.id).await?;
+    let mut waveforms = HashMap::new();
+    for recording in &recordings {
+        let Some(upload_id) = recording.upload_id else {
+            continue;
+        };
+        let store = ctx.storage.as_store_err(uploads::STORE)?;
+        if let Some(peaks) = Peaks::load(store, upload_id).await {
+            waveforms.insert(recording.id, peaks);
+        }
+    }
+    let comments = comments::Model::list_by_set_id(&ctx.db, item.id).await?;
+    let stats = item.find_stats(&ctx.db).await?;
+    let similarities =
+        set_similarities::Model::list_by_set_id(&ctx.db, item.id, SIMILAR_SETS).await?;
+    let ids = similarities
+        .iter()
+        .map(|similarity| similarity.similar_set_id)
+        .collect::<Vec<_>>();
+    let similar_sets = sets::Model::list_by_ids(&ctx.db, &ids).await?;
+    let mut proposals = vec![];
+    let mut viewer = Viewer {
+        user_name,
+        ..Default::default()
+    };
+    if let Some(jwt_user) = &jwt_user {
+        let user_id = jwt_user.user.id;
+        viewer.user_id = Some(user_id);
+        viewer.is_owner = item.creator_id == user_id;
+        viewer.dj_follows = dj_follows::Model::list_by_user_id(&ctx.db, user_id).await?;
+        viewer.venue_follows = venue_follows::Model::list_by_user_id(&ctx.db, user_id).await?;
+        viewer.id_requests =
+            id_requests::Model::list_track_numbers(&ctx.db, user_id, item.id).await?;
+        viewer.reactions = item.find_reactions(&ctx.db, user_id).await?;
+        viewer.collections = collections::Model::list_by_owner_id(&ctx.db, user_id, true).await?;
+        if viewer.is_owner {
+            proposals = edit_proposals::Model::list_open_by_set_id(&ctx.db, item.id).await?;
         }
-    } else {
-        views::index::not_found(&v, &user_name)
     }
+    let page = SetPage {
+        item: &item,
+        tracklist: &tracklist,
+        tags: &tags,
+        recordings: &recordings,
+        waveforms: &waveforms,
+        proposals: &proposals,
+        comments: &comments,
+        stats: &stats,
+        similarities: &similarities,
+        similar_sets: &similar_sets,
+        sort,
+    };
+    views::sets::show(&v, &page, &viewer)
+}
+
+/// Cache key of a rendered share image. Rendering takes long enough to be
+/// worth keeping, and the ETag changes with the set.
+#[must_use]
+pub fn share_image_key(etag: &str) -> String {
+    format!("share:{etag}")
+}
+
+/// Draws the preview image of a set shared without a flyer. The image only
+/// changes with the set, so chat apps can keep it.
+#[debug_handler]
+async fn share_image(
+    Path(id): Path<Uuid>,
+    headers: HeaderMap,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, None).await?;
+    let validators = Validators::new("share", [(&item.id, &item.updated_at)]);
+    let png = if validators.is_fresh(&headers) {
+        vec![]
+    } else {
+        let key = share_image_key(&validators.etag);
+        if let Some(cached) = ctx.cache.get(&key).await.map_err(Report::new)? {
+            BASE64_STANDARD
+                .decode(cached)
+                .map_err(|err| Error::Message(err.to_string()))?
+        } else {
+            let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+            let svg = views::sets::share_image(&v, &item, &tracklist)?;
+            let png = tokio::task::spawn_blocking(move || share::rasterize(&svg))
+                .await
+                .map_err(|err| Error::Message(err.to_string()))??;
+            ctx.cache
+                .insert(&key, &BASE64_STANDARD.encode(&png))
+                .await
+                .map_err(Report::new)?;
+            png
+        }
+    };
+    let mut response = validators.respond(&headers, "image/png", || Ok(png))?;
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static("public, max-age=86400"),
+    );
+    Ok(response)
 }
 
 #[debug_handler]
 pub async fn add(
     auth: auth::JWT,
     State(ctx): State<AppContext>,
-    Json(params): Json<Params>,
+    Json(mut params): Json<Params>,
 ) -> Result<Response> {
+    params.validate_tags()?;
     let uuid = uuid::Uuid::parse_str(&auth.claims.id)?;
     let mut item = sets::Model {
         id: Uuid::now_v7(),
         creator_id: uuid,
         ..Default::default()
     };
-    params.update(&mut item);
-    item.insert(&ctx.db).await?;
+    let tags = params.tags.take();
+    let setlist = params.update(&mut item);
+    services::sets::create(&ctx, &item, &setlist, tags.as_deref()).await?;
     hx_redirect(&PathAndQuery::from_static("/sets"))
 }
 
 #[debug_handler]
-pub async fn remove(Path(id): Path<Uuid>, State(ctx): State<AppContext>) -> Result<Response> {
-    sets::Model::delete_by_id(&ctx.db, id).await?;
+pub async fn remove(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_owned_item(&ctx, id, &jwt_user.user).await?;
+    services::sets::delete(&ctx, &item).await?;
     hx_redirect(&PathAndQuery::from_static("/sets"))
 }
 
+#[debug_handler]
+pub async fn propose(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<ProposalParams>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    if item.creator_id == jwt_user.user.id {
+        return bad_request("Edit your own set instead of proposing an edit");
+    }
+    let proposal =
+        edit_proposals::Model::create(&ctx.db, item.id, jwt_user.user.id, &params.message)
+            .await
+            .map_err(model_error)?;
+    NotificationMailer::edit_proposed(&ctx, &item, &proposal, &jwt_user.user).await?;
+    views::sets::proposal_sent(&v, &item)
+}
+
+async fn resolve_proposal(
+    ctx: &AppContext,
+    user: &users::Model,
+    id: Uuid,
+    proposal_id: Uuid,
+    status: ProposalStatus,
+) -> Result<(sets::Model, Vec<edit_proposals::Model>)> {
+    let item = load_item(ctx, id).await?;
+    if item.creator_id != user.id {
+        return forbidden();
+    }
+    edit_proposals::Model::find_by_id_and_set_id(&ctx.db, proposal_id, item.id)
+        .await?
+        .set_status(&ctx.db, status)
+        .await?;
+    let proposals = edit_proposals::Model::list_open_by_set_id(&ctx.db, item.id).await?;
+    Ok((item, proposals))
+}
+
+#[debug_handler]
+pub async fn accept_proposal(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, proposal_id)): Path<(Uuid, Uuid)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let (item, proposals) = resolve_proposal(
+        &ctx,
+        &jwt_user.user,
+        id,
+        proposal_id,
+        ProposalStatus::Accepted,
+    )
+    .await?;
+    views::sets::proposals(&v, &item, &proposals)
+}
+
+#[debug_handler]
+pub async fn decline_proposal(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, proposal_id)): Path<(Uuid, Uuid)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let (item, proposals) = resolve_proposal(
+        &ctx,
+        &jwt_user.user,
+        id,
+        proposal_id,
+        ProposalStatus::Declined,
+    )
+    .await?;
+    views::sets::proposals(&v, &item, &proposals)
+}
+
+/// Asks to be told when an unidentified track gets identified.
+#[debug_handler]
+pub async fn request_id(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, track_number)): Path<(Uuid, i32)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = load_visible_item(&ctx, id, Some(&jwt_user.user)).await?;
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    let song = tracklist
+        .iter()
+        .find(|song| song.track_number == Some(track_number))
+        .ok_or(Error::NotFound)?;
+    if song.is_identified() {
+        return bad_request("This track is already identified");
+    }
+    id_requests::Model::create(&ctx.db, jwt_user.user.id, item.id, track_number).await?;
+    views::sets::id_requested(&v, &item, song)
+}
+
 pub fn routes() -> Routes {
     Routes::new()
         .prefix("sets")
@@ -175,7 +621,21 @@ pub fn routes() -> Routes {
         .add("/new", get(new))
         .add("/:id", get(show))
         .add("/:id/edit", get(edit))
+        .add("/:id/share.png", get(share_image))
         .add("/:id", post(update))
+        .doc(
+            Operation::new("Update a set")
+                .request::<Params>()
+                .response::<sets::Model>(),
+        )
         .add("/:id", delete(remove))
+        .add("/:id/proposals", post(propose))
+        .add("/:id/proposals/:proposal_id/accept", post(accept_proposal))
+        .add(
+            "/:id/proposals/:proposal_id/decline",
+            post(decline_proposal),
+        )
+        .add("/:id/tracks/:track_number/id_request", post(request_id))
         .add("/", post(add))
+        .doc(Operation::new("Create a set").request::<Params>())
 }
@@ -0,0 +1,341 @@
+use auth::JWTWithUser;
+use axum::debug_handler;
+use eyre::Report;
+use loco_rs::{controller::bad_request, prelude::*};
+use serde::{de, Deserialize, Deserializer};
+use uuid::Uuid;
+
+use crate::{
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{
+        api_keys, dj_follows,
+        email_preferences::{self, Topic},
+        users, webhook_deliveries,
+        webhooks::{self, WebhookEvent},
+    },
+    settings::Settings,
+    utils::model_error,
+    views,
+    workers::webhook::{WebhookWorker, WebhookWorkerArgs},
+};
+
+/// htmx sends a single checked checkbox as a string and several as a list.
+fn one_or_many<'de, D, T>(d: D) -> Result<Vec<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany<T> {
+        One(T),
+        Many(Vec<T>),
+    }
+
+    Ok(match Option::<OneOrMany<T>>::deserialize(d)? {
+        None => vec![],
+        Some(OneOrMany::One(scope)) => vec![scope],
+        Some(OneOrMany::Many(scopes)) => scopes,
+    })
+}
+
+/// The expiry select sends the lifetime in days, or an empty string for keys
+/// that never expire.
+fn expires_in_days<'de, D>(d: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let s = Option::<String>::deserialize(d)?.unwrap_or_default();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse::<i64>()
+        .ok()
+        .filter(|days| (1..=api_keys::MAX_LIFETIME_DAYS).contains(days))
+        .map(Some)
+        .ok_or_else(|| {
+            de::Error::custom(format!(
+                "expiry must be between 1 and {} days",
+                api_keys::MAX_LIFETIME_DAYS
+            ))
+        })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyParams {
+    pub name: String,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub scopes: Vec<api_keys::ApiScope>,
+    #[serde(default, deserialize_with = "expires_in_days")]
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookParams {
+    pub url: String,
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub events: Vec<WebhookEvent>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationParams {
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub topics: Vec<Topic>,
+}
+
+async fn render_keys(
+    v: &MiniJinjaView,
+    ctx: &AppContext,
+    user: &users::Model,
+    new_key: Option<&str>,
+) -> Result<Response> {
+    let keys = api_keys::Model::list_by_user_id(&ctx.db, user.id).await?;
+    views::settings::api_keys_list(v, &keys, new_key)
+}
+
+#[debug_handler]
+async fn api_keys(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let keys = api_keys::Model::list_by_user_id(&ctx.db, jwt_user.user.id).await?;
+    views::settings::api_keys(&v, &keys, &api_keys::ApiScope::ALL, &jwt_user.user.username)
+}
+
+#[debug_handler]
+async fn create_api_key(
+    jwt_user: JWTWithUser<users::Model>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreateApiKeyParams>,
+) -> Result<Response> {
+    if params.name.trim().is_empty() || params.scopes.is_empty() {
+        return bad_request("An API key needs a name and at least one scope");
+    }
+    let expires_at = params
+        .expires_in_days
+        .map(|days| {
+            api_keys::expires_in_days(days)
+                .ok_or_else(|| Error::BadRequest("The expiry is too far away".to_string()))
+        })
+        .transpose()?;
+
+    let (_, key) = api_keys::Model::create(
+        &ctx.db,
+        jwt_user.user.id,
+        &params.name,
+        &params.scopes,
+        expires_at,
+    )
+    .await?;
+    render_keys(&v, &ctx, &jwt_user.user, Some(&key)).await
+}
+
+#[debug_handler]
+async fn rotate_api_key(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = api_keys::Model::find_by_id_and_user_id(&ctx.db, id, jwt_user.user.id).await?;
+    let (_, key) = item.rotate(&ctx.db).await?;
+    render_keys(&v, &ctx, &jwt_user.user, Some(&key)).await
+}
+
+#[debug_handler]
+async fn revoke_api_key(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = api_keys::Model::find_by_id_and_user_id(&ctx.db, id, jwt_user.user.id).await?;
+    item.revoke(&ctx.db).await?;
+    render_keys(&v, &ctx, &jwt_user.user, None).await
+}
+
+#[debug_handler]
+async fn webhooks(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let items = webhooks::Model::list_by_user_id(&ctx.db, jwt_user.user.id).await?;
+    views::settings::webhooks(&v, &items, &WebhookEvent::ALL, &jwt_user.user.username)
+}
+
+#[debug_handler]
+async fn create_webhook(
+    jwt_user: JWTWithUser<users::Model>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<CreateWebhookParams>,
+) -> Result<Response> {
+    let allow_private = Settings::from_config(&ctx.config)?
+        .webhooks
+        .allow_private_addresses;
+    webhooks::Model::create(
+        &ctx.db,
+        jwt_user.user.id,
+        &params.url,
+        &params.events,
+        allow_private,
+    )
+    .await
+    .map_err(model_error)?;
+
+    let items = webhooks::Model::list_by_user_id(&ctx.db, jwt_user.user.id).await?;
+    views::settings::webhooks_list(&v, &items)
+}
+
+#[debug_handler]
+async fn delete_webhook(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = webhooks::Model::find_by_id_and_user_id(&ctx.db, id, jwt_user.user.id).await?;
+    item.delete(&ctx.db).await?;
+
+    let items = webhooks::Model::list_by_user_id(&ctx.db, jwt_user.user.id).await?;
+    views::settings::webhooks_list(&v, &items)
+}
+
+#[debug_handler]
+async fn show_webhook(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    Path(id): Path<Uuid>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let Ok(item) = webhooks::Model::find_by_id_and_user_id(&ctx.db, id, jwt_user.user.id).await
+    else {
+        return views::index::not_found(&v, &jwt_user.user.username);
+    };
+    let deliveries = webhook_deliveries::Model::list_recent_by_webhook_id(&ctx.db, item.id).await?;
+    views::settings::webhook(&v, &item, &deliveries, &jwt_user.user.username)
+}
+
+#[debug_handler]
+async fn redeliver(
+    jwt_user: JWTWithUser<users::Model>,
+    Path((id, delivery_id)): Path<(Uuid, Uuid)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = webhooks::Model::find_by_id_and_user_id(&ctx.db, id, jwt_user.user.id).await?;
+    let delivery =
+        webhook_deliveries::Model::find_by_id_and_webhook_id(&ctx.db, delivery_id, item.id)
+            .await?
+            .reset(&ctx.db)
+            .await?;
+    WebhookWorker::perform_later(
+        &ctx,
+        WebhookWorkerArgs {
+            delivery_id: delivery.id,
+        },
+    )
+    .await
+    .map_err(Report::new)?;
+
+    let deliveries = webhook_deliveries::Model::list_recent_by_webhook_id(&ctx.db, item.id).await?;
+    views::settings::webhook_deliveries(&v, &item, &deliveries)
+}
+
+#[debug_handler]
+async fn calendar(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let follows = dj_follows::Model::list_by_user_id(&ctx.db, jwt_user.user.id).await?;
+    views::settings::calendar(&v, &jwt_user.user, &follows, &ctx.config.server.full_url())
+}
+
+#[debug_handler]
+async fn reset_calendar_token(
+    jwt_user: JWTWithUser<users::Model>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let mut user = jwt_user.user;
+    user.reset_calendar_token(&ctx.db).await?;
+    views::settings::calendar_url(&v, &user, &ctx.config.server.full_url())
+}
+
+#[debug_handler]
+async fn unfollow_dj(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(slug): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    dj_follows::Model::unfollow(&ctx.db, jwt_user.user.id, &slug).await?;
+    let follows = dj_follows::Model::list_by_user_id(&ctx.db, jwt_user.user.id).await?;
+    views::settings::calendar_follows(&v, &follows)
+}
+
+#[debug_handler]
+async fn notifications(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let Some(jwt_user) = jwt_user else {
+        return views::index::unauthorized(&v);
+    };
+    let prefs = email_preferences::Model::find_or_create(&ctx.db, jwt_user.user.id).await?;
+    views::settings::notifications(&v, &prefs, &jwt_user.user.username)
+}
+
+#[debug_handler]
+async fn update_notifications(
+    jwt_user: JWTWithUser<users::Model>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<NotificationParams>,
+) -> Result<Response> {
+    let mut prefs = email_preferences::Model::find_or_create(&ctx.db, jwt_user.user.id).await?;
+    for topic in Topic::ALL {
+        prefs.set_enabled(topic, params.topics.contains(&topic));
+    }
+    prefs.update(&ctx.db).await?;
+    views::settings::notification_topics(&v, &prefs, true)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("settings")
+        .add("/api_keys", get(api_keys))
+        .add("/api_keys", post(create_api_key))
+        .add("/api_keys/:id", delete(revoke_api_key))
+        .add("/api_keys/:id/rotate", post(rotate_api_key))
+        .add("/webhooks", get(webhooks))
+        .add("/webhooks", post(create_webhook))
+        .add("/webhooks/:id", get(show_webhook))
+        .add("/webhooks/:id", delete(delete_webhook))
+        .add(
+            "/webhooks/:id/deliveries/:delivery_id/redeliver",
+            post(redeliver),
+        )
+        .add("/calendar", get(calendar))
+        .add("/calendar/token", post(reset_calendar_token))
+        .add("/calendar/djs/:slug", delete(unfollow_dj))
+        .add("/notifications", get(notifications))
+        .add("/notifications", post(update_notifications))
+}
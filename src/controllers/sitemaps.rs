@@ -0,0 +1,144 @@
+use std::future::Future;
+
+use axum::{debug_handler, http::header};
+use eyre::Report;
+use loco_rs::prelude::*;
+
+use crate::{
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::sitemaps::Section,
+    settings::Settings,
+    views::{
+        self,
+        sitemaps::{TEXT_CONTENT_TYPE, XML_CONTENT_TYPE},
+    },
+};
+
+const INDEX_KEY: &str = "sitemap:index";
+/// Names of the pages the cached index lists, one per line.
+const PAGES_KEY: &str = "sitemap:pages";
+
+fn page_key(name: &str) -> String {
+    format!("sitemap:{name}")
+}
+
+/// Serves `content` from the cache, rendering and caching it when missing.
+async fn cached(
+    ctx: &AppContext,
+    key: &str,
+    render: impl Future<Output = Result<String>> + Send,
+) -> Result<String> {
+    if let Some(content) = ctx.cache.get(key).await.map_err(Report::new)? {
+        return Ok(content);
+    }
+    let content = render.await?;
+    ctx.cache.insert(key, &content).await.map_err(Report::new)?;
+    Ok(content)
+}
+
+/// Names of the pages of sitemaps, like `sets-1`, as many per section as its
+/// entries need.
+async fn pages(ctx: &AppContext) -> Result<Vec<String>> {
+    let pages = cached(ctx, PAGES_KEY, async {
+        let page_size = Settings::from_config(&ctx.config)?.sitemaps.page_size;
+        let mut pages = Vec::new();
+        for section in Section::ALL {
+            let count = section.count(&ctx.db).await?;
+            for page in 1..=(count + page_size - 1) / page_size {
+                pages.push(format!("{}-{page}", section.name()));
+            }
+        }
+        Ok(pages.join("\n"))
+    })
+    .await?;
+    Ok(pages.lines().map(str::to_string).collect())
+}
+
+/// Forgets the cached sitemaps, to be called after writing anything they list.
+/// Failures are only logged, the write itself succeeded.
+pub async fn invalidate(ctx: &AppContext) {
+    let result: Result<()> = async {
+        if let Some(pages) = ctx.cache.get(PAGES_KEY).await.map_err(Report::new)? {
+            for name in pages.lines() {
+                ctx.cache
+                    .remove(&page_key(name))
+                    .await
+                    .map_err(Report::new)?;
+            }
+        }
+        ctx.cache.remove(PAGES_KEY).await.map_err(Report::new)?;
+        ctx.cache.remove(INDEX_KEY).await.map_err(Report::new)?;
+        Ok(())
+    }
+    .await;
+    if let Err(err) = result {
+        tracing::error!(error = err.to_string(), "could not invalidate sitemaps");
+    }
+}
+
+fn xml(content: String) -> Result<Response> {
+    format::render()
+        .header(header::CONTENT_TYPE, XML_CONTENT_TYPE)
+        .header(header::CACHE_CONTROL, "public, max-age=3600")
+        .response()
+        .body(content.into())
+        .map_err(Into::into)
+}
+
+#[debug_handler]
+async fn index(
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let content = cached(&ctx, INDEX_KEY, async {
+        views::sitemaps::index(&v, &pages(&ctx).await?)
+    })
+    .await?;
+    xml(content)
+}
+
+#[debug_handler]
+async fn page(
+    Path(file): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let name = file.strip_suffix(".xml").ok_or(Error::NotFound)?;
+    if !pages(&ctx).await?.iter().any(|page| page == name) {
+        return Err(Error::NotFound);
+    }
+    let (section, page) = name.rsplit_once('-').ok_or(Error::NotFound)?;
+    let section = Section::from_name(section).ok_or(Error::NotFound)?;
+    let page = page.parse::<i64>().map_err(|_| Error::NotFound)?;
+
+    let content = cached(&ctx, &page_key(name), async {
+        let page_size = Settings::from_config(&ctx.config)?.sitemaps.page_size;
+        let entries = section
+            .list_page(&ctx.db, page_size, (page - 1) * page_size)
+            .await?;
+        views::sitemaps::urlset(&v, &ctx.config.server.full_url(), section, &entries)
+    })
+    .await?;
+    xml(content)
+}
+
+#[debug_handler]
+async fn robots(
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let disallow = Settings::from_config(&ctx.config)?.robots.disallow;
+    format::render()
+        .header(header::CONTENT_TYPE, TEXT_CONTENT_TYPE)
+        .header(header::CACHE_CONTROL, "public, max-age=86400")
+        .response()
+        .body(views::sitemaps::robots(&v, &disallow)?.into())
+        .map_err(Into::into)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .add("/sitemap.xml", get(index))
+        .add("/sitemaps/:file", get(page))
+        .add("/robots.txt", get(robots))
+}
@@ -0,0 +1,50 @@
+use auth::JWTWithUser;
+use axum::{debug_handler, extract::Query};
+use chrono::Utc;
+use loco_rs::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{stats, users},
+    utils::get_username,
+    views::{self, stats::Stats},
+};
+
+/// How many tracks, artists, DJs and venues each ranking lists.
+const RANKING_LENGTH: i64 = 10;
+/// How many months the monthly charts cover, up to the current one.
+const CHART_MONTHS: u32 = 24;
+
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    #[serde(default)]
+    pub window: stats::Window,
+}
+
+/// Statistics are read from what `StatsWorker` last computed.
+#[debug_handler]
+async fn index(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    Query(query): Query<StatsQuery>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user_name = get_username(jwt_user).unwrap_or_default();
+    let window = query.window;
+    let today = Utc::now().date_naive();
+    let since = views::stats::months_since(today, CHART_MONTHS);
+    let stats = Stats {
+        summary: stats::find_summary(&ctx.db, window).await?,
+        tracks: stats::list_top_tracks(&ctx.db, window, RANKING_LENGTH).await?,
+        artists: stats::list_top_artists(&ctx.db, window, RANKING_LENGTH).await?,
+        djs: stats::list_top_djs(&ctx.db, window, RANKING_LENGTH).await?,
+        venues: stats::list_top_venues(&ctx.db, window, RANKING_LENGTH).await?,
+        months: stats::list_months(&ctx.db, since).await?,
+    };
+    views::stats::index(&v, window, stats, since, today, &user_name)
+}
+
+pub fn routes() -> Routes {
+    Routes::new().add("/stats", get(index))
+}
@@ -0,0 +1,128 @@
+use auth::JWTWithUser;
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    controllers::sitemaps,
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{sets, tags, users},
+    utils::{forbidden, hx_redirect_to, model_error, slugify},
+    views,
+};
+
+/// How many tags are suggested while typing.
+const SUGGESTIONS: i64 = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    /// The value of the comma separated tag input.
+    #[serde(default)]
+    pub tags: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MergeParams {
+    /// Name of the tag to merge into.
+    pub into: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenreParams {
+    pub is_genre: bool,
+}
+
+fn redirect_to(tag: &tags::Model) -> Result<Response> {
+    hx_redirect_to(format!("/tags/{}", tag.slug))
+}
+
+/// Finds a tag for a moderator to change.
+async fn load_for_moderator(
+    ctx: &AppContext,
+    user: &users::Model,
+    slug: &str,
+) -> Result<Option<tags::Model>> {
+    if !user.is_moderator {
+        return Ok(None);
+    }
+    Ok(Some(tags::Model::find_by_slug(&ctx.db, slug).await?))
+}
+
+#[debug_handler]
+async fn suggest(
+    Query(query): Query<SuggestQuery>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let typed = query.tags.rsplit(',').next().unwrap_or_default().trim();
+    let tags = tags::Model::suggest(&ctx.db, typed, SUGGESTIONS).await?;
+    views::tags::suggestions(&v, &query.tags, &tags)
+}
+
+/// Synonyms redirect to the tag they were merged into.
+#[debug_handler]
+async fn show(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    Path(slug): Path<String>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let own_user = jwt_user.as_ref().map(|jwt_user| &jwt_user.user);
+    let user_name = own_user
+        .map(|user| user.username.as_str())
+        .unwrap_or_default();
+    let Ok(tag) = tags::Model::find_by_slug(&ctx.db, &slug).await else {
+        return views::index::not_found(&v, user_name);
+    };
+    if let Some(id) = tag.merged_into_id {
+        let target = tags::Model::find_by_id(&ctx.db, id).await?;
+        return format::render().redirect(&format!("/tags/{}", target.slug));
+    }
+
+    let synonyms = tag.list_synonyms(&ctx.db).await?;
+    let sets = sets::Model::list_by_tag_id(&ctx.db, tag.id).await?;
+    let is_moderator = own_user.is_some_and(|user| user.is_moderator);
+    views::tags::show(&v, &tag, &synonyms, &sets, is_moderator, user_name)
+}
+
+/// Moderators merge synonyms, like "dnb" into "Drum & Bass".
+#[debug_handler]
+async fn merge(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(slug): Path<String>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<MergeParams>,
+) -> Result<Response> {
+    let Some(mut tag) = load_for_moderator(&ctx, &jwt_user.user, &slug).await? else {
+        return forbidden();
+    };
+    let target = tags::Model::find_by_slug(&ctx.db, &slugify(&params.into)).await?;
+    tag.merge_into(&ctx.db, &target)
+        .await
+        .map_err(model_error)?;
+    sitemaps::invalidate(&ctx).await;
+    redirect_to(&target)
+}
+
+#[debug_handler]
+async fn genre(
+    jwt_user: JWTWithUser<users::Model>,
+    Path(slug): Path<String>,
+    State(ctx): State<AppContext>,
+    Json(params): Json<GenreParams>,
+) -> Result<Response> {
+    let Some(mut tag) = load_for_moderator(&ctx, &jwt_user.user, &slug).await? else {
+        return forbidden();
+    };
+    tag.set_genre(&ctx.db, params.is_genre).await?;
+    redirect_to(&tag)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("tags")
+        .add("/suggest", get(suggest))
+        .add("/:slug", get(show))
+        .add("/:slug/merge", post(merge))
+        .add("/:slug/genre", put(genre))
+}
@@ -0,0 +1,97 @@
+use auth::JWTWithUser;
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{transitions, users},
+    utils::get_username,
+    views,
+};
+
+/// How many tracks played before and after a track are shown.
+const NEIGHBOURS: i64 = 10;
+/// How many transitions the explorer starts with.
+const TOP_TRANSITIONS: i64 = 20;
+/// How many tracks a search of the explorer finds.
+const SEARCH_RESULTS: i64 = 10;
+
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    #[serde(default)]
+    pub q: String,
+}
+
+async fn load(
+    ctx: &AppContext,
+    artist: &str,
+    title: &str,
+) -> Result<(
+    transitions::Track,
+    Vec<transitions::Neighbour>,
+    Vec<transitions::Neighbour>,
+)> {
+    let track = transitions::find_track(&ctx.db, artist, title).await?;
+    let before = transitions::list_before(&ctx.db, artist, title, NEIGHBOURS).await?;
+    let after = transitions::list_after(&ctx.db, artist, title, NEIGHBOURS).await?;
+    Ok((track, before, after))
+}
+
+/// Transitions are read from the graph `StatsWorker` last computed.
+#[debug_handler]
+async fn show(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    Path((artist, title)): Path<(String, String)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user_name = get_username(jwt_user).unwrap_or_default();
+    let Ok((track, before, after)) = load(&ctx, &artist, &title).await else {
+        return views::index::not_found(&v, &user_name);
+    };
+    views::tracks::show(&v, &track, &before, &after, &user_name)
+}
+
+#[debug_handler]
+async fn graph(
+    Path((artist, title)): Path<(String, String)>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let (track, before, after) = load(&ctx, &artist, &title).await?;
+    views::tracks::graph_fragment(&v, &track, &before, &after)
+}
+
+#[debug_handler]
+async fn explorer(
+    jwt_user: Option<JWTWithUser<users::Model>>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let user_name = get_username(jwt_user).unwrap_or_default();
+    let top = transitions::list_page(&ctx.db, TOP_TRANSITIONS, 0).await?;
+    views::tracks::explorer(&v, &top, &user_name)
+}
+
+#[debug_handler]
+async fn search(
+    Query(query): Query<SearchQuery>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let tracks = if query.q.trim().is_empty() {
+        Vec::new()
+    } else {
+        transitions::search_tracks(&ctx.db, &query.q, SEARCH_RESULTS).await?
+    };
+    views::tracks::search_results(&v, &tracks)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .add("/tracks/:artist/:title", get(show))
+        .add("/tracks/:artist/:title/graph", get(graph))
+        .add("/transitions", get(explorer))
+        .add("/transitions/search", get(search))
+}
@@ -0,0 +1,53 @@
+use axum::{debug_handler, extract::Query};
+use loco_rs::prelude::*;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::email_preferences::{self, Topic},
+    views,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct UnsubscribeQuery {
+    /// Unsubscribes from every topic when missing.
+    pub topic: Option<Topic>,
+}
+
+/// Asks for confirmation, so that link scanners following the address do not
+/// unsubscribe anyone.
+#[debug_handler]
+async fn confirm(
+    Path(token): Path<Uuid>,
+    Query(query): Query<UnsubscribeQuery>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    email_preferences::Model::find_by_unsubscribe_token(&ctx.db, token).await?;
+    views::settings::unsubscribe(&v, &token.to_string(), query.topic, false)
+}
+
+/// Also the target of one-click unsubscribes from mail clients (RFC 8058).
+#[debug_handler]
+async fn unsubscribe(
+    Path(token): Path<Uuid>,
+    Query(query): Query<UnsubscribeQuery>,
+    ViewEngine(v): ViewEngine<MiniJinjaView>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let mut prefs = email_preferences::Model::find_by_unsubscribe_token(&ctx.db, token).await?;
+    let topics = query.topic.map_or(Topic::ALL.to_vec(), |topic| vec![topic]);
+    for topic in topics {
+        prefs.set_enabled(topic, false);
+    }
+    prefs.update(&ctx.db).await?;
+    views::settings::unsubscribe(&v, &token.to_string(), query.topic, true)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("unsubscribe")
+        .add("/:token", get(confirm))
+        .add("/:token", post(unsubscribe))
+}
@@ -0,0 +1,255 @@
+use std::{
+    ops::Range,
+    sync::atomic::{AtomicI64, Ordering},
+};
+
+use axum::{
+    body::{Body, Bytes},
+    debug_handler,
+    http::{header, HeaderMap, StatusCode},
+};
+use futures_util::{Stream, StreamExt as _};
+use loco_rs::{
+    controller::ErrorDetail,
+    prelude::*,
+    storage::{drivers::StoreDriver, StorageError},
+};
+use uuid::Uuid;
+
+use crate::{
+    models::{uploads, users},
+    settings::Settings,
+};
+
+/// Name of the store uploads are kept in.
+pub const STORE: &str = "uploads";
+
+fn store(ctx: &AppContext) -> Result<&dyn StoreDriver> {
+    Ok(ctx.storage.as_store_err(STORE)?)
+}
+
+fn over_quota<T>() -> Result<T> {
+    Err(Error::CustomError(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        ErrorDetail::new("over_quota", "This upload would exceed your quota"),
+    ))
+}
+
+/// Streams a file into storage as an upload of `user`, as long as it fits in
+/// what is left of their quota.
+///
+/// # Errors
+///
+/// When the file is empty or too large, or could not be stored
+pub async fn create<E>(
+    ctx: &AppContext,
+    user: &users::Model,
+    content: impl Stream<Item = Result<Bytes, E>> + Send,
+    content_type: &str,
+) -> Result<uploads::Model>
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    let quota = Settings::from_config(&ctx.config)?.uploads.quota;
+    let left = quota - uploads::Model::used_bytes(&ctx.db, user.id).await?;
+    let id = Uuid::now_v7();
+    let path = uploads::storage_path(id);
+
+    let size = AtomicI64::new(0);
+    let stream = content.map(|chunk| {
+        let chunk = chunk.map_err(|err| StorageError::Any(err.into()))?;
+        #[allow(clippy::cast_possible_wrap)]
+        let len = chunk.len() as i64;
+        if size.fetch_add(len, Ordering::Relaxed) + len > left {
+            return Err(StorageError::Any("upload exceeds the quota".into()));
+        }
+        Ok(chunk)
+    });
+    let stored = store(ctx)?.upload_stream(&path, stream.boxed()).await;
+    let size = size.into_inner();
+    if size > left {
+        return over_quota();
+    }
+    stored?;
+    if size == 0 {
+        store(ctx)?.delete(&path).await?;
+        return Err(Error::BadRequest("The file is empty".to_string()));
+    }
+
+    match uploads::Model::create(&ctx.db, id, user.id, content_type, size, quota).await {
+        Ok(item) => Ok(item),
+        Err(err) => {
+            store(ctx)?.delete(&path).await?;
+            match err {
+                ModelError::Any(_) => over_quota(),
+                err => Err(err.into()),
+            }
+        }
+    }
+}
+
+/// Deletes an upload along with what links to it and its content.
+///
+/// # Errors
+///
+/// When could not find or delete the upload
+pub async fn delete(ctx: &AppContext, id: Uuid) -> Result<()> {
+    let item = uploads::Model::find_by_id(&ctx.db, id).await?;
+    remove(ctx, &item).await
+}
+
+async fn remove(ctx: &AppContext, item: &uploads::Model) -> Result<()> {
+    uploads::Model::delete(&ctx.db, item.id).await?;
+    let store = store(ctx)?;
+    store.delete(&item.storage_path()).await?;
+    for variant in &item.variants {
+        store.delete(&item.variant_path(variant)).await?;
+    }
+    Ok(())
+}
+
+/// Deletes the uploaded recordings of a set, before the set goes.
+///
+/// # Errors
+///
+/// When could not delete an upload
+pub async fn delete_for_set(ctx: &AppContext, set_id: Uuid) -> Result<()> {
+    for item in uploads::Model::list_by_set_id(&ctx.db, set_id).await? {
+        remove(ctx, &item).await?;
+    }
+    Ok(())
+}
+
+/// What a `Range` header asks for.
+enum Requested {
+    Whole,
+    Part(Range<usize>),
+    Unsatisfiable,
+}
+
+/// Reads a `Range` header for a file of `size` bytes. Only single byte ranges
+/// are supported, the whole file is sent for anything else.
+fn requested_range(headers: &HeaderMap, size: usize) -> Requested {
+    let Some(spec) = headers
+        .get(header::RANGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("bytes="))
+        .filter(|spec| !spec.contains(','))
+    else {
+        return Requested::Whole;
+    };
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return Requested::Whole;
+    };
+    if start.is_empty() {
+        // the last `end` bytes
+        return match end.parse::<usize>() {
+            Ok(0) => Requested::Unsatisfiable,
+            Ok(suffix) => Requested::Part(size.saturating_sub(suffix)..size),
+            Err(_) => Requested::Whole,
+        };
+    }
+    let Ok(start) = start.parse::<usize>() else {
+        return Requested::Whole;
+    };
+    if start >= size {
+        return Requested::Unsatisfiable;
+    }
+    if end.is_empty() {
+        return Requested::Part(start..size);
+    }
+    match end.parse::<usize>() {
+        Ok(end) if end >= start => Requested::Part(start..size.min(end + 1)),
+        _ => Requested::Whole,
+    }
+}
+
+/// Serves an upload, in parts when asked so that players can seek.
+#[debug_handler]
+async fn show(
+    Path(id): Path<Uuid>,
+    State(ctx): State<AppContext>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let item = uploads::Model::find_by_id(&ctx.db, id).await?;
+    let size = usize::try_from(item.size).map_err(|err| Error::Message(err.to_string()))?;
+    let path = item.storage_path();
+    let response = Response::builder()
+        .header(header::CONTENT_TYPE, &item.content_type)
+        // the type was given by the uploader, browsers must not guess another
+        .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+        .header(header::ACCEPT_RANGES, "bytes")
+        // uploads never change
+        .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable");
+
+    let response = match requested_range(&headers, size) {
+        Requested::Whole => {
+            let content = store(&ctx)?.get(&path).await?;
+            response
+                .header(header::CONTENT_LENGTH, size)
+                .body(Body::from_stream(content.into_stream()))
+        }
+        Requested::Part(range) => {
+            let content_range = format!("bytes {}-{}/{size}", range.start, range.end - 1);
+            let content_length = range.len();
+            let content = store(&ctx)?.get_range(&path, range).await?;
+            response
+                .status(StatusCode::PARTIAL_CONTENT)
+                .header(header::CONTENT_RANGE, content_range)
+                .header(header::CONTENT_LENGTH, content_length)
+                .body(Body::from_stream(content.into_stream()))
+        }
+        Requested::Unsatisfiable => response
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{size}"))
+            .body(Body::empty()),
+    };
+    Ok(response?)
+}
+
+/// The type of a variant, by its extension.
+fn variant_content_type(variant: &str) -> Option<&'static str> {
+    match variant.rsplit_once('.')?.1 {
+        "jpg" => Some("image/jpeg"),
+        "webp" => Some("image/webp"),
+        "json" => Some("application/json"),
+        _ => None,
+    }
+}
+
+/// Serves a file derived from an upload. Until an image is resized, the
+/// original stands in for it.
+#[debug_handler]
+async fn show_variant(
+    Path((id, variant)): Path<(Uuid, String)>,
+    State(ctx): State<AppContext>,
+) -> Result<Response> {
+    let item = uploads::Model::find_by_id(&ctx.db, id).await?;
+    let content_type = variant_content_type(&variant).ok_or(Error::NotFound)?;
+    let response = if item.has_variant(&variant) {
+        let content = store(&ctx)?.get(&item.variant_path(&variant)).await?;
+        Response::builder()
+            .header(header::CONTENT_TYPE, content_type)
+            .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+            // variants never change either
+            .header(header::CACHE_CONTROL, "public, max-age=31536000, immutable")
+            .body(Body::from_stream(content.into_stream()))
+    } else if content_type.starts_with("image/") && item.content_type.starts_with("image/") {
+        let content = store(&ctx)?.get(&item.storage_path()).await?;
+        Response::builder()
+            .header(header::CONTENT_TYPE, &item.content_type)
+            .header(header::X_CONTENT_TYPE_OPTIONS, "nosniff")
+            .header(header::CACHE_CONTROL, "no-cache")
+            .body(Body::from_stream(content.into_stream()))
+    } else {
+        return Err(Error::NotFound);
+    };
+    Ok(response?)
+}
+
+pub fn routes() -> Routes {
+    Routes::new()
+        .prefix("uploads")
+        .add("/:id", get(show))
+        .add("/:id/:variant", get(show_variant))
+}
@@ -1,26 +1,68 @@
 use auth::JWTWithUser;
-use axum::debug_handler;
+use axum::{debug_handler, extract::Query};
 use loco_rs::prelude::*;
+use serde::Deserialize;
 
 use crate::{
-    initializers::minijinja_view_engine::MiniJinjaView, models::users, utils::get_username, views,
+    initializers::minijinja_view_engine::MiniJinjaView,
+    models::{collections, sets, user_follows, users},
+    views::{self, follows::Follow, user::Tab},
 };
 
+#[derive(Debug, Deserialize)]
+pub struct ProfileQuery {
+    #[serde(default)]
+    pub tab: Tab,
+}
+
 #[debug_handler]
 async fn user(
     jwt_user: Option<JWTWithUser<users::Model>>,
     Path(username): Path<String>,
+    Query(query): Query<ProfileQuery>,
     ViewEngine(v): ViewEngine<MiniJinjaView>,
     State(ctx): State<AppContext>,
 ) -> Result<Response> {
     let user = users::Model::find_by_username(&ctx.db, &username).await;
-    let own_user = get_username(jwt_user).unwrap_or_default();
-    if let Ok(user) = user {
-        let sets = crate::models::sets::Model::list_by_creator_id(&ctx.db, user.id).await?;
-        views::user::sets(&v, &user.username, &sets, &own_user)
-    } else {
-        views::index::not_found(&v, &own_user)
-    }
+    let own_user = jwt_user.as_ref().map(|jwt_user| &jwt_user.user);
+    let own_user_name = own_user
+        .map(|own_user| own_user.username.as_str())
+        .unwrap_or_default();
+    let Ok(user) = user else {
+        return views::index::not_found(&v, own_user_name);
+    };
+    let is_own_profile = own_user.is_some_and(|own_user| own_user.id == user.id);
+    let mut collections = vec![];
+    let sets = match query.tab {
+        Tab::Sets => sets::Model::list_by_creator_id(&ctx.db, user.id, is_own_profile).await?,
+        // where someone was is their own history, not for everyone to see
+        Tab::Attended if is_own_profile => sets::Model::list_attended_by(&ctx.db, user.id).await?,
+        Tab::Attended => return views::index::not_found(&v, own_user_name),
+        Tab::Liked => sets::Model::list_liked_by(&ctx.db, user.id).await?,
+        Tab::Collections => {
+            // private collections are only listed to their owner
+            collections =
+                collections::Model::list_by_owner_id(&ctx.db, user.id, is_own_profile).await?;
+            vec![]
+        }
+    };
+    // users cannot follow themselves
+    let follow = match own_user.filter(|own_user| own_user.id != user.id) {
+        Some(own_user) => {
+            let followed = user_follows::Model::is_following(&ctx.db, own_user.id, user.id).await?;
+            Some(Follow::user(&user.username, followed))
+        }
+        None => None,
+    };
+    views::user::sets(
+        &v,
+        &user.username,
+        &sets,
+        &collections,
+        query.tab,
+        follow.as_ref(),
+        own_user_name,
+    )
 }
 
 pub fn routes() -> Routes {
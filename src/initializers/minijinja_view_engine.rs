@@ -6,7 +6,7 @@ use loco_rs::{
     prelude::*,
     Error, Result,
 };
-use minijinja::{path_loader, Environment};
+use minijinja::{default_auto_escape_callback, path_loader, AutoEscape, Environment};
 #[cfg(debug_assertions)]
 use minijinja_autoreload::AutoReloader;
 use serde::Serialize;
@@ -19,13 +19,26 @@ pub struct MiniJinjaView {
     env: Arc<Environment<'static>>,
 }
 
+/// Sets up templates to be loaded from `assets/views`, with `base_url` for
+/// links which leave the site.
+fn environment(template_path: &std::path::Path, base_url: &str) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_loader(path_loader(template_path));
+    env.set_auto_escape_callback(|name| match name.rsplit('.').next() {
+        // share images are drawn from user content too
+        Some("svg") => AutoEscape::Html,
+        _ => default_auto_escape_callback(name),
+    });
+    env.add_global("base_url", base_url);
+    env
+}
+
 impl MiniJinjaView {
     #[cfg(debug_assertions)]
-    fn new() -> Self {
+    fn new(base_url: String) -> Self {
         let reloader = AutoReloader::new(move |notifier| {
             let template_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/views");
-            let mut env = Environment::new();
-            env.set_loader(path_loader(&template_path));
+            let env = environment(&template_path, &base_url);
             notifier.watch_path(&template_path, true);
             notifier.set_fast_reload(true);
             Ok(env)
@@ -37,10 +50,9 @@ impl MiniJinjaView {
     }
 
     #[cfg(not(debug_assertions))]
-    fn new() -> Self {
+    fn new(base_url: String) -> Self {
         let template_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("assets/views");
-        let mut env = Environment::new();
-        env.set_loader(path_loader(&template_path));
+        let env = environment(&template_path, &base_url);
         Self { env: Arc::new(env) }
     }
 }
@@ -84,7 +96,8 @@ impl Initializer for ViewEngineInitializer {
         "view-engine".to_string()
     }
 
-    async fn after_routes(&self, router: AxumRouter, _ctx: &AppContext) -> Result<AxumRouter> {
-        Ok(router.layer(Extension(ViewEngine::from(MiniJinjaView::new()))))
+    async fn after_routes(&self, router: AxumRouter, ctx: &AppContext) -> Result<AxumRouter> {
+        let view = MiniJinjaView::new(ctx.config.server.full_url());
+        Ok(router.layer(Extension(ViewEngine::from(view))))
     }
 }
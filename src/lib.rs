@@ -3,6 +3,8 @@ pub mod controllers;
 pub mod initializers;
 pub mod mailers;
 pub mod models;
+pub mod services;
+pub mod settings;
 pub mod utils;
 pub mod views;
 pub mod workers;
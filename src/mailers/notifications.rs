@@ -0,0 +1,257 @@
+// notification mailer
+#![allow(non_upper_case_globals)]
+
+use std::collections::BTreeMap;
+
+use loco_rs::prelude::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        edit_proposals,
+        email_preferences::{self, Topic},
+        id_requests,
+        notifications::{self, NotificationKind},
+        sets::{self, Visibility},
+        songs, users,
+    },
+    utils::slugify,
+    views::feeds::set_title,
+};
+
+static new_set: Dir<'_> = include_dir!("src/mailers/notifications/new_set");
+static edit_proposal: Dir<'_> = include_dir!("src/mailers/notifications/edit_proposal");
+static identified: Dir<'_> = include_dir!("src/mailers/notifications/identified");
+static digest: Dir<'_> = include_dir!("src/mailers/notifications/digest");
+
+/// The link unsubscribing from `topic`, also used for one-click unsubscribe
+/// (RFC 8058).
+#[must_use]
+pub fn unsubscribe_url(
+    ctx: &AppContext,
+    preferences: &email_preferences::Model,
+    topic: Topic,
+) -> String {
+    format!(
+        "{}/unsubscribe/{}?topic={}",
+        ctx.config.server.full_url(),
+        preferences.unsubscribe_token,
+        topic.as_str()
+    )
+}
+
+#[allow(clippy::module_name_repetitions)]
+pub struct NotificationMailer {}
+impl Mailer for NotificationMailer {}
+impl NotificationMailer {
+    /// Sends a notification about `topic`, unless the user turned it off.
+    async fn send(
+        ctx: &AppContext,
+        user: &users::Model,
+        preferences: &email_preferences::Model,
+        topic: Topic,
+        dir: &Dir<'_>,
+        mut locals: serde_json::Value,
+    ) -> Result<()> {
+        if !preferences.is_enabled(topic) {
+            return Ok(());
+        }
+        let unsubscribe_url = unsubscribe_url(ctx, preferences, topic);
+        if let Some(locals) = locals.as_object_mut() {
+            locals.insert("username".to_string(), json!(user.username));
+            locals.insert("domain".to_string(), json!(ctx.config.server.full_url()));
+            locals.insert("unsubscribe_url".to_string(), json!(unsubscribe_url));
+        }
+
+        Self::mail_template(
+            ctx,
+            dir,
+            mailer::Args {
+                to: user.email.to_string(),
+                locals,
+                headers: vec![
+                    (
+                        "List-Unsubscribe".to_string(),
+                        format!("<{unsubscribe_url}>"),
+                    ),
+                    (
+                        "List-Unsubscribe-Post".to_string(),
+                        "List-Unsubscribe=One-Click".to_string(),
+                    ),
+                ],
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Tells the followers of the set's DJs about a new set, when it is
+    /// public.
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn set_created(ctx: &AppContext, set: &sets::Model) -> Result<()> {
+        if set.visibility() != Visibility::Public {
+            return Ok(());
+        }
+        let slugs = set
+            .dj_names
+            .iter()
+            .map(|name| slugify(name))
+            .collect::<Vec<_>>();
+        let followers = users::Model::list_following_djs(&ctx.db, &slugs).await?;
+        for user in followers.iter().filter(|user| user.id != set.creator_id) {
+            let preferences = email_preferences::Model::find_or_create(&ctx.db, user.id).await?;
+            Self::send(
+                ctx,
+                user,
+                &preferences,
+                Topic::FollowedSets,
+                &new_set,
+                json!({
+                    "title": set_title(set),
+                    "start_time": set.start_time,
+                    "set_id": set.id,
+                }),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Tells the owner of a set about a proposed edit, in their notification
+    /// center and by email.
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn edit_proposed(
+        ctx: &AppContext,
+        set: &sets::Model,
+        proposal: &edit_proposals::Model,
+        proposer: &users::Model,
+    ) -> Result<()> {
+        let owner = users::Model::find_by_id(&ctx.db, set.creator_id).await?;
+        notifications::Model::create(
+            &ctx.db,
+            owner.id,
+            NotificationKind::EditProposal,
+            &format!(
+                "{} proposed an edit to {}",
+                proposer.username,
+                set_title(set)
+            ),
+            &format!("/sets/{}#proposals", set.id),
+        )
+        .await?;
+        let preferences = email_preferences::Model::find_or_create(&ctx.db, owner.id).await?;
+        Self::send(
+            ctx,
+            &owner,
+            &preferences,
+            Topic::EditProposals,
+            &edit_proposal,
+            json!({
+                "title": set_title(set),
+                "set_id": set.id,
+                "proposer": proposer.username,
+                "message": proposal.message,
+            }),
+        )
+        .await
+    }
+
+    /// Tells the users who asked about tracks of `after` that were
+    /// unidentified in `before` that they are identified now, in their
+    /// notification center and by email.
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn tracks_identified(
+        ctx: &AppContext,
+        set: &sets::Model,
+        before: &[songs::Model],
+        after: &[songs::Model],
+    ) -> Result<()> {
+        let tracks = songs::Model::newly_identified(before, after);
+        let track_numbers = tracks
+            .iter()
+            .filter_map(|song| song.track_number)
+            .collect::<Vec<_>>();
+        if track_numbers.is_empty() {
+            return Ok(());
+        }
+
+        let requests = id_requests::Model::take_for_tracks(&ctx.db, set.id, &track_numbers).await?;
+        let mut by_user = BTreeMap::<Uuid, Vec<&songs::Model>>::new();
+        for request in &requests {
+            let song = tracks
+                .iter()
+                .find(|song| song.track_number == Some(request.track_number));
+            if let Some(song) = song {
+                by_user.entry(request.user_id).or_default().push(song);
+            }
+        }
+
+        for (user_id, tracks) in by_user {
+            let user = users::Model::find_by_id(&ctx.db, user_id).await?;
+            let names = tracks
+                .iter()
+                .map(|song| format!("{} - {}", song.track_artist, song.track_title))
+                .collect::<Vec<_>>()
+                .join(", ");
+            notifications::Model::create(
+                &ctx.db,
+                user.id,
+                NotificationKind::TrackIdentified,
+                &format!("Identified in {}: {names}", set_title(set)),
+                &format!("/sets/{}", set.id),
+            )
+            .await?;
+            let preferences = email_preferences::Model::find_or_create(&ctx.db, user.id).await?;
+            Self::send(
+                ctx,
+                &user,
+                &preferences,
+                Topic::IdentifiedTracks,
+                &identified,
+                json!({
+                    "title": set_title(set),
+                    "set_id": set.id,
+                    "tracks": tracks,
+                }),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Sends the weekly digest of `items`.
+    ///
+    /// # Errors
+    ///
+    /// When email sending is failed
+    pub async fn digest(
+        ctx: &AppContext,
+        user: &users::Model,
+        preferences: &email_preferences::Model,
+        items: &[sets::Model],
+    ) -> Result<()> {
+        let sets = items
+            .iter()
+            .map(|set| json!({"id": set.id, "title": set_title(set), "start_time": set.start_time}))
+            .collect::<Vec<_>>();
+        Self::send(
+            ctx,
+            user,
+            preferences,
+            Topic::WeeklyDigest,
+            &digest,
+            json!({"sets": sets}),
+        )
+        .await
+    }
+}
@@ -0,0 +1,16 @@
+<html>
+
+<body>
+  <p>Hi {{username|e}},</p>
+  <p>New and updated sets from the DJs, venues and users you follow:</p>
+  <ul>
+    {% for set in sets %}
+    <li><a href="{{domain}}/sets/{{set.id}}">{{set.title|e}}</a>, {{set.start_time}}</li>
+    {% endfor %}
+  </ul>
+  <p><a href="{{domain}}/feed">See everything</a></p>
+  <p><br>SetListList</p>
+  <p><small><a href="{{unsubscribe_url}}">Stop the weekly digest</a></small></p>
+</body>
+
+</html>
@@ -0,0 +1 @@
+Your week on SetListList: {{sets|length}} new sets
@@ -0,0 +1,10 @@
+Hi {{username}},
+
+New and updated sets from the DJs, venues and users you follow:
+{% for set in sets %}
+- {{set.title}}, {{set.start_time}}
+  {{domain}}/sets/{{set.id}}{% endfor %}
+
+See everything at {{domain}}/feed
+
+Stop the weekly digest: {{unsubscribe_url}}
@@ -0,0 +1,12 @@
+<html>
+
+<body>
+  <p>Hi {{username|e}},</p>
+  <p>{{proposer|e}} proposed an edit to your set {{title|e}}:</p>
+  <blockquote>{{message|e}}</blockquote>
+  <p><a href="{{domain}}/sets/{{set_id}}">Review it</a></p>
+  <p><br>SetListList</p>
+  <p><small><a href="{{unsubscribe_url}}">Stop these emails</a></small></p>
+</body>
+
+</html>
@@ -0,0 +1 @@
+{{proposer}} proposed an edit to {{title}}
@@ -0,0 +1,9 @@
+Hi {{username}},
+
+{{proposer}} proposed an edit to your set {{title}}:
+
+{{message}}
+
+Review it at {{domain}}/sets/{{set_id}}
+
+Stop these emails: {{unsubscribe_url}}
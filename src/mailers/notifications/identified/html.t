@@ -0,0 +1,15 @@
+<html>
+
+<body>
+  <p>Hi {{username|e}},</p>
+  <p>Tracks you asked about in <a href="{{domain}}/sets/{{set_id}}">{{title|e}}</a> were identified:</p>
+  <ol>
+    {% for track in tracks %}
+    <li value="{{track.track_number}}">{{track.track_artist|e}} – {{track.track_title|e}}</li>
+    {% endfor %}
+  </ol>
+  <p><br>SetListList</p>
+  <p><small><a href="{{unsubscribe_url}}">Stop these emails</a></small></p>
+</body>
+
+</html>
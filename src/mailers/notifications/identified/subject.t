@@ -0,0 +1 @@
+IDs identified in {{title}}
@@ -0,0 +1,9 @@
+Hi {{username}},
+
+Tracks you asked about in {{title}} were identified:
+{% for track in tracks %}
+{{track.track_number}}. {{track.track_artist}} – {{track.track_title}}{% endfor %}
+
+{{domain}}/sets/{{set_id}}
+
+Stop these emails: {{unsubscribe_url}}
@@ -0,0 +1,14 @@
+<html>
+
+<body>
+  <p>Hi {{username|e}},</p>
+  <p>There is a new set by a DJ you follow:</p>
+  <p>
+    <a href="{{domain}}/sets/{{set_id}}">{{title|e}}</a><br>
+    {{start_time}}
+  </p>
+  <p><br>SetListList</p>
+  <p><small><a href="{{unsubscribe_url}}">Stop these emails</a></small></p>
+</body>
+
+</html>
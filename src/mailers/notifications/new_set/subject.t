@@ -0,0 +1 @@
+New set: {{title}}
@@ -0,0 +1,9 @@
+Hi {{username}},
+
+There is a new set by a DJ you follow:
+
+{{title}}
+{{start_time}}
+{{domain}}/sets/{{set_id}}
+
+Stop these emails: {{unsubscribe_url}}
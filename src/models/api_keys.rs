@@ -0,0 +1,267 @@
+use chrono::{NaiveDateTime, TimeDelta, Utc};
+use loco_rs::{
+    controller::middleware::auth::Scope,
+    model::{ModelError, ModelResult},
+};
+use rand::{distributions::Alphanumeric, Rng as _};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
+use sqlx::{types::Uuid, PgConnection, PgPool};
+
+/// Every generated key starts with this, so leaked keys are easy to spot.
+const KEY_PREFIX: &str = "sll_";
+const KEY_SECRET_LENGTH: usize = 32;
+/// How much of the key is stored in plain text to let users tell keys apart.
+const VISIBLE_PREFIX_LENGTH: usize = KEY_PREFIX.len() + 8;
+/// The longest lifetime a key can be given, in days.
+pub const MAX_LIFETIME_DAYS: i64 = 3650;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum ApiScope {
+    #[serde(rename = "read")]
+    Read,
+    #[serde(rename = "sets:write")]
+    WriteSets,
+    /// Managing the account's API keys and webhooks.
+    #[serde(rename = "admin")]
+    Admin,
+}
+
+impl ApiScope {
+    pub const ALL: [Self; 3] = [Self::Read, Self::WriteSets, Self::Admin];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::WriteSets => "sets:write",
+            Self::Admin => "admin",
+        }
+    }
+
+    /// Scopes that grant this one: `admin` grants everything and write
+    /// access grants read access.
+    const fn granted_by(self) -> &'static [Self] {
+        match self {
+            Self::Read => &[Self::Read, Self::WriteSets, Self::Admin],
+            Self::WriteSets => &[Self::WriteSets, Self::Admin],
+            Self::Admin => &[Self::Admin],
+        }
+    }
+
+    #[must_use]
+    pub fn is_granted(self, scopes: &[String]) -> bool {
+        self.granted_by()
+            .iter()
+            .any(|scope| scopes.iter().any(|granted| granted == scope.as_str()))
+    }
+}
+
+/// Scopes checked by `auth::ApiToken`.
+pub mod scopes {
+    use super::{ApiScope, Scope};
+
+    pub struct Read;
+    impl Scope for Read {
+        fn is_granted(scopes: &[String]) -> bool {
+            ApiScope::Read.is_granted(scopes)
+        }
+    }
+
+    pub struct WriteSets;
+    impl Scope for WriteSets {
+        fn is_granted(scopes: &[String]) -> bool {
+            ApiScope::WriteSets.is_granted(scopes)
+        }
+    }
+
+    pub struct Admin;
+    impl Scope for Admin {
+        fn is_granted(scopes: &[String]) -> bool {
+            ApiScope::Admin.is_granted(scopes)
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+/// When a key given a lifetime of `days` expires, nothing when the lifetime is
+/// not between a day and [`MAX_LIFETIME_DAYS`].
+#[must_use]
+pub fn expires_in_days(days: i64) -> Option<NaiveDateTime> {
+    if !(1..=MAX_LIFETIME_DAYS).contains(&days) {
+        return None;
+    }
+    TimeDelta::try_days(days)
+        .and_then(|lifetime| Utc::now().naive_utc().checked_add_signed(lifetime))
+}
+
+/// Keys are long random strings, so a fast hash is enough to store them.
+#[must_use]
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn generate_key() -> String {
+    let secret = rand::thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(KEY_SECRET_LENGTH)
+        .map(char::from)
+        .collect::<String>();
+    format!("{KEY_PREFIX}{secret}")
+}
+
+impl Model {
+    /// Creates a key and returns it together with the plain text key, which
+    /// is never stored and can only be shown once.
+    ///
+    /// # Errors
+    ///
+    /// When could not save the key into the DB
+    pub async fn create(
+        db: &PgPool,
+        user_id: Uuid,
+        name: &str,
+        scopes: &[ApiScope],
+        expires_at: Option<NaiveDateTime>,
+    ) -> ModelResult<(Self, String)> {
+        if name.trim().is_empty() || scopes.is_empty() {
+            return Err(ModelError::Any(eyre::eyre!(
+                "an API key needs a name and at least one scope"
+            )));
+        }
+
+        Self::insert(&mut *db.acquire().await?, user_id, name, scopes, expires_at).await
+    }
+
+    async fn insert(
+        db: &mut PgConnection,
+        user_id: Uuid,
+        name: &str,
+        scopes: &[ApiScope],
+        expires_at: Option<NaiveDateTime>,
+    ) -> ModelResult<(Self, String)> {
+        let key = generate_key();
+        let scopes = scopes
+            .iter()
+            .map(|scope| scope.as_str().to_string())
+            .collect::<Vec<_>>();
+        let item = sqlx::query_as!(
+            Self,
+            r#"INSERT INTO api_keys (id, user_id, name, prefix, key_hash, scopes, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING *"#,
+            Uuid::now_v7(),
+            user_id,
+            name.trim(),
+            &key[..VISIBLE_PREFIX_LENGTH],
+            hash_key(&key),
+            &scopes,
+            expires_at
+        )
+        .fetch_one(&mut *db)
+        .await?;
+
+        Ok((item, key))
+    }
+
+    pub async fn list_by_user_id(db: &PgPool, user_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM api_keys WHERE user_id = $1 ORDER BY created_at, id",
+            user_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn find_by_id_and_user_id(db: &PgPool, id: Uuid, user_id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(
+            Self,
+            "SELECT * FROM api_keys WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Revokes the key, it can no longer be used.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn revoke(&self, db: &PgPool) -> ModelResult<()> {
+        sqlx::query!("DELETE FROM api_keys WHERE id = $1", self.id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
+    /// Replaces the key by a new one with the same name, scopes and lifetime,
+    /// returning the new key like [`Self::create`]. Either both happen or
+    /// neither does, so the old key is never left working next to the new one.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn rotate(&self, db: &PgPool) -> ModelResult<(Self, String)> {
+        let scopes = self
+            .scopes
+            .iter()
+            .filter_map(|scope| ApiScope::ALL.into_iter().find(|s| s.as_str() == scope))
+            .collect::<Vec<_>>();
+        let expires_at = self
+            .expires_at
+            .map(|expires_at| Utc::now().naive_utc() + (expires_at - self.created_at));
+
+        let mut tx = db.begin().await?;
+        sqlx::query!("DELETE FROM api_keys WHERE id = $1", self.id)
+            .execute(&mut *tx)
+            .await?;
+        let rotated = Self::insert(&mut tx, self.user_id, &self.name, &scopes, expires_at).await?;
+        tx.commit().await?;
+        Ok(rotated)
+    }
+
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| expires_at <= Utc::now().naive_utc())
+    }
+
+    /// Looks up a usable key, recording that it was used. Returns the owner
+    /// id and the granted scopes.
+    ///
+    /// # Errors
+    ///
+    /// When the key is unknown or expired, or DB query error
+    pub async fn use_key(db: &PgPool, key: &str) -> ModelResult<(Uuid, Vec<String>)> {
+        let grant = sqlx::query!(
+            r#"UPDATE api_keys SET last_used_at = $1
+            WHERE key_hash = $2 AND (expires_at IS NULL OR expires_at > $1)
+            RETURNING user_id, scopes"#,
+            Utc::now().naive_utc(),
+            hash_key(key)
+        )
+        .fetch_optional(db)
+        .await?;
+        grant
+            .map(|grant| (grant.user_id, grant.scopes))
+            .ok_or(ModelError::EntityNotFound)
+    }
+}
@@ -0,0 +1,37 @@
+use loco_rs::model::ModelResult;
+use sqlx::{types::Uuid, PgPool};
+
+/// A user saying they were at a set.
+pub struct Model;
+
+impl Model {
+    /// Checks in to the set, doing nothing when the user already did.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn attend(db: &PgPool, user_id: Uuid, set_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            "INSERT INTO attendances (user_id, set_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id,
+            set_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn unattend(db: &PgPool, user_id: Uuid, set_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            "DELETE FROM attendances WHERE user_id = $1 AND set_id = $2",
+            user_id,
+            set_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
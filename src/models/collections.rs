@@ -0,0 +1,244 @@
+use chrono::{NaiveDateTime, Utc};
+use loco_rs::model::{ModelError, ModelResult};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+use crate::models::sets;
+
+/// Longest name a collection can have.
+const MAX_NAME_LENGTH: usize = 100;
+/// Longest description a collection can have.
+const MAX_DESCRIPTION_LENGTH: usize = 2000;
+
+pub struct Params<'a> {
+    pub name: &'a str,
+    pub description: &'a str,
+    pub is_public: bool,
+}
+
+impl Params<'_> {
+    fn validate(&self) -> ModelResult<()> {
+        let name = self.name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(ModelError::Any(eyre::eyre!(
+                "A collection needs a name of at most {MAX_NAME_LENGTH} characters"
+            )));
+        }
+        if self.description.chars().count() > MAX_DESCRIPTION_LENGTH {
+            return Err(ModelError::Any(eyre::eyre!(
+                "A description has at most {MAX_DESCRIPTION_LENGTH} characters"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// A named, ordered list of sets curated by a user, e.g. "Best of Berlin
+/// 2024".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub id: Uuid,
+    pub owner_id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub is_public: bool,
+}
+
+impl Model {
+    /// # Errors
+    ///
+    /// When the name or description is invalid, or when could not save the
+    /// collection into the DB
+    pub async fn create(db: &PgPool, owner_id: Uuid, params: &Params<'_>) -> ModelResult<Self> {
+        params.validate()?;
+        Ok(sqlx::query_as!(
+            Self,
+            r#"INSERT INTO collections (id, owner_id, name, description, is_public)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *"#,
+            Uuid::now_v7(),
+            owner_id,
+            params.name.trim(),
+            params.description.trim(),
+            params.is_public
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// When could not find the collection or DB query error
+    pub async fn find_by_id(db: &PgPool, id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(Self, "SELECT * FROM collections WHERE id = $1", id)
+            .fetch_optional(db)
+            .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Collections of a user by name, only the public ones unless
+    /// `include_private`.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_owner_id(
+        db: &PgPool,
+        owner_id: Uuid,
+        include_private: bool,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM collections WHERE owner_id = $1 AND (is_public OR $2)
+            ORDER BY name, id"#,
+            owner_id,
+            include_private
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Private collections are only visible to their owner.
+    #[must_use]
+    pub fn is_visible_to(&self, user_id: Option<Uuid>) -> bool {
+        self.is_public || user_id == Some(self.owner_id)
+    }
+
+    /// # Errors
+    ///
+    /// When the name or description is invalid, or DB query error
+    pub async fn update(&mut self, db: &PgPool, params: &Params<'_>) -> ModelResult<()> {
+        params.validate()?;
+        self.name = params.name.trim().to_string();
+        self.description = params.description.trim().to_string();
+        self.is_public = params.is_public;
+        self.updated_at = Utc::now().naive_utc();
+        sqlx::query!(
+            r#"UPDATE collections SET name = $1, description = $2, is_public = $3, updated_at = $4
+            WHERE id = $5"#,
+            self.name,
+            self.description,
+            self.is_public,
+            self.updated_at,
+            self.id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn delete(&self, db: &PgPool) -> ModelResult<()> {
+        sqlx::query!("DELETE FROM collections WHERE id = $1", self.id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
+    async fn touch(&mut self, db: &PgPool) -> ModelResult<()> {
+        self.updated_at = Utc::now().naive_utc();
+        sqlx::query!(
+            "UPDATE collections SET updated_at = $1 WHERE id = $2",
+            self.updated_at,
+            self.id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// The sets of the collection, in order, leaving out the private sets of
+    /// anyone but the owner.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_sets(&self, db: &PgPool) -> ModelResult<Vec<sets::Model>> {
+        Ok(sqlx::query_as!(
+            sets::Model,
+            r#"SELECT sets.* FROM sets
+            JOIN collection_sets ON collection_sets.set_id = sets.id
+            WHERE collection_sets.collection_id = $1
+            AND (sets.visibility <> 'private' OR sets.creator_id = $2)
+            ORDER BY collection_sets.position"#,
+            self.id,
+            self.owner_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Adds the set at the end, doing nothing when it is already in the
+    /// collection.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn add_set(&mut self, db: &PgPool, set_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            r#"INSERT INTO collection_sets (collection_id, set_id, position)
+            SELECT $1, $2, COALESCE(MAX(position) + 1, 0) FROM collection_sets
+            WHERE collection_id = $1
+            ON CONFLICT DO NOTHING"#,
+            self.id,
+            set_id
+        )
+        .execute(db)
+        .await?;
+        self.touch(db).await
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn remove_set(&mut self, db: &PgPool, set_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            "DELETE FROM collection_sets WHERE collection_id = $1 AND set_id = $2",
+            self.id,
+            set_id
+        )
+        .execute(db)
+        .await?;
+        self.touch(db).await
+    }
+
+    /// Moves a set `offset` places up (negative) or down (positive) the
+    /// collection, stopping at either end.
+    ///
+    /// # Errors
+    ///
+    /// When the set is not in the collection, or DB query error
+    pub async fn move_set(&mut self, db: &PgPool, set_id: Uuid, offset: i32) -> ModelResult<()> {
+        let mut ids = sqlx::query_scalar!(
+            r#"SELECT set_id FROM collection_sets WHERE collection_id = $1
+            ORDER BY position"#,
+            self.id
+        )
+        .fetch_all(db)
+        .await?;
+        let from = ids
+            .iter()
+            .position(|id| *id == set_id)
+            .ok_or(ModelError::EntityNotFound)?;
+        let to = from
+            .saturating_add_signed(offset as isize)
+            .min(ids.len() - 1);
+        let id = ids.remove(from);
+        ids.insert(to, id);
+
+        sqlx::query!(
+            r#"UPDATE collection_sets SET position = (new.position - 1)::int
+            FROM UNNEST($2::uuid[]) WITH ORDINALITY AS new (set_id, position)
+            WHERE collection_sets.collection_id = $1 AND collection_sets.set_id = new.set_id"#,
+            self.id,
+            &ids
+        )
+        .execute(db)
+        .await?;
+        self.touch(db).await
+    }
+}
@@ -0,0 +1,159 @@
+use chrono::{NaiveDateTime, Utc};
+use loco_rs::model::{ModelError, ModelResult};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+/// Longest comment body.
+const MAX_BODY_LENGTH: usize = 5000;
+
+fn validate_body(body: &str) -> ModelResult<&str> {
+    let body = body.trim();
+    if body.is_empty() || body.chars().count() > MAX_BODY_LENGTH {
+        return Err(ModelError::Any(eyre::eyre!(
+            "A comment needs a text of at most {MAX_BODY_LENGTH} characters"
+        )));
+    }
+    Ok(body)
+}
+
+/// A comment on a set, or on one of its tracks, with the name of its author.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub id: Uuid,
+    pub set_id: Uuid,
+    pub author_id: Uuid,
+    pub author_name: String,
+    pub parent_id: Option<Uuid>,
+    pub track_number: Option<i32>,
+    pub body: String,
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+pub struct NewComment<'a> {
+    pub set_id: Uuid,
+    pub author_id: Uuid,
+    /// Replying to a reply replies to its parent, as replies are one level
+    /// deep.
+    pub parent: Option<&'a Model>,
+    pub track_number: Option<i32>,
+    pub body: &'a str,
+}
+
+impl Model {
+    /// # Errors
+    ///
+    /// When the body is empty or too long, when the parent was deleted, or
+    /// when could not save the comment into the DB
+    pub async fn create(db: &PgPool, params: &NewComment<'_>) -> ModelResult<Self> {
+        let body = validate_body(params.body)?;
+        let (parent_id, track_number) = match params.parent {
+            Some(parent) if parent.deleted_at.is_some() => {
+                return Err(ModelError::Any(eyre::eyre!(
+                    "You cannot reply to a deleted comment"
+                )));
+            }
+            // replies are about the same track as their thread
+            Some(parent) => (
+                Some(parent.parent_id.unwrap_or(parent.id)),
+                parent.track_number,
+            ),
+            None => (None, params.track_number),
+        };
+
+        let id = sqlx::query_scalar!(
+            r#"INSERT INTO comments (id, set_id, author_id, parent_id, track_number, body)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id"#,
+            Uuid::now_v7(),
+            params.set_id,
+            params.author_id,
+            parent_id,
+            track_number,
+            body
+        )
+        .fetch_one(db)
+        .await?;
+        Self::find_by_id_and_set_id(db, id, params.set_id).await
+    }
+
+    /// # Errors
+    ///
+    /// When could not find the comment or DB query error
+    pub async fn find_by_id_and_set_id(db: &PgPool, id: Uuid, set_id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(
+            Self,
+            r#"SELECT comments.*, users.username AS author_name
+            FROM comments JOIN users ON users.id = comments.author_id
+            WHERE comments.id = $1 AND comments.set_id = $2"#,
+            id,
+            set_id
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Comments of a set, oldest first. Deleted comments are left out unless
+    /// they still have replies.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_set_id(db: &PgPool, set_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT comments.*, users.username AS author_name
+            FROM comments JOIN users ON users.id = comments.author_id
+            WHERE comments.set_id = $1
+                AND (
+                    comments.deleted_at IS NULL
+                    OR EXISTS (
+                        SELECT 1 FROM comments replies
+                        WHERE replies.parent_id = comments.id AND replies.deleted_at IS NULL
+                    )
+                )
+            ORDER BY comments.created_at, comments.id"#,
+            set_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// When the body is empty or too long, or DB query error
+    pub async fn update_body(&mut self, db: &PgPool, body: &str) -> ModelResult<()> {
+        self.body = validate_body(body)?.to_string();
+        self.updated_at = Utc::now().naive_utc();
+        sqlx::query!(
+            "UPDATE comments SET body = $1, updated_at = $2 WHERE id = $3",
+            self.body,
+            self.updated_at,
+            self.id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// Deletes the text of the comment, keeping a placeholder for its replies.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn delete(&mut self, db: &PgPool) -> ModelResult<()> {
+        let now = Utc::now().naive_utc();
+        self.body = String::new();
+        self.deleted_at = Some(now);
+        sqlx::query!(
+            "UPDATE comments SET body = '', deleted_at = $1, updated_at = $1 WHERE id = $2",
+            now,
+            self.id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
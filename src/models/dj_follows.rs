@@ -0,0 +1,58 @@
+use chrono::NaiveDateTime;
+use loco_rs::model::ModelResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+use crate::utils::slugify;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub user_id: Uuid,
+    pub dj_slug: String,
+    pub dj_name: String,
+}
+
+impl Model {
+    /// Follows the DJ, doing nothing when the user already follows them.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn follow(db: &PgPool, user_id: Uuid, dj_name: &str) -> ModelResult<()> {
+        sqlx::query!(
+            r#"INSERT INTO dj_follows (user_id, dj_slug, dj_name) VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING"#,
+            user_id,
+            slugify(dj_name),
+            dj_name
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn unfollow(db: &PgPool, user_id: Uuid, dj_slug: &str) -> ModelResult<()> {
+        sqlx::query!(
+            "DELETE FROM dj_follows WHERE user_id = $1 AND dj_slug = $2",
+            user_id,
+            dj_slug
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_by_user_id(db: &PgPool, user_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM dj_follows WHERE user_id = $1 ORDER BY dj_name",
+            user_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+}
@@ -0,0 +1,55 @@
+use loco_rs::model::{ModelError, ModelResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// DJs are not stored on their own, they are the distinct names found in
+/// `sets.dj_names` of public sets.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[schemars(rename = "Dj")]
+pub struct Model {
+    pub name: String,
+    pub set_count: i64,
+}
+
+impl Model {
+    pub async fn list_page(db: &PgPool, limit: i64, offset: i64) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT dj_name AS "name!", COUNT(*) AS "set_count!"
+            FROM sets, UNNEST(dj_names) AS dj_name
+            WHERE visibility = 'public'
+            GROUP BY dj_name
+            ORDER BY dj_name
+            LIMIT $1 OFFSET $2"#,
+            limit,
+            offset
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// The name of the DJ whose name has the slug `slug`, see
+    /// [`crate::utils::slugify`].
+    pub async fn find_name_by_slug(db: &PgPool, slug: &str) -> ModelResult<String> {
+        let name = sqlx::query_scalar!(
+            r#"SELECT dj_name AS "name!" FROM sets, UNNEST(dj_names) AS dj_name
+            WHERE slugify(dj_name) = $1 AND visibility = 'public'
+            ORDER BY sets.updated_at DESC
+            LIMIT 1"#,
+            slug
+        )
+        .fetch_optional(db)
+        .await?;
+        name.ok_or(ModelError::EntityNotFound)
+    }
+
+    pub async fn count(db: &PgPool) -> ModelResult<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COUNT(DISTINCT dj_name) AS "count!" FROM sets, UNNEST(dj_names) AS dj_name
+            WHERE visibility = 'public'"#
+        )
+        .fetch_one(db)
+        .await?)
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{NaiveDateTime, Utc};
+use loco_rs::model::{ModelError, ModelResult};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+/// Longest message a proposal can have.
+const MAX_MESSAGE_LENGTH: usize = 2000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ProposalStatus {
+    /// Waiting for the set owner.
+    Open,
+    Accepted,
+    Declined,
+}
+
+impl ProposalStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Accepted => "accepted",
+            Self::Declined => "declined",
+        }
+    }
+}
+
+/// A change to a set suggested by someone other than its owner, e.g. a
+/// missing track or a wrong start time.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub id: Uuid,
+    pub set_id: Uuid,
+    pub proposer_id: Uuid,
+    pub message: String,
+    pub status: String,
+}
+
+impl Model {
+    /// # Errors
+    ///
+    /// When the message is empty or too long, or when could not save the
+    /// proposal into the DB
+    pub async fn create(
+        db: &PgPool,
+        set_id: Uuid,
+        proposer_id: Uuid,
+        message: &str,
+    ) -> ModelResult<Self> {
+        let message = message.trim();
+        if message.is_empty() || message.chars().count() > MAX_MESSAGE_LENGTH {
+            return Err(ModelError::Any(eyre::eyre!(
+                "A proposal needs a message of at most {MAX_MESSAGE_LENGTH} characters"
+            )));
+        }
+
+        Ok(sqlx::query_as!(
+            Self,
+            r#"INSERT INTO edit_proposals (id, set_id, proposer_id, message)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *"#,
+            Uuid::now_v7(),
+            set_id,
+            proposer_id,
+            message
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    pub async fn find_by_id_and_set_id(db: &PgPool, id: Uuid, set_id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(
+            Self,
+            "SELECT * FROM edit_proposals WHERE id = $1 AND set_id = $2",
+            id,
+            set_id
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Open proposals of a set, oldest first.
+    pub async fn list_open_by_set_id(db: &PgPool, set_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM edit_proposals WHERE set_id = $1 AND status = $2
+            ORDER BY created_at, id"#,
+            set_id,
+            ProposalStatus::Open.as_str()
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_status(&mut self, db: &PgPool, status: ProposalStatus) -> ModelResult<()> {
+        self.status = status.as_str().to_string();
+        self.updated_at = Utc::now().naive_utc();
+        sqlx::query!(
+            "UPDATE edit_proposals SET status = $1, updated_at = $2 WHERE id = $3",
+            self.status,
+            self.updated_at,
+            self.id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
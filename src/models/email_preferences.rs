@@ -0,0 +1,175 @@
+use chrono::{Duration, NaiveDateTime, Utc};
+use loco_rs::model::{ModelError, ModelResult};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+/// How long after a digest the next one is due. A little under a week, so a
+/// late run of the weekly job does not skip a user.
+const DIGEST_INTERVAL_DAYS: i64 = 6;
+
+/// The kinds of email a user can turn on or off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topic {
+    /// A followed DJ has a new set.
+    FollowedSets,
+    /// Someone proposed an edit to one of the user's sets.
+    EditProposals,
+    /// A track the user asked about was identified.
+    IdentifiedTracks,
+    WeeklyDigest,
+}
+
+impl Topic {
+    pub const ALL: [Self; 4] = [
+        Self::FollowedSets,
+        Self::EditProposals,
+        Self::IdentifiedTracks,
+        Self::WeeklyDigest,
+    ];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::FollowedSets => "followed_sets",
+            Self::EditProposals => "edit_proposals",
+            Self::IdentifiedTracks => "identified_tracks",
+            Self::WeeklyDigest => "weekly_digest",
+        }
+    }
+
+    #[must_use]
+    pub const fn description(self) -> &'static str {
+        match self {
+            Self::FollowedSets => "New sets by DJs you follow",
+            Self::EditProposals => "Edits proposed to your sets",
+            Self::IdentifiedTracks => "IDs you asked about are identified",
+            Self::WeeklyDigest => "Weekly digest of what you follow",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub user_id: Uuid,
+    pub followed_sets: bool,
+    pub edit_proposals: bool,
+    pub identified_tracks: bool,
+    pub weekly_digest: bool,
+    pub unsubscribe_token: Uuid,
+    pub last_digest_at: Option<NaiveDateTime>,
+}
+
+impl Model {
+    /// The preferences of a user, created with the defaults on first use.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn find_or_create(db: &PgPool, user_id: Uuid) -> ModelResult<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"INSERT INTO email_preferences (user_id, unsubscribe_token) VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE SET user_id = EXCLUDED.user_id
+            RETURNING *"#,
+            user_id,
+            Uuid::new_v4()
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// When could not find the preferences or DB query error
+    pub async fn find_by_unsubscribe_token(db: &PgPool, token: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(
+            Self,
+            "SELECT * FROM email_preferences WHERE unsubscribe_token = $1",
+            token
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Users whose weekly digest is due at `now`.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_due_for_digest(db: &PgPool, now: NaiveDateTime) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM email_preferences
+            WHERE weekly_digest AND (last_digest_at IS NULL OR last_digest_at <= $1)"#,
+            now - Duration::days(DIGEST_INTERVAL_DAYS)
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    #[must_use]
+    pub const fn is_enabled(&self, topic: Topic) -> bool {
+        match topic {
+            Topic::FollowedSets => self.followed_sets,
+            Topic::EditProposals => self.edit_proposals,
+            Topic::IdentifiedTracks => self.identified_tracks,
+            Topic::WeeklyDigest => self.weekly_digest,
+        }
+    }
+
+    pub fn set_enabled(&mut self, topic: Topic, enabled: bool) {
+        match topic {
+            Topic::FollowedSets => self.followed_sets = enabled,
+            Topic::EditProposals => self.edit_proposals = enabled,
+            Topic::IdentifiedTracks => self.identified_tracks = enabled,
+            Topic::WeeklyDigest => self.weekly_digest = enabled,
+        }
+    }
+
+    /// Saves which topics are enabled.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn update(&mut self, db: &PgPool) -> ModelResult<()> {
+        self.updated_at = Utc::now().naive_utc();
+        sqlx::query!(
+            r#"UPDATE email_preferences
+            SET updated_at = $1, followed_sets = $2, edit_proposals = $3, identified_tracks = $4,
+                weekly_digest = $5
+            WHERE user_id = $6"#,
+            self.updated_at,
+            self.followed_sets,
+            self.edit_proposals,
+            self.identified_tracks,
+            self.weekly_digest,
+            self.user_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_digest_sent(
+        &mut self,
+        db: &PgPool,
+        sent_at: NaiveDateTime,
+    ) -> ModelResult<()> {
+        self.last_digest_at = Some(sent_at);
+        sqlx::query!(
+            "UPDATE email_preferences SET last_digest_at = $1 WHERE user_id = $2",
+            self.last_digest_at,
+            self.user_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,75 @@
+use chrono::NaiveDateTime;
+use loco_rs::model::ModelResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+/// A user asking to be told when an unidentified track gets identified.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub user_id: Uuid,
+    pub set_id: Uuid,
+    pub track_number: i32,
+}
+
+impl Model {
+    /// Asks about a track, doing nothing when the user already did.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn create(
+        db: &PgPool,
+        user_id: Uuid,
+        set_id: Uuid,
+        track_number: i32,
+    ) -> ModelResult<()> {
+        sqlx::query!(
+            r#"INSERT INTO id_requests (user_id, set_id, track_number) VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING"#,
+            user_id,
+            set_id,
+            track_number
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// Track numbers of a set the user asked about.
+    pub async fn list_track_numbers(
+        db: &PgPool,
+        user_id: Uuid,
+        set_id: Uuid,
+    ) -> ModelResult<Vec<i32>> {
+        Ok(sqlx::query_scalar!(
+            "SELECT track_number FROM id_requests WHERE user_id = $1 AND set_id = $2",
+            user_id,
+            set_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Removes and returns the requests for `track_numbers` of a set, once
+    /// they are identified.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn take_for_tracks(
+        db: &PgPool,
+        set_id: Uuid,
+        track_numbers: &[i32],
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"DELETE FROM id_requests WHERE set_id = $1 AND track_number = ANY($2)
+            RETURNING *"#,
+            set_id,
+            track_numbers
+        )
+        .fetch_all(db)
+        .await?)
+    }
+}
@@ -1,5 +1,28 @@
+pub mod api_keys;
+pub mod attendances;
+pub mod collections;
+pub mod comments;
+pub mod dj_follows;
+pub mod djs;
+pub mod edit_proposals;
+pub mod email_preferences;
+pub mod id_requests;
+pub mod musical_keys;
+pub mod notifications;
+pub mod recordings;
+pub mod set_flow;
+pub mod set_likes;
+pub mod set_ratings;
+pub mod set_similarities;
 pub mod sets;
-// TODO remove!!
-#[allow(dead_code)]
+pub mod sitemaps;
 pub mod songs;
+pub mod stats;
+pub mod tags;
+pub mod transitions;
+pub mod uploads;
+pub mod user_follows;
 pub mod users;
+pub mod venue_follows;
+pub mod webhook_deliveries;
+pub mod webhooks;
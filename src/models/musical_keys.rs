@@ -0,0 +1,174 @@
+use std::{fmt, str::FromStr};
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Names of the major keys by pitch class, starting from C, spelled the way
+/// DJ software usually shows them.
+const MAJOR_NAMES: [&str; 12] = [
+    "C", "Db", "D", "Eb", "E", "F", "F#", "G", "Ab", "A", "Bb", "B",
+];
+const MINOR_NAMES: [&str; 12] = [
+    "Cm", "C#m", "Dm", "Ebm", "Em", "Fm", "F#m", "Gm", "G#m", "Am", "Bbm", "Bm",
+];
+
+/// The key a track is in, written in the usual notation ("Am", "F#"), as a
+/// Camelot code ("8A") or in Open Key notation ("1m").
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MusicalKey {
+    /// Pitch class of the tonic, 0 for C up to 11 for B.
+    tonic: u8,
+    minor: bool,
+}
+
+impl MusicalKey {
+    #[must_use]
+    pub const fn is_minor(self) -> bool {
+        self.minor
+    }
+
+    /// Position on the Camelot wheel, from 1 to 12. Neighbouring positions
+    /// are a fifth apart and mix well.
+    #[must_use]
+    pub const fn wheel_number(self) -> u8 {
+        // C major is 8B and A minor 8A, each fifth up is the next number
+        let offset = if self.minor { 3 } else { 0 };
+        ((self.tonic + offset) * 7 % 12 + 7) % 12 + 1
+    }
+
+    /// Steps clockwise around the Camelot wheel from this key to `to`, from
+    /// -5 to 6, counter-clockwise when negative.
+    #[must_use]
+    pub fn wheel_steps(self, to: Self) -> i8 {
+        // both numbers are at most 12
+        #[allow(clippy::cast_possible_wrap)]
+        let steps = (to.wheel_number() + 12 - self.wheel_number()) as i8 % 12;
+        if steps > 6 {
+            steps - 12
+        } else {
+            steps
+        }
+    }
+
+    /// How far apart two keys are on the Camelot wheel, switching between
+    /// minor and major counting as a step. Keys at most one step apart mix
+    /// harmonically.
+    #[must_use]
+    pub fn wheel_distance(self, other: Self) -> u8 {
+        self.wheel_steps(other).unsigned_abs() + u8::from(self.minor != other.minor)
+    }
+
+    #[must_use]
+    pub fn camelot(self) -> String {
+        format!(
+            "{}{}",
+            self.wheel_number(),
+            if self.minor { 'A' } else { 'B' }
+        )
+    }
+
+    /// Open Key starts its wheel at C major (1d) and A minor (1m).
+    #[must_use]
+    pub fn open_key(self) -> String {
+        format!(
+            "{}{}",
+            (self.wheel_number() + 4) % 12 + 1,
+            if self.minor { 'm' } else { 'd' }
+        )
+    }
+
+    /// The key at `number` on the Camelot wheel.
+    const fn from_wheel(number: u8, minor: bool) -> Self {
+        let tonic = (number + 4) * 7 % 12;
+        Self {
+            tonic: if minor { (tonic + 9) % 12 } else { tonic },
+            minor,
+        }
+    }
+
+    /// "8A" or "1m", telling Camelot and Open Key apart by their letters.
+    fn parse_code(value: &str) -> Option<Self> {
+        let digits = value.chars().take_while(char::is_ascii_digit).count();
+        let number: u8 = value[..digits].parse().ok()?;
+        if !(1..=12).contains(&number) {
+            return None;
+        }
+        let open_key = (number + 6) % 12 + 1;
+        match value[digits..].to_ascii_lowercase().as_str() {
+            "a" => Some(Self::from_wheel(number, true)),
+            "b" => Some(Self::from_wheel(number, false)),
+            "m" => Some(Self::from_wheel(open_key, true)),
+            "d" => Some(Self::from_wheel(open_key, false)),
+            _ => None,
+        }
+    }
+
+    fn parse_notation(value: &str) -> Option<Self> {
+        let mut chars = value.chars();
+        let tonic: u8 = match chars.next()?.to_ascii_uppercase() {
+            'C' => 0,
+            'D' => 2,
+            'E' => 4,
+            'F' => 5,
+            'G' => 7,
+            'A' => 9,
+            'B' => 11,
+            _ => return None,
+        };
+        let rest = chars.as_str();
+        let mut after_accidental = rest.chars();
+        let tonic = match after_accidental.next() {
+            Some('#' | '♯') => (tonic + 1) % 12,
+            Some('b' | '♭') => (tonic + 11) % 12,
+            _ => {
+                after_accidental = rest.chars();
+                tonic
+            }
+        };
+        let minor = match after_accidental
+            .as_str()
+            .trim()
+            .to_ascii_lowercase()
+            .as_str()
+        {
+            "" | "maj" | "major" => false,
+            "m" | "min" | "minor" => true,
+            _ => return None,
+        };
+        Some(Self { tonic, minor })
+    }
+}
+
+impl FromStr for MusicalKey {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let trimmed = value.trim();
+        let key = if trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+            Self::parse_code(trimmed)
+        } else {
+            Self::parse_notation(trimmed)
+        };
+        key.ok_or_else(|| format!("`{value}` is not a musical key, write it like Am, F#, 8A or 1m"))
+    }
+}
+
+impl fmt::Display for MusicalKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = if self.minor { MINOR_NAMES } else { MAJOR_NAMES };
+        f.write_str(names[usize::from(self.tonic)])
+    }
+}
+
+impl Serialize for MusicalKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for MusicalKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
@@ -0,0 +1,153 @@
+use chrono::{NaiveDateTime, Utc};
+use loco_rs::model::{ModelError, ModelResult};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+/// What a notification is about. Sets have a single creator and no
+/// collaborators, so there are no collaborator invites to notify about yet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// Someone proposed an edit to one of the user's sets.
+    EditProposal,
+    /// A track the user asked about was identified.
+    TrackIdentified,
+    /// Someone commented on one of the user's sets.
+    Comment,
+    /// Someone replied to one of the user's comments.
+    Reply,
+}
+
+impl NotificationKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::EditProposal => "edit_proposal",
+            Self::TrackIdentified => "track_identified",
+            Self::Comment => "comment",
+            Self::Reply => "reply",
+        }
+    }
+}
+
+/// An entry of a user's notification center.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub kind: String,
+    pub summary: String,
+    pub path: String,
+    pub read_at: Option<NaiveDateTime>,
+}
+
+impl Model {
+    /// # Errors
+    ///
+    /// When could not save the notification into the DB
+    pub async fn create(
+        db: &PgPool,
+        user_id: Uuid,
+        kind: NotificationKind,
+        summary: &str,
+        path: &str,
+    ) -> ModelResult<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"INSERT INTO notifications (id, user_id, kind, summary, path)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *"#,
+            Uuid::now_v7(),
+            user_id,
+            kind.as_str(),
+            summary,
+            path
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// A page of a user's notifications, newest first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_page_by_user_id(
+        db: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM notifications WHERE user_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2 OFFSET $3"#,
+            user_id,
+            limit,
+            offset
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn count_by_user_id(db: &PgPool, user_id: Uuid) -> ModelResult<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM notifications WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn count_unread(db: &PgPool, user_id: Uuid) -> ModelResult<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM notifications
+            WHERE user_id = $1 AND read_at IS NULL"#,
+            user_id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// Marks one of the user's notifications as read.
+    ///
+    /// # Errors
+    ///
+    /// When the user has no such notification or DB query error
+    pub async fn mark_read(db: &PgPool, user_id: Uuid, id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(
+            Self,
+            r#"UPDATE notifications SET read_at = COALESCE(read_at, $1)
+            WHERE id = $2 AND user_id = $3
+            RETURNING *"#,
+            Utc::now().naive_utc(),
+            id,
+            user_id
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn mark_all_read(db: &PgPool, user_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            "UPDATE notifications SET read_at = $1 WHERE user_id = $2 AND read_at IS NULL",
+            Utc::now().naive_utc(),
+            user_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
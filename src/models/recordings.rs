@@ -0,0 +1,284 @@
+use chrono::NaiveDateTime;
+use loco_rs::model::{ModelError, ModelResult};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+/// Most recordings a set can link to.
+const MAX_RECORDINGS_PER_SET: i64 = 5;
+/// Extensions of audio files browsers play, for self-hosted recordings.
+const AUDIO_EXTENSIONS: &[&str] = &["aac", "flac", "m4a", "mp3", "oga", "ogg", "opus", "wav"];
+
+/// Where a recording is hosted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    SoundCloud,
+    YouTube,
+    Mixcloud,
+    /// An audio file served as is.
+    File,
+    /// An audio file uploaded by the owner of the set.
+    Upload,
+}
+
+impl Provider {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::SoundCloud => "sound_cloud",
+            Self::YouTube => "you_tube",
+            Self::Mixcloud => "mixcloud",
+            Self::File => "file",
+            Self::Upload => "upload",
+        }
+    }
+
+    fn from_str(provider: &str) -> Option<Self> {
+        [
+            Self::SoundCloud,
+            Self::YouTube,
+            Self::Mixcloud,
+            Self::File,
+            Self::Upload,
+        ]
+        .into_iter()
+        .find(|candidate| candidate.as_str() == provider)
+    }
+
+    /// Recognises the provider from the URL of a recording.
+    #[must_use]
+    pub fn detect(url: &Url) -> Option<Self> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        let host = url.host_str()?;
+        let host = host
+            .strip_prefix("www.")
+            .or_else(|| host.strip_prefix("m."))
+            .unwrap_or(host);
+        match host {
+            "soundcloud.com" => Some(Self::SoundCloud),
+            "youtube.com" | "music.youtube.com" | "youtu.be" => Some(Self::YouTube),
+            "mixcloud.com" => Some(Self::Mixcloud),
+            _ => url
+                .path()
+                .rsplit_once('.')
+                .map(|(_, extension)| extension.to_ascii_lowercase())
+                .filter(|extension| AUDIO_EXTENSIONS.contains(&extension.as_str()))
+                .map(|_| Self::File),
+        }
+    }
+}
+
+/// "1:02:03" or "2:03"
+#[must_use]
+pub fn format_offset(seconds: i32) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// A link to a recording of a set.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub id: Uuid,
+    pub set_id: Uuid,
+    pub url: String,
+    pub provider: String,
+    pub upload_id: Option<Uuid>,
+}
+
+impl Model {
+    /// # Errors
+    ///
+    /// When the URL is not a link to a known provider or an audio file, the
+    /// set already links to it or to too many recordings, or DB query error
+    pub async fn create(db: &PgPool, set_id: Uuid, url: &str) -> ModelResult<Self> {
+        let url = Url::parse(url.trim())
+            .ok()
+            .and_then(|url| Provider::detect(&url).map(|provider| (url, provider)));
+        let Some((url, provider)) = url else {
+            return Err(ModelError::Any(eyre::eyre!(
+                "Link a SoundCloud, YouTube or Mixcloud page, or an audio file"
+            )));
+        };
+        Self::check_room(db, set_id).await?;
+
+        let item = sqlx::query_as!(
+            Self,
+            r#"INSERT INTO recordings (id, set_id, url, provider)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (set_id, url) DO NOTHING
+            RETURNING *"#,
+            Uuid::now_v7(),
+            set_id,
+            url.as_str(),
+            provider.as_str()
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or_else(|| ModelError::Any(eyre::eyre!("This recording is already linked")))
+    }
+
+    /// Links the set to a file its owner uploaded.
+    ///
+    /// # Errors
+    ///
+    /// When the set already links to too many recordings or DB query error
+    pub async fn create_upload(db: &PgPool, set_id: Uuid, upload_id: Uuid) -> ModelResult<Self> {
+        Self::check_room(db, set_id).await?;
+        Ok(sqlx::query_as!(
+            Self,
+            r#"INSERT INTO recordings (id, set_id, url, provider, upload_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *"#,
+            Uuid::now_v7(),
+            set_id,
+            format!("/uploads/{upload_id}"),
+            Provider::Upload.as_str(),
+            upload_id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// When the set already links to too many recordings or DB query error
+    pub async fn check_room(db: &PgPool, set_id: Uuid) -> ModelResult<()> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM recordings WHERE set_id = $1"#,
+            set_id
+        )
+        .fetch_one(db)
+        .await?;
+        if count >= MAX_RECORDINGS_PER_SET {
+            return Err(ModelError::Any(eyre::eyre!(
+                "A set links to at most {MAX_RECORDINGS_PER_SET} recordings"
+            )));
+        }
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// When the set has no such recording or DB query error
+    pub async fn find(db: &PgPool, set_id: Uuid, id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(
+            Self,
+            "SELECT * FROM recordings WHERE id = $1 AND set_id = $2",
+            id,
+            set_id
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Recordings of a set, in the order they were linked.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_set_id(db: &PgPool, set_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM recordings WHERE set_id = $1 ORDER BY created_at, id",
+            set_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// When the set has no such recording or DB query error
+    pub async fn delete(db: &PgPool, set_id: Uuid, id: Uuid) -> ModelResult<()> {
+        let result = sqlx::query!(
+            "DELETE FROM recordings WHERE id = $1 AND set_id = $2",
+            id,
+            set_id
+        )
+        .execute(db)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Err(ModelError::EntityNotFound);
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn provider(&self) -> Option<Provider> {
+        Provider::from_str(&self.provider)
+    }
+
+    /// Link playing the recording from `seconds` in, when the provider
+    /// supports it: `?t=` for YouTube, `#t=` for SoundCloud and media
+    /// fragments for files.
+    #[must_use]
+    pub fn deep_link(&self, seconds: i32) -> Option<String> {
+        if self.provider()? == Provider::Upload {
+            // served by this app, so the URL is only a path
+            return Some(format!("{}#t={seconds}", self.url));
+        }
+        let mut url = Url::parse(&self.url).ok()?;
+        match self.provider()? {
+            Provider::YouTube => {
+                let query = url
+                    .query_pairs()
+                    .filter(|(key, _)| key != "t")
+                    .map(|(key, value)| (key.into_owned(), value.into_owned()))
+                    .collect::<Vec<_>>();
+                url.query_pairs_mut()
+                    .clear()
+                    .extend_pairs(query)
+                    .append_pair("t", &seconds.to_string());
+            }
+            Provider::SoundCloud => {
+                url.set_fragment(Some(&format!("t={}", format_offset(seconds))))
+            }
+            Provider::File => url.set_fragment(Some(&format!("t={seconds}"))),
+            Provider::Mixcloud | Provider::Upload => return None,
+        }
+        Some(url.into())
+    }
+
+    /// URL of the provider's player to embed in a page, if it has one.
+    #[must_use]
+    pub fn embed_url(&self) -> Option<String> {
+        let url = Url::parse(&self.url).ok()?;
+        match self.provider()? {
+            Provider::YouTube => {
+                let video = if url.host_str() == Some("youtu.be") {
+                    url.path().trim_start_matches('/').to_string()
+                } else {
+                    url.query_pairs()
+                        .find(|(key, _)| key == "v")
+                        .map(|(_, value)| value.into_owned())?
+                };
+                let valid = !video.is_empty()
+                    && video
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+                valid.then(|| format!("https://www.youtube-nocookie.com/embed/{video}"))
+            }
+            Provider::SoundCloud => {
+                let mut embed = Url::parse("https://w.soundcloud.com/player/").ok()?;
+                embed.query_pairs_mut().append_pair("url", url.as_str());
+                Some(embed.into())
+            }
+            Provider::Mixcloud => {
+                let mut embed =
+                    Url::parse("https://player-widget.mixcloud.com/widget/iframe/").ok()?;
+                embed.query_pairs_mut().append_pair("feed", url.path());
+                Some(embed.into())
+            }
+            Provider::File | Provider::Upload => None,
+        }
+    }
+}
@@ -0,0 +1,164 @@
+use serde::Serialize;
+
+use crate::models::{musical_keys::MusicalKey, songs};
+
+/// The largest tempo change a DJ can usually ride on the pitch fader, in
+/// percent. Larger changes are flagged.
+pub const MAX_TEMPO_CHANGE: f64 = 6.0;
+
+/// How well the keys of two tracks played one after the other go together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Harmony {
+    /// The same key.
+    Same,
+    /// A step around the Camelot wheel, or the relative major or minor.
+    Compatible,
+    /// Further apart, which usually sounds off when the tracks overlap.
+    Clash,
+}
+
+impl Harmony {
+    #[must_use]
+    pub const fn from_distance(distance: u8) -> Self {
+        match distance {
+            0 => Self::Same,
+            1 => Self::Compatible,
+            _ => Self::Clash,
+        }
+    }
+}
+
+/// How a track played after another changes the tempo.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TempoChange {
+    /// BPM of the next track, halved or doubled when mixed at half or double
+    /// time, relative to the previous one.
+    pub delta: f64,
+    pub percent: f64,
+    /// Whether the next track is mixed at half (0.5) or double (2) time.
+    pub ratio: f64,
+    /// Further than the pitch fader usually goes.
+    pub jump: bool,
+}
+
+impl TempoChange {
+    /// The change from `from` to `to` BPM, mixing `to` at the ratio closest
+    /// to `from`.
+    #[must_use]
+    pub fn new(from: f64, to: f64) -> Self {
+        let (ratio, matched) = [(1.0, to), (0.5, to / 2.0), (2.0, to * 2.0)]
+            .into_iter()
+            .min_by(|(_, a), (_, b)| (a - from).abs().total_cmp(&(b - from).abs()))
+            .unwrap_or((1.0, to));
+        let delta = matched - from;
+        let percent = delta / from * 100.0;
+        Self {
+            delta,
+            percent,
+            ratio,
+            jump: percent.abs() > MAX_TEMPO_CHANGE,
+        }
+    }
+}
+
+/// A track of a set with what is known of its key and tempo.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowTrack<'a> {
+    pub song: &'a songs::Model,
+    pub key: Option<MusicalKey>,
+    /// Estimated energy relative to the first track, see `analyze`.
+    pub energy: f64,
+}
+
+/// Two tracks played one after the other.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FlowTransition {
+    /// Positions of the tracks in `Flow::tracks`.
+    pub from: usize,
+    pub to: usize,
+    /// Steps around the Camelot wheel, when both keys are known.
+    pub key_distance: Option<u8>,
+    pub harmony: Option<Harmony>,
+    pub tempo: Option<TempoChange>,
+}
+
+impl FlowTransition {
+    /// Whether the transition is worth another listen.
+    #[must_use]
+    pub fn is_rough(&self) -> bool {
+        self.harmony == Some(Harmony::Clash) || self.tempo.is_some_and(|tempo| tempo.jump)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Flow<'a> {
+    pub tracks: Vec<FlowTrack<'a>>,
+    pub transitions: Vec<FlowTransition>,
+}
+
+impl Flow<'_> {
+    /// Whether any transition could be analysed at all.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.transitions
+            .iter()
+            .all(|transition| transition.harmony.is_none() && transition.tempo.is_none())
+    }
+}
+
+/// Analyses the transitions of a tracklist in the order it was played.
+///
+/// Energy is estimated from the tempo and from moves around the Camelot wheel,
+/// the way DJs use it to lift or calm a crowd: a percent faster, a step
+/// clockwise or a switch from minor to major each lift it by one, the reverse
+/// lowers it. Keys too far apart to mix leave it as it is.
+#[must_use]
+pub fn analyze(tracklist: &[songs::Model]) -> Flow<'_> {
+    let mut played = tracklist.iter().collect::<Vec<_>>();
+    played.sort_by_key(|song| song.track_number);
+    let mut tracks: Vec<FlowTrack<'_>> = Vec::with_capacity(played.len());
+    let mut transitions = vec![];
+    for song in played {
+        let key = song
+            .track_key
+            .as_deref()
+            .and_then(|key| key.parse::<MusicalKey>().ok());
+        let mut energy = 0.0;
+        if let Some(previous) = tracks.last() {
+            let key_distance = previous
+                .key
+                .zip(key)
+                .map(|(from, to)| from.wheel_distance(to));
+            let tempo = previous
+                .song
+                .track_bpm
+                .zip(song.track_bpm)
+                .map(|(from, to)| TempoChange::new(from, to));
+            let mut lift = tempo.map_or(0.0, |tempo| tempo.percent);
+            if let (Some(from), Some(to), Some(distance)) = (previous.key, key, key_distance) {
+                if distance <= 2 {
+                    lift += f64::from(from.wheel_steps(to));
+                    lift += match (from.is_minor(), to.is_minor()) {
+                        (true, false) => 1.0,
+                        (false, true) => -1.0,
+                        _ => 0.0,
+                    };
+                }
+            }
+            energy = previous.energy + lift;
+            transitions.push(FlowTransition {
+                from: tracks.len() - 1,
+                to: tracks.len(),
+                key_distance,
+                harmony: key_distance.map(Harmony::from_distance),
+                tempo,
+            });
+        }
+        tracks.push(FlowTrack { song, key, energy });
+    }
+    Flow {
+        tracks,
+        transitions,
+    }
+}
@@ -0,0 +1,36 @@
+use loco_rs::model::ModelResult;
+use sqlx::{types::Uuid, PgPool};
+
+pub struct Model;
+
+impl Model {
+    /// Likes the set, doing nothing when the user already likes it.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn like(db: &PgPool, user_id: Uuid, set_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            "INSERT INTO set_likes (user_id, set_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            user_id,
+            set_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn unlike(db: &PgPool, user_id: Uuid, set_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            "DELETE FROM set_likes WHERE user_id = $1 AND set_id = $2",
+            user_id,
+            set_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use loco_rs::model::{ModelError, ModelResult};
+use sqlx::{types::Uuid, PgPool};
+
+pub const MIN_RATING: i16 = 1;
+pub const MAX_RATING: i16 = 5;
+
+pub struct Model;
+
+impl Model {
+    /// Rates the set, replacing the user's previous rating.
+    ///
+    /// # Errors
+    ///
+    /// When the rating is out of range, or DB query error
+    pub async fn rate(db: &PgPool, user_id: Uuid, set_id: Uuid, rating: i16) -> ModelResult<()> {
+        if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+            return Err(ModelError::Any(eyre::eyre!(
+                "A rating is from {MIN_RATING} to {MAX_RATING}"
+            )));
+        }
+        sqlx::query!(
+            r#"INSERT INTO set_ratings (user_id, set_id, rating) VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, set_id)
+            DO UPDATE SET rating = EXCLUDED.rating, updated_at = CURRENT_TIMESTAMP"#,
+            user_id,
+            set_id,
+            rating
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn remove(db: &PgPool, user_id: Uuid, set_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            "DELETE FROM set_ratings WHERE user_id = $1 AND set_id = $2",
+            user_id,
+            set_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,226 @@
+use chrono::NaiveDateTime;
+use loco_rs::model::ModelResult;
+use serde::Serialize;
+use sqlx::{types::Uuid, PgPool};
+
+/// How much sharing tracks counts towards the similarity of two sets, the rest
+/// goes to DJs and tags.
+const TRACKS_WEIGHT: f64 = 0.6;
+const DJS_WEIGHT: f64 = 0.25;
+const TAGS_WEIGHT: f64 = 0.15;
+/// The most similar sets kept for a set when it changes.
+const KEPT: usize = 20;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Model {
+    pub computed_at: NaiveDateTime,
+    pub set_id: Uuid,
+    pub similar_set_id: Uuid,
+    pub score: f64,
+    /// Identified tracks both sets played, told apart by their slugs.
+    pub shared_tracks: i64,
+    pub shared_djs: i64,
+    pub shared_tags: i64,
+}
+
+/// A set sharing at least a track, a DJ or a tag with another, with what
+/// each of them has, to compare them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Candidate {
+    pub set_id: Uuid,
+    pub shared_tracks: i64,
+    pub tracks: i64,
+    pub own_tracks: i64,
+    pub shared_djs: i64,
+    pub djs: i64,
+    pub own_djs: i64,
+    pub shared_tags: i64,
+    pub tags: i64,
+    pub own_tags: i64,
+}
+
+/// Share of the items of two sets that both have, nothing when neither has
+/// any.
+#[allow(clippy::cast_precision_loss)]
+fn jaccard(shared: i64, own: i64, other: i64) -> f64 {
+    let union = own + other - shared;
+    if union > 0 {
+        shared as f64 / union as f64
+    } else {
+        0.0
+    }
+}
+
+impl Candidate {
+    /// From 0 for sets with nothing in common to 1 for sets with the same
+    /// tracks, DJs and tags.
+    #[must_use]
+    pub fn score(&self) -> f64 {
+        TAGS_WEIGHT.mul_add(
+            jaccard(self.shared_tags, self.own_tags, self.tags),
+            TRACKS_WEIGHT.mul_add(
+                jaccard(self.shared_tracks, self.own_tracks, self.tracks),
+                DJS_WEIGHT * jaccard(self.shared_djs, self.own_djs, self.djs),
+            ),
+        )
+    }
+}
+
+impl Model {
+    /// Sets sharing anything with the set `set_id`, compared by their
+    /// identified tracks, DJ slugs and tags.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_candidates(db: &PgPool, set_id: Uuid) -> ModelResult<Vec<Candidate>> {
+        Ok(sqlx::query_as!(
+            Candidate,
+            r#"WITH own_tracks AS (
+                SELECT DISTINCT slugify(track_artist) AS artist_slug,
+                    slugify(track_title) AS title_slug
+                FROM setlist_songs
+                WHERE setlist_id = $1
+                    AND upper(btrim(track_artist)) NOT IN ('', 'ID')
+                    AND upper(btrim(track_title)) NOT IN ('', 'ID')
+                    AND slugify(track_artist) <> '' AND slugify(track_title) <> ''
+            ),
+            own_djs AS (
+                SELECT DISTINCT slugify(dj_name) AS slug
+                FROM sets, unnest(sets.dj_names) AS dj_name
+                WHERE sets.id = $1 AND slugify(dj_name) <> ''
+            ),
+            own_tags AS (SELECT tag_id FROM set_tags WHERE set_id = $1),
+            shared_tracks AS (
+                SELECT setlist_id AS set_id,
+                    count(DISTINCT (slugify(track_artist), slugify(track_title))) AS shared
+                FROM setlist_songs JOIN own_tracks
+                    ON slugify(track_artist) = own_tracks.artist_slug
+                    AND slugify(track_title) = own_tracks.title_slug
+                WHERE setlist_id <> $1
+                    AND upper(btrim(track_artist)) NOT IN ('', 'ID')
+                    AND upper(btrim(track_title)) NOT IN ('', 'ID')
+                GROUP BY setlist_id
+            ),
+            shared_djs AS (
+                SELECT sets.id AS set_id, count(DISTINCT slugify(dj_name)) AS shared
+                FROM sets, unnest(sets.dj_names) AS dj_name
+                WHERE sets.id <> $1 AND slugify(dj_name) IN (SELECT slug FROM own_djs)
+                GROUP BY sets.id
+            ),
+            shared_tags AS (
+                SELECT set_id, count(*) AS shared
+                FROM set_tags
+                WHERE set_id <> $1 AND tag_id IN (SELECT tag_id FROM own_tags)
+                GROUP BY set_id
+            ),
+            candidates AS (
+                SELECT set_id FROM shared_tracks
+                UNION SELECT set_id FROM shared_djs
+                UNION SELECT set_id FROM shared_tags
+            )
+            SELECT
+                candidates.set_id AS "set_id!",
+                coalesce(shared_tracks.shared, 0) AS "shared_tracks!",
+                (SELECT count(DISTINCT (slugify(track_artist), slugify(track_title)))
+                    FROM setlist_songs
+                    WHERE setlist_id = candidates.set_id
+                        AND upper(btrim(track_artist)) NOT IN ('', 'ID')
+                        AND upper(btrim(track_title)) NOT IN ('', 'ID')
+                        AND slugify(track_artist) <> '' AND slugify(track_title) <> ''
+                ) AS "tracks!",
+                (SELECT count(*) FROM own_tracks) AS "own_tracks!",
+                coalesce(shared_djs.shared, 0) AS "shared_djs!",
+                (SELECT count(DISTINCT slugify(dj_name))
+                    FROM sets, unnest(sets.dj_names) AS dj_name
+                    WHERE sets.id = candidates.set_id AND slugify(dj_name) <> ''
+                ) AS "djs!",
+                (SELECT count(*) FROM own_djs) AS "own_djs!",
+                coalesce(shared_tags.shared, 0) AS "shared_tags!",
+                (SELECT count(*) FROM set_tags WHERE set_id = candidates.set_id) AS "tags!",
+                (SELECT count(*) FROM own_tags) AS "own_tags!"
+            FROM candidates
+            LEFT JOIN shared_tracks USING (set_id)
+            LEFT JOIN shared_djs USING (set_id)
+            LEFT JOIN shared_tags USING (set_id)"#,
+            set_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Replaces the similarities of the set `set_id` with its `KEPT` best
+    /// `candidates`, stored both ways so that they list it too. What the
+    /// other sets keep of their own is left alone, unless they have nothing
+    /// in common with it anymore.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn replace_for_set(
+        db: &PgPool,
+        set_id: Uuid,
+        candidates: &[Candidate],
+    ) -> ModelResult<()> {
+        let mut scored = candidates
+            .iter()
+            .map(|candidate| (candidate.score(), candidate))
+            .filter(|(score, _)| *score > 0.0)
+            .collect::<Vec<_>>();
+        let alike = scored
+            .iter()
+            .map(|(_, candidate)| candidate.set_id)
+            .collect::<Vec<_>>();
+        scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+        scored.truncate(KEPT);
+
+        let mut tx = db.begin().await?;
+        sqlx::query!(
+            r#"DELETE FROM set_similarities
+            WHERE set_id = $1 OR (similar_set_id = $1 AND NOT set_id = ANY($2))"#,
+            set_id,
+            &alike
+        )
+        .execute(&mut *tx)
+        .await?;
+        for (score, candidate) in scored {
+            sqlx::query!(
+                r#"INSERT INTO set_similarities
+                (set_id, similar_set_id, score, shared_tracks, shared_djs, shared_tags)
+                VALUES ($1, $2, $3, $4, $5, $6), ($2, $1, $3, $4, $5, $6)
+                ON CONFLICT (set_id, similar_set_id) DO UPDATE
+                SET computed_at = CURRENT_TIMESTAMP, score = EXCLUDED.score,
+                    shared_tracks = EXCLUDED.shared_tracks, shared_djs = EXCLUDED.shared_djs,
+                    shared_tags = EXCLUDED.shared_tags"#,
+                set_id,
+                candidate.set_id,
+                score,
+                candidate.shared_tracks,
+                candidate.shared_djs,
+                candidate.shared_tags
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// The sets most similar to the set `set_id`, the most similar first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_set_id(db: &PgPool, set_id: Uuid, limit: i64) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM set_similarities WHERE set_id = $1
+            ORDER BY score DESC, similar_set_id
+            LIMIT $2"#,
+            set_id,
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+}
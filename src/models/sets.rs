@@ -1,9 +1,11 @@
 use chrono::NaiveDateTime;
 use loco_rs::model::{ModelError, ModelResult};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use sqlx::{types::Uuid, PgPool};
+use sqlx::{types::Uuid, PgConnection, PgPool};
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default, JsonSchema)]
+#[schemars(rename = "Set")]
 pub struct Model {
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -15,15 +17,89 @@ pub struct Model {
     pub event_name: Option<String>,
     pub start_time: NaiveDateTime,
     pub duration_seconds: Option<i32>,
+    /// Upload of the event flyer or cover image.
+    pub flyer_upload_id: Option<Uuid>,
+    /// See [`Visibility`].
+    pub visibility: String,
+}
+
+/// Who finds a set. Only public sets are listed, on pages, in feeds, in
+/// sitemaps and in notifications. Unlisted sets are shown to anyone with the
+/// link, private sets only to their creator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Unlisted,
+    Private,
+}
+
+impl Visibility {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Public => "public",
+            Self::Unlisted => "unlisted",
+            Self::Private => "private",
+        }
+    }
+
+    fn from_str(visibility: &str) -> Option<Self> {
+        [Self::Public, Self::Unlisted, Self::Private]
+            .into_iter()
+            .find(|candidate| candidate.as_str() == visibility)
+    }
+}
+
+/// How many users liked, rated and attended a set.
+#[derive(Clone, Debug, PartialEq, Serialize, Default)]
+pub struct Stats {
+    pub set_id: Uuid,
+    pub likes: i64,
+    pub attendees: i64,
+    pub ratings: i64,
+    pub average_rating: Option<f64>,
+}
+
+/// What a user did with a set.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Default)]
+pub struct Reactions {
+    pub liked: bool,
+    pub attended: bool,
+    pub rating: Option<i16>,
 }
 
 impl Model {
-    pub async fn list_by_creator_id(db: &PgPool, id: Uuid) -> ModelResult<Vec<Self>> {
-        Ok(
-            sqlx::query_as!(Self, "SELECT * FROM sets WHERE creator_id = $1", id)
-                .fetch_all(db)
-                .await?,
+    /// Sets not stored with a known visibility are public, like the column
+    /// defaults to.
+    #[must_use]
+    pub fn visibility(&self) -> Visibility {
+        Visibility::from_str(&self.visibility).unwrap_or_default()
+    }
+
+    /// Whether the set can be shown to `user_id`, or to anyone without a
+    /// login when `None`.
+    #[must_use]
+    pub fn is_visible_to(&self, user_id: Option<Uuid>) -> bool {
+        self.visibility() != Visibility::Private || user_id == Some(self.creator_id)
+    }
+
+    /// Sets added by the user `id`, only the public ones unless
+    /// `include_hidden`.
+    pub async fn list_by_creator_id(
+        db: &PgPool,
+        id: Uuid,
+        include_hidden: bool,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE creator_id = $1 AND ($2 OR visibility = 'public')",
+            id,
+            include_hidden
         )
+        .fetch_all(db)
+        .await?)
     }
 
     pub async fn find_by_id(db: &PgPool, id: Uuid) -> ModelResult<Self> {
@@ -33,23 +109,535 @@ impl Model {
         set.ok_or(ModelError::EntityNotFound)
     }
 
-    pub async fn delete_by_id(db: &PgPool, id: Uuid) -> ModelResult<()> {
+    pub async fn delete_by_id(db: &mut PgConnection, id: Uuid) -> ModelResult<()> {
         sqlx::query!("DELETE FROM sets WHERE id = $1", id)
             .execute(db)
             .await?;
         Ok(())
     }
 
+    pub async fn list_page(db: &PgPool, limit: i64, offset: i64) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE visibility = 'public' ORDER BY start_time DESC, id LIMIT $1 OFFSET $2",
+            limit,
+            offset
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn count(db: &PgPool) -> ModelResult<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM sets WHERE visibility = 'public'"#
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    pub async fn list_page_by_creator_id(
+        db: &PgPool,
+        id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE creator_id = $1 AND visibility = 'public' ORDER BY start_time DESC, id LIMIT $2 OFFSET $3",
+            id,
+            limit,
+            offset
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn count_by_creator_id(db: &PgPool, id: Uuid) -> ModelResult<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM sets WHERE creator_id = $1 AND visibility = 'public'"#,
+            id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    pub async fn list_page_by_dj_name(
+        db: &PgPool,
+        dj_name: &str,
+        limit: i64,
+        offset: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE $1 = ANY(dj_names) AND visibility = 'public' ORDER BY start_time DESC, id LIMIT $2 OFFSET $3",
+            dj_name,
+            limit,
+            offset
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn count_by_dj_name(db: &PgPool, dj_name: &str) -> ModelResult<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM sets WHERE $1 = ANY(dj_names) AND visibility = 'public'"#,
+            dj_name
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// The most recently updated sets, for feeds.
+    pub async fn list_recently_updated(db: &PgPool, limit: i64) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE visibility = 'public' ORDER BY updated_at DESC, id LIMIT $1",
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn list_recently_updated_by_creator_id(
+        db: &PgPool,
+        id: Uuid,
+        limit: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE creator_id = $1 AND visibility = 'public' ORDER BY updated_at DESC, id LIMIT $2",
+            id,
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Sets played by the DJ whose name has the slug `slug`, see
+    /// [`crate::utils::slugify`].
+    pub async fn list_recently_updated_by_dj_slug(
+        db: &PgPool,
+        slug: &str,
+        limit: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM sets
+            WHERE visibility = 'public'
+            AND EXISTS (SELECT 1 FROM UNNEST(dj_names) AS dj_name WHERE slugify(dj_name) = $1)
+            ORDER BY updated_at DESC, id LIMIT $2"#,
+            slug,
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Sets played at the venue whose name has the slug `slug`.
+    pub async fn list_recently_updated_by_venue_slug(
+        db: &PgPool,
+        slug: &str,
+        limit: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE slugify(venue) = $1 AND visibility = 'public' ORDER BY updated_at DESC, id LIMIT $2",
+            slug,
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// The name of the venue whose name has the slug `slug`, see
+    /// [`crate::utils::slugify`].
+    pub async fn find_venue_by_slug(db: &PgPool, slug: &str) -> ModelResult<String> {
+        let venue = sqlx::query_scalar!(
+            r#"SELECT venue AS "venue!" FROM sets
+            WHERE slugify(venue) = $1 AND visibility = 'public'
+            ORDER BY updated_at DESC
+            LIMIT 1"#,
+            slug
+        )
+        .fetch_optional(db)
+        .await?;
+        venue.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Sets played at the event whose name has the slug `slug`.
+    pub async fn list_recently_updated_by_event_slug(
+        db: &PgPool,
+        slug: &str,
+        limit: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE slugify(event_name) = $1 AND visibility = 'public' ORDER BY updated_at DESC, id LIMIT $2",
+            slug,
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// The name of the event whose name has the slug `slug`, see
+    /// [`crate::utils::slugify`].
+    pub async fn find_event_by_slug(db: &PgPool, slug: &str) -> ModelResult<String> {
+        let event_name = sqlx::query_scalar!(
+            r#"SELECT event_name AS "event_name!" FROM sets
+            WHERE slugify(event_name) = $1 AND visibility = 'public'
+            ORDER BY updated_at DESC
+            LIMIT 1"#,
+            slug
+        )
+        .fetch_optional(db)
+        .await?;
+        event_name.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Every set played by the DJ whose name has the slug `slug`, latest
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_dj_slug(db: &PgPool, slug: &str) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM sets
+            WHERE visibility = 'public'
+            AND EXISTS (SELECT 1 FROM UNNEST(dj_names) AS dj_name WHERE slugify(dj_name) = $1)
+            ORDER BY start_time DESC, id"#,
+            slug
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Every set played at the venue whose name has the slug `slug`, latest
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_venue_slug(db: &PgPool, slug: &str) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE slugify(venue) = $1 AND visibility = 'public' ORDER BY start_time DESC, id",
+            slug
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Every set played at the event whose name has the slug `slug`, latest
+    /// first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_event_slug(db: &PgPool, slug: &str) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE slugify(event_name) = $1 AND visibility = 'public' ORDER BY start_time DESC, id",
+            slug
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Sets starting after `now`, soonest first, for calendars.
+    pub async fn list_upcoming(db: &PgPool, now: NaiveDateTime) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE start_time > $1 AND visibility = 'public' ORDER BY start_time, id",
+            now
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn list_upcoming_by_dj_slug(
+        db: &PgPool,
+        slug: &str,
+        now: NaiveDateTime,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM sets
+            WHERE start_time > $2 AND visibility = 'public'
+            AND EXISTS (SELECT 1 FROM UNNEST(dj_names) AS dj_name WHERE slugify(dj_name) = $1)
+            ORDER BY start_time, id"#,
+            slug,
+            now
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn list_upcoming_by_venue_slug(
+        db: &PgPool,
+        slug: &str,
+        now: NaiveDateTime,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE start_time > $2 AND slugify(venue) = $1 AND visibility = 'public' ORDER BY start_time, id",
+            slug,
+            now
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn list_upcoming_by_event_slug(
+        db: &PgPool,
+        slug: &str,
+        now: NaiveDateTime,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE start_time > $2 AND slugify(event_name) = $1 AND visibility = 'public' ORDER BY start_time, id",
+            slug,
+            now
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Upcoming sets played by any DJ the user follows.
+    pub async fn list_upcoming_by_followed_djs(
+        db: &PgPool,
+        user_id: Uuid,
+        now: NaiveDateTime,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM sets
+            WHERE start_time > $2 AND visibility = 'public'
+            AND EXISTS (
+                SELECT 1 FROM dj_follows, UNNEST(sets.dj_names) AS name
+                WHERE dj_follows.user_id = $1 AND dj_follows.dj_slug = slugify(name)
+            )
+            ORDER BY start_time, id"#,
+            user_id,
+            now
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Sets added by the users, or played by the DJs or at the venues `user_id`
+    /// follows, latest first.
+    pub async fn list_page_followed_by(
+        db: &PgPool,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM sets
+            WHERE visibility = 'public'
+            AND (
+                creator_id IN (SELECT followee_id FROM user_follows WHERE follower_id = $1)
+                OR slugify(venue) IN (SELECT venue_slug FROM venue_follows WHERE user_id = $1)
+                OR EXISTS (
+                    SELECT 1 FROM dj_follows, UNNEST(sets.dj_names) AS name
+                    WHERE dj_follows.user_id = $1 AND dj_follows.dj_slug = slugify(name)
+                )
+            )
+            ORDER BY start_time DESC, created_at DESC, id DESC
+            LIMIT $2 OFFSET $3"#,
+            user_id,
+            limit,
+            offset
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Sets of the feed of `user_id` created or updated after `since`, latest
+    /// first.
+    pub async fn list_followed_by_updated_since(
+        db: &PgPool,
+        user_id: Uuid,
+        since: NaiveDateTime,
+        limit: i64,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM sets
+            WHERE updated_at > $2 AND visibility = 'public'
+            AND (
+                creator_id IN (SELECT followee_id FROM user_follows WHERE follower_id = $1)
+                OR slugify(venue) IN (SELECT venue_slug FROM venue_follows WHERE user_id = $1)
+                OR EXISTS (
+                    SELECT 1 FROM dj_follows, UNNEST(sets.dj_names) AS name
+                    WHERE dj_follows.user_id = $1 AND dj_follows.dj_slug = slugify(name)
+                )
+            )
+            ORDER BY start_time DESC, created_at DESC, id DESC
+            LIMIT $3"#,
+            user_id,
+            since,
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn count_followed_by(db: &PgPool, user_id: Uuid) -> ModelResult<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM sets
+            WHERE visibility = 'public'
+            AND (
+                creator_id IN (SELECT followee_id FROM user_follows WHERE follower_id = $1)
+                OR slugify(venue) IN (SELECT venue_slug FROM venue_follows WHERE user_id = $1)
+                OR EXISTS (
+                    SELECT 1 FROM dj_follows, UNNEST(sets.dj_names) AS name
+                    WHERE dj_follows.user_id = $1 AND dj_follows.dj_slug = slugify(name)
+                )
+            )"#,
+            user_id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// Public sets the user liked, latest first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_liked_by(db: &PgPool, user_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT sets.* FROM sets JOIN set_likes ON set_likes.set_id = sets.id
+            WHERE set_likes.user_id = $1 AND sets.visibility = 'public'
+            ORDER BY sets.start_time DESC, sets.id"#,
+            user_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Public sets the user was at, latest first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_attended_by(db: &PgPool, user_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT sets.* FROM sets JOIN attendances ON attendances.set_id = sets.id
+            WHERE attendances.user_id = $1 AND sets.visibility = 'public'
+            ORDER BY sets.start_time DESC, sets.id"#,
+            user_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_stats(db: &PgPool, ids: &[Uuid]) -> ModelResult<Vec<Stats>> {
+        Ok(sqlx::query_as!(
+            Stats,
+            r#"SELECT
+                sets.id AS set_id,
+                (SELECT COUNT(*) FROM set_likes WHERE set_id = sets.id) AS "likes!",
+                (SELECT COUNT(*) FROM attendances WHERE set_id = sets.id) AS "attendees!",
+                (SELECT COUNT(*) FROM set_ratings WHERE set_id = sets.id) AS "ratings!",
+                (SELECT AVG(rating)::float8 FROM set_ratings WHERE set_id = sets.id)
+                    AS average_rating
+            FROM sets WHERE id = ANY($1)"#,
+            ids
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn find_stats(&self, db: &PgPool) -> ModelResult<Stats> {
+        let stats = Self::list_stats(db, &[self.id]).await?;
+        stats.into_iter().next().ok_or(ModelError::EntityNotFound)
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn find_reactions(&self, db: &PgPool, user_id: Uuid) -> ModelResult<Reactions> {
+        Ok(sqlx::query_as!(
+            Reactions,
+            r#"SELECT
+                EXISTS (SELECT 1 FROM set_likes WHERE user_id = $1 AND set_id = $2) AS "liked!",
+                EXISTS (SELECT 1 FROM attendances WHERE user_id = $1 AND set_id = $2)
+                    AS "attended!",
+                (SELECT rating FROM set_ratings WHERE user_id = $1 AND set_id = $2) AS rating"#,
+            user_id,
+            self.id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
     pub async fn list_all(db: &PgPool) -> ModelResult<Vec<Self>> {
-        Ok(sqlx::query_as!(Self, "SELECT * FROM sets")
+        Ok(
+            sqlx::query_as!(Self, "SELECT * FROM sets WHERE visibility = 'public'")
+                .fetch_all(db)
+                .await?,
+        )
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_ids(db: &PgPool) -> ModelResult<Vec<Uuid>> {
+        Ok(sqlx::query_scalar!("SELECT id FROM sets ORDER BY id")
             .fetch_all(db)
             .await?)
     }
 
-    pub async fn insert(&self, db: &PgPool) -> ModelResult<()> {
+    /// The public sets of `ids` that still exist, in no particular order.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_ids(db: &PgPool, ids: &[Uuid]) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM sets WHERE id = ANY($1) AND visibility = 'public'",
+            ids
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Sets tagged with the tag `tag_id`, latest first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_tag_id(db: &PgPool, tag_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT sets.* FROM sets JOIN set_tags ON set_tags.set_id = sets.id
+            WHERE set_tags.tag_id = $1 AND sets.visibility = 'public'
+            ORDER BY sets.start_time DESC, sets.id"#,
+            tag_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn insert(&self, db: &mut PgConnection) -> ModelResult<()> {
         sqlx::query!(
-            r#"INSERT INTO sets (id, creator_id, dj_names, venue, city, event_name, start_time, duration_seconds)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+            r#"INSERT INTO sets (id, creator_id, dj_names, venue, city, event_name, start_time, duration_seconds, visibility)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)"#,
             self.id,
             self.creator_id,
             &self.dj_names,
@@ -57,26 +645,50 @@ impl Model {
             self.city,
             self.event_name,
             self.start_time,
-            self.duration_seconds
+            self.duration_seconds,
+            self.visibility().as_str()
         )
         .execute(db)
         .await?;
         Ok(())
     }
 
-    pub async fn update(&self, db: &PgPool) -> ModelResult<()> {
+    pub async fn update(&self, db: &mut PgConnection) -> ModelResult<()> {
         sqlx::query!(
             r#"UPDATE sets
-            SET dj_names = $1, venue = $2, city = $3, event_name = $4, start_time = $5, duration_seconds = $6"#,
+            SET dj_names = $1, venue = $2, city = $3, event_name = $4, start_time = $5, duration_seconds = $6, updated_at = $7, visibility = $8
+            WHERE id = $9"#,
             &self.dj_names,
             self.venue,
             self.city,
             self.event_name,
             self.start_time,
             self.duration_seconds,
+            self.updated_at,
+            self.visibility().as_str(),
+            self.id,
         )
         .execute(db)
         .await?;
         Ok(())
     }
+
+    /// Replaces the flyer of a set, returning the upload of the previous one.
+    pub async fn set_flyer(
+        db: &PgPool,
+        id: Uuid,
+        upload_id: Option<Uuid>,
+    ) -> ModelResult<Option<Uuid>> {
+        let previous = sqlx::query_scalar!(
+            r#"UPDATE sets SET flyer_upload_id = $2, updated_at = CURRENT_TIMESTAMP
+            FROM (SELECT flyer_upload_id FROM sets WHERE id = $1 FOR UPDATE) AS previous
+            WHERE id = $1
+            RETURNING previous.flyer_upload_id"#,
+            id,
+            upload_id
+        )
+        .fetch_optional(db)
+        .await?;
+        previous.ok_or(ModelError::EntityNotFound)
+    }
 }
@@ -0,0 +1,240 @@
+use chrono::NaiveDateTime;
+use loco_rs::model::ModelResult;
+use serde::Serialize;
+use sqlx::PgPool;
+
+/// The kinds of public pages listed in sitemaps. DJs, venues and events are
+/// not stored on their own, their pages are found through the names of sets.
+/// Only public sets and collections are listed, and only the pages found
+/// through public sets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Section {
+    Sets,
+    Djs,
+    Venues,
+    Events,
+    Users,
+    Tags,
+    Collections,
+}
+
+/// A page to list, by what identifies it within its section.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Entry {
+    pub key: String,
+    pub lastmod: NaiveDateTime,
+}
+
+impl Section {
+    pub const ALL: [Self; 7] = [
+        Self::Sets,
+        Self::Djs,
+        Self::Venues,
+        Self::Events,
+        Self::Users,
+        Self::Tags,
+        Self::Collections,
+    ];
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Sets => "sets",
+            Self::Djs => "djs",
+            Self::Venues => "venues",
+            Self::Events => "events",
+            Self::Users => "users",
+            Self::Tags => "tags",
+            Self::Collections => "collections",
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|section| section.name() == name)
+    }
+
+    /// Segments of the path of the page of `key`, to be URL encoded.
+    #[must_use]
+    pub fn path_segments(self, key: &str) -> [String; 2] {
+        match self {
+            Self::Sets => ["sets".to_string(), key.to_string()],
+            Self::Djs => ["djs".to_string(), key.to_string()],
+            Self::Venues => ["venues".to_string(), key.to_string()],
+            Self::Events => ["events".to_string(), key.to_string()],
+            Self::Users => ["user".to_string(), key.to_string()],
+            Self::Tags => ["tags".to_string(), key.to_string()],
+            Self::Collections => ["collections".to_string(), key.to_string()],
+        }
+    }
+
+    /// How many pages the section lists.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn count(self, db: &PgPool) -> ModelResult<i64> {
+        let count = match self {
+            Self::Sets => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(*) AS "count!" FROM sets WHERE visibility = 'public'"#
+                )
+                .fetch_one(db)
+                .await?
+            }
+            Self::Djs => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(DISTINCT slugify(dj_name)) AS "count!"
+                    FROM sets, UNNEST(dj_names) AS dj_name
+                    WHERE slugify(dj_name) <> '' AND visibility = 'public'"#
+                )
+                .fetch_one(db)
+                .await?
+            }
+            Self::Venues => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(DISTINCT slugify(venue)) AS "count!" FROM sets
+                    WHERE slugify(venue) <> '' AND visibility = 'public'"#
+                )
+                .fetch_one(db)
+                .await?
+            }
+            Self::Events => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(DISTINCT slugify(event_name)) AS "count!" FROM sets
+                    WHERE slugify(event_name) <> '' AND visibility = 'public'"#
+                )
+                .fetch_one(db)
+                .await?
+            }
+            Self::Users => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(DISTINCT creator_id) AS "count!" FROM sets
+                    WHERE visibility = 'public'"#
+                )
+                .fetch_one(db)
+                .await?
+            }
+            Self::Tags => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(DISTINCT tags.id) AS "count!" FROM tags
+                    JOIN set_tags ON set_tags.tag_id = tags.id
+                    JOIN sets ON sets.id = set_tags.set_id
+                    WHERE tags.merged_into_id IS NULL AND sets.visibility = 'public'"#
+                )
+                .fetch_one(db)
+                .await?
+            }
+            Self::Collections => {
+                sqlx::query_scalar!(
+                    r#"SELECT COUNT(*) AS "count!" FROM collections WHERE is_public"#
+                )
+                .fetch_one(db)
+                .await?
+            }
+        };
+        Ok(count)
+    }
+
+    /// A page of the pages the section lists, with when they last changed.
+    /// Profiles, DJs, venues, events and tags change with their sets.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_page(self, db: &PgPool, limit: i64, offset: i64) -> ModelResult<Vec<Entry>> {
+        let entries = match self {
+            Self::Sets => {
+                sqlx::query_as!(
+                    Entry,
+                    r#"SELECT id::text AS "key!", updated_at AS "lastmod!" FROM sets
+                    WHERE visibility = 'public'
+                    ORDER BY id LIMIT $1 OFFSET $2"#,
+                    limit,
+                    offset
+                )
+                .fetch_all(db)
+                .await?
+            }
+            Self::Djs => {
+                sqlx::query_as!(
+                    Entry,
+                    r#"SELECT slugify(dj_name) AS "key!", MAX(updated_at) AS "lastmod!"
+                    FROM sets, UNNEST(dj_names) AS dj_name
+                    WHERE slugify(dj_name) <> '' AND visibility = 'public'
+                    GROUP BY 1 ORDER BY 1 LIMIT $1 OFFSET $2"#,
+                    limit,
+                    offset
+                )
+                .fetch_all(db)
+                .await?
+            }
+            Self::Venues => {
+                sqlx::query_as!(
+                    Entry,
+                    r#"SELECT slugify(venue) AS "key!", MAX(updated_at) AS "lastmod!" FROM sets
+                    WHERE slugify(venue) <> '' AND visibility = 'public'
+                    GROUP BY 1 ORDER BY 1 LIMIT $1 OFFSET $2"#,
+                    limit,
+                    offset
+                )
+                .fetch_all(db)
+                .await?
+            }
+            Self::Events => {
+                sqlx::query_as!(
+                    Entry,
+                    r#"SELECT slugify(event_name) AS "key!", MAX(updated_at) AS "lastmod!"
+                    FROM sets
+                    WHERE slugify(event_name) <> '' AND visibility = 'public'
+                    GROUP BY 1 ORDER BY 1 LIMIT $1 OFFSET $2"#,
+                    limit,
+                    offset
+                )
+                .fetch_all(db)
+                .await?
+            }
+            Self::Users => {
+                sqlx::query_as!(
+                    Entry,
+                    r#"SELECT users.username AS "key!",
+                    GREATEST(users.updated_at, MAX(sets.updated_at)) AS "lastmod!"
+                    FROM users JOIN sets ON sets.creator_id = users.id
+                    WHERE sets.visibility = 'public'
+                    GROUP BY users.id ORDER BY users.id LIMIT $1 OFFSET $2"#,
+                    limit,
+                    offset
+                )
+                .fetch_all(db)
+                .await?
+            }
+            Self::Tags => {
+                sqlx::query_as!(
+                    Entry,
+                    r#"SELECT tags.slug AS "key!", MAX(sets.updated_at) AS "lastmod!" FROM tags
+                    JOIN set_tags ON set_tags.tag_id = tags.id
+                    JOIN sets ON sets.id = set_tags.set_id
+                    WHERE tags.merged_into_id IS NULL AND sets.visibility = 'public'
+                    GROUP BY tags.id ORDER BY tags.id LIMIT $1 OFFSET $2"#,
+                    limit,
+                    offset
+                )
+                .fetch_all(db)
+                .await?
+            }
+            Self::Collections => {
+                sqlx::query_as!(
+                    Entry,
+                    r#"SELECT id::text AS "key!", updated_at AS "lastmod!" FROM collections
+                    WHERE is_public
+                    ORDER BY id LIMIT $1 OFFSET $2"#,
+                    limit,
+                    offset
+                )
+                .fetch_all(db)
+                .await?
+            }
+        };
+        Ok(entries)
+    }
+}
@@ -1,19 +1,27 @@
+use chrono::Utc;
 use loco_rs::model::ModelResult;
 use serde::{Deserialize, Serialize};
-use sqlx::PgPool;
+use sqlx::{PgConnection, PgPool};
 use uuid::Uuid;
 
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
-struct Model {
-    setlist_id: Uuid,
-    track_title: String,
-    track_artist: String,
-    track_number: Option<i32>,
-    track_start_time_offset_seconds: Option<i32>,
-    track_duration_seconds: Option<i32>,
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub setlist_id: Uuid,
+    pub track_title: String,
+    pub track_artist: String,
+    pub track_number: Option<i32>,
+    pub track_start_time_offset_seconds: Option<i32>,
+    pub track_duration_seconds: Option<i32>,
+    pub track_bpm: Option<f64>,
+    /// In the usual notation, like "Am" or "F#".
+    pub track_key: Option<String>,
+    pub track_label: Option<String>,
+    pub track_release_year: Option<i32>,
+    pub track_remixer: Option<String>,
 }
 
 impl Model {
+    #[must_use]
     pub fn from_song_and_setlist_id(
         song: crate::controllers::sets::Song,
         setlist_id: Uuid,
@@ -25,52 +33,108 @@ impl Model {
             track_number: song.track_number,
             track_start_time_offset_seconds: song.track_start_time_offset_seconds,
             track_duration_seconds: song.track_duration_seconds,
+            track_bpm: song.track_bpm,
+            track_key: song.track_key.map(|key| key.to_string()),
+            track_label: song.track_label,
+            track_release_year: song.track_release_year,
+            track_remixer: song.track_remixer,
         }
     }
 
+    /// Whether the track has been identified. Unknown tracks are listed as
+    /// "ID" by convention.
+    #[must_use]
+    pub fn is_identified(&self) -> bool {
+        [&self.track_title, &self.track_artist]
+            .iter()
+            .all(|field| !field.trim().is_empty() && !field.trim().eq_ignore_ascii_case("ID"))
+    }
+
+    /// Tracks of `after` that were unidentified at the same track number in
+    /// `before`.
+    #[must_use]
+    pub fn newly_identified<'a>(before: &[Self], after: &'a [Self]) -> Vec<&'a Self> {
+        after
+            .iter()
+            .filter(|song| song.is_identified())
+            .filter(|song| {
+                before.iter().any(|previous| {
+                    previous.track_number == song.track_number && !previous.is_identified()
+                })
+            })
+            .collect()
+    }
+
     pub async fn list_by_setlist_id(db: &PgPool, setlist_id: Uuid) -> ModelResult<Vec<Self>> {
         Ok(sqlx::query_as!(
             Self,
-            "SELECT * FROM setlist_songs WHERE setlist_id = $1",
+            "SELECT * FROM setlist_songs WHERE setlist_id = $1 ORDER BY track_number",
             setlist_id
         )
         .fetch_all(db)
         .await?)
     }
 
-    pub async fn insert_many(db: &PgPool, many: &[Self]) -> ModelResult<()> {
-        for song in many {
-            song.insert(db).await?;
-        }
-        Ok(())
-    }
-
-    pub async fn insert(&self, db: &PgPool) -> ModelResult<()> {
-        sqlx::query_as!(
+    /// The tracklists of several sets, ordered by set and track number.
+    pub async fn list_by_setlist_ids(db: &PgPool, setlist_ids: &[Uuid]) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
             Self,
-            r#"INSERT INTO setlist_songs
-            (setlist_id, track_title, track_artist, track_number, track_start_time_offset_seconds, track_duration_seconds) VALUES ($1, $2, $3, $4, $5, $6)"#,
-            self.setlist_id,
-            &self.track_title,
-            &self.track_artist,
-            self.track_number,
-            self.track_start_time_offset_seconds,
-            self.track_duration_seconds
+            "SELECT * FROM setlist_songs WHERE setlist_id = ANY($1) ORDER BY setlist_id, track_number",
+            setlist_ids
         )
-        .execute(db)
-        .await?;
-        Ok(())
+        .fetch_all(db)
+        .await?)
     }
 
-    pub async fn update(&self, db: &PgPool, many: &[Self], setlist_id: Uuid) -> ModelResult<()> {
-        // TODO this is so dumb
+    /// Replaces the whole tracklist of a set. Tracks without a track number
+    /// are numbered by their position in `many`. The set counts as updated,
+    /// so that feeds and caches keyed on its `updated_at` pick up the change.
+    /// Meant to run in the transaction which changes the rest of the set.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn replace_for_setlist(
+        db: &mut PgConnection,
+        setlist_id: Uuid,
+        many: &[Self],
+    ) -> ModelResult<()> {
         sqlx::query!(
             "DELETE FROM setlist_songs WHERE setlist_id = $1",
             setlist_id
         )
-        .execute(db)
+        .execute(&mut *db)
         .await?;
 
-        Self::insert_many(db, many).await
+        for (position, song) in (1..).zip(many) {
+            sqlx::query!(
+                r#"INSERT INTO setlist_songs
+                (setlist_id, track_title, track_artist, track_number, track_start_time_offset_seconds, track_duration_seconds,
+                track_bpm, track_key, track_label, track_release_year, track_remixer)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)"#,
+                setlist_id,
+                &song.track_title,
+                &song.track_artist,
+                song.track_number.or(Some(position)),
+                song.track_start_time_offset_seconds,
+                song.track_duration_seconds,
+                song.track_bpm,
+                song.track_key,
+                song.track_label,
+                song.track_release_year,
+                song.track_remixer
+            )
+            .execute(&mut *db)
+            .await?;
+        }
+
+        sqlx::query!(
+            "UPDATE sets SET updated_at = $1 WHERE id = $2",
+            Utc::now().naive_utc(),
+            setlist_id
+        )
+        .execute(&mut *db)
+        .await?;
+        Ok(())
     }
 }
@@ -0,0 +1,197 @@
+use chrono::NaiveDate;
+use loco_rs::model::ModelResult;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+/// The materialized views holding the statistics, see the `stats` migration.
+const VIEWS: [&str; 6] = [
+    "stats_top_tracks",
+    "stats_top_artists",
+    "stats_top_djs",
+    "stats_top_venues",
+    "stats_months",
+    "stats_summaries",
+];
+
+/// How far back statistics look, counting sets by when they were played.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Window {
+    /// The past 30 days.
+    Month,
+    /// The past year.
+    Year,
+    #[default]
+    All,
+}
+
+impl Window {
+    pub const ALL: [Self; 3] = [Self::Month, Self::Year, Self::All];
+
+    /// The name of the window in `stats_windows`.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Month => "month",
+            Self::Year => "year",
+            Self::All => "all",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Track {
+    pub artist: String,
+    pub title: String,
+    pub plays: i64,
+    pub sets: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Artist {
+    pub artist: String,
+    pub plays: i64,
+    pub sets: i64,
+}
+
+/// A DJ or a venue, with the sets played by or at them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Ranked {
+    pub slug: String,
+    pub name: String,
+    pub sets: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Month {
+    pub month: NaiveDate,
+    pub sets: i64,
+    pub average_duration_seconds: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub sets: i64,
+    pub average_duration_seconds: Option<f64>,
+}
+
+/// Recomputes all statistics. Readers keep seeing the previous ones until
+/// done.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn refresh(db: &PgPool) -> ModelResult<()> {
+    for view in VIEWS {
+        sqlx::query(&format!("REFRESH MATERIALIZED VIEW CONCURRENTLY {view}"))
+            .execute(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// The most played identified tracks, telling apart tracks only by case or
+/// surrounding whitespace.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn list_top_tracks(db: &PgPool, window: Window, limit: i64) -> ModelResult<Vec<Track>> {
+    Ok(sqlx::query_as!(
+        Track,
+        r#"SELECT artist AS "artist!", title AS "title!", plays AS "plays!", sets AS "sets!"
+        FROM stats_top_tracks WHERE window_name = $1
+        ORDER BY plays DESC, sets DESC, artist_key, title_key LIMIT $2"#,
+        window.name(),
+        limit
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// # Errors
+///
+/// when has DB query error
+pub async fn list_top_artists(db: &PgPool, window: Window, limit: i64) -> ModelResult<Vec<Artist>> {
+    Ok(sqlx::query_as!(
+        Artist,
+        r#"SELECT artist AS "artist!", plays AS "plays!", sets AS "sets!"
+        FROM stats_top_artists WHERE window_name = $1
+        ORDER BY plays DESC, sets DESC, artist_key LIMIT $2"#,
+        window.name(),
+        limit
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// The DJs who played the most sets.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn list_top_djs(db: &PgPool, window: Window, limit: i64) -> ModelResult<Vec<Ranked>> {
+    Ok(sqlx::query_as!(
+        Ranked,
+        r#"SELECT slug AS "slug!", name AS "name!", sets AS "sets!"
+        FROM stats_top_djs WHERE window_name = $1
+        ORDER BY sets DESC, slug LIMIT $2"#,
+        window.name(),
+        limit
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// The venues most sets were played at.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn list_top_venues(db: &PgPool, window: Window, limit: i64) -> ModelResult<Vec<Ranked>> {
+    Ok(sqlx::query_as!(
+        Ranked,
+        r#"SELECT slug AS "slug!", name AS "name!", sets AS "sets!"
+        FROM stats_top_venues WHERE window_name = $1
+        ORDER BY sets DESC, slug LIMIT $2"#,
+        window.name(),
+        limit
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// The months since `since` in which sets were played, oldest first.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn list_months(db: &PgPool, since: NaiveDate) -> ModelResult<Vec<Month>> {
+    Ok(sqlx::query_as!(
+        Month,
+        r#"SELECT month AS "month!", sets AS "sets!", average_duration_seconds
+        FROM stats_months WHERE month >= $1
+        ORDER BY month"#,
+        since
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// # Errors
+///
+/// when has DB query error
+pub async fn find_summary(db: &PgPool, window: Window) -> ModelResult<Summary> {
+    let summary = sqlx::query_as!(
+        Summary,
+        r#"SELECT sets AS "sets!", average_duration_seconds
+        FROM stats_summaries WHERE window_name = $1"#,
+        window.name()
+    )
+    .fetch_optional(db)
+    .await?;
+    Ok(summary.unwrap_or(Summary {
+        sets: 0,
+        average_duration_seconds: None,
+    }))
+}
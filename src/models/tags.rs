@@ -0,0 +1,314 @@
+use chrono::NaiveDateTime;
+use loco_rs::model::{ModelError, ModelResult};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgConnection, PgPool};
+
+use crate::utils::slugify;
+
+/// Most tags a set can have.
+const MAX_TAGS_PER_SET: usize = 10;
+/// Longest name a tag can have.
+const MAX_NAME_LENGTH: usize = 50;
+
+fn validate_name(name: &str) -> ModelResult<String> {
+    let slug = slugify(name);
+    if slug.is_empty() || name.trim().chars().count() > MAX_NAME_LENGTH {
+        return Err(ModelError::Any(eyre::eyre!(
+            "A tag needs a name of at most {MAX_NAME_LENGTH} characters"
+        )));
+    }
+    Ok(slug)
+}
+
+/// Checks the tag names given for a set, blank names being ignored.
+///
+/// # Errors
+///
+/// When there are too many tags or a name is invalid
+pub fn validate_names(names: &[String]) -> ModelResult<Vec<&str>> {
+    let names = names
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>();
+    if names.len() > MAX_TAGS_PER_SET {
+        return Err(ModelError::Any(eyre::eyre!(
+            "A set has at most {MAX_TAGS_PER_SET} tags"
+        )));
+    }
+    for name in &names {
+        validate_name(name)?;
+    }
+    Ok(names)
+}
+
+/// A genre or free-form tag. Synonyms are merged into a single tag, and
+/// point to it with `merged_into_id`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub is_genre: bool,
+    pub merged_into_id: Option<Uuid>,
+}
+
+/// A tag of one of several sets.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Default)]
+pub struct SetTag {
+    pub set_id: Uuid,
+    pub slug: String,
+    pub name: String,
+    pub is_genre: bool,
+}
+
+/// A tag with how many sets have it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Default)]
+pub struct TagCount {
+    pub slug: String,
+    pub name: String,
+    pub is_genre: bool,
+    pub sets: i64,
+}
+
+impl Model {
+    /// # Errors
+    ///
+    /// When could not find the tag or DB query error
+    pub async fn find_by_slug(db: &PgPool, slug: &str) -> ModelResult<Self> {
+        let item = sqlx::query_as!(Self, "SELECT * FROM tags WHERE slug = $1", slug)
+            .fetch_optional(db)
+            .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// The tag named `name`, created if it is new, or the tag it was merged
+    /// into.
+    ///
+    /// # Errors
+    ///
+    /// When the name is empty or too long, or DB query error
+    pub async fn find_or_create(db: &mut PgConnection, name: &str) -> ModelResult<Self> {
+        let name = name.trim();
+        let slug = validate_name(name)?;
+
+        let item = sqlx::query_as!(
+            Self,
+            r#"INSERT INTO tags (id, slug, name) VALUES ($1, $2, $3)
+            ON CONFLICT (slug) DO UPDATE SET slug = EXCLUDED.slug
+            RETURNING *"#,
+            Uuid::now_v7(),
+            slug,
+            name
+        )
+        .fetch_one(&mut *db)
+        .await?;
+        match item.merged_into_id {
+            Some(id) => Ok(
+                sqlx::query_as!(Self, "SELECT * FROM tags WHERE id = $1", id)
+                    .fetch_one(&mut *db)
+                    .await?,
+            ),
+            None => Ok(item),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// When could not find the tag or DB query error
+    pub async fn find_by_id(db: &PgPool, id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(Self, "SELECT * FROM tags WHERE id = $1", id)
+            .fetch_optional(db)
+            .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Tags of a set, genres first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_set_id(db: &PgPool, set_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT tags.* FROM tags JOIN set_tags ON set_tags.tag_id = tags.id
+            WHERE set_tags.set_id = $1
+            ORDER BY tags.is_genre DESC, tags.name"#,
+            set_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Tags of several sets, genres first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_set_ids(db: &PgPool, set_ids: &[Uuid]) -> ModelResult<Vec<SetTag>> {
+        Ok(sqlx::query_as!(
+            SetTag,
+            r#"SELECT set_tags.set_id, tags.slug, tags.name, tags.is_genre
+            FROM tags JOIN set_tags ON set_tags.tag_id = tags.id
+            WHERE set_tags.set_id = ANY($1)
+            ORDER BY tags.is_genre DESC, tags.name"#,
+            set_ids
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Replaces the tags of a set with the ones named `names`, creating new
+    /// tags as needed. Meant to run in the transaction which changes the rest
+    /// of the set.
+    ///
+    /// # Errors
+    ///
+    /// When there are too many tags or a name is invalid, or DB query error
+    pub async fn replace_for_set(
+        db: &mut PgConnection,
+        set_id: Uuid,
+        names: &[String],
+    ) -> ModelResult<()> {
+        let mut ids = vec![];
+        for name in validate_names(names)? {
+            ids.push(Self::find_or_create(db, name).await?.id);
+        }
+
+        sqlx::query!("DELETE FROM set_tags WHERE set_id = $1", set_id)
+            .execute(&mut *db)
+            .await?;
+        sqlx::query!(
+            r#"INSERT INTO set_tags (set_id, tag_id)
+            SELECT $1, UNNEST($2::uuid[])
+            ON CONFLICT DO NOTHING"#,
+            set_id,
+            &ids
+        )
+        .execute(&mut *db)
+        .await?;
+        Ok(())
+    }
+
+    /// Tags starting with `prefix` for autocompletion, genres and the most
+    /// used first. Synonyms are left out in favour of the tags they were
+    /// merged into.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn suggest(db: &PgPool, prefix: &str, limit: i64) -> ModelResult<Vec<Self>> {
+        let slug = slugify(prefix);
+        if slug.is_empty() {
+            return Ok(vec![]);
+        }
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT tags.* FROM tags
+            WHERE tags.merged_into_id IS NULL
+                AND (
+                    tags.slug LIKE $1 || '%'
+                    OR tags.id IN (
+                        SELECT merged_into_id FROM tags synonyms WHERE synonyms.slug LIKE $1 || '%'
+                    )
+                )
+            ORDER BY
+                tags.is_genre DESC,
+                (SELECT COUNT(*) FROM set_tags WHERE set_tags.tag_id = tags.id) DESC,
+                tags.name
+            LIMIT $2"#,
+            slug,
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Tags with sets, genres and the most used first.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_popular(db: &PgPool, limit: i64) -> ModelResult<Vec<TagCount>> {
+        Ok(sqlx::query_as!(
+            TagCount,
+            r#"SELECT tags.slug, tags.name, tags.is_genre, COUNT(*) AS "sets!"
+            FROM tags JOIN set_tags ON set_tags.tag_id = tags.id
+            GROUP BY tags.id
+            ORDER BY tags.is_genre DESC, COUNT(*) DESC, tags.name
+            LIMIT $1"#,
+            limit
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Synonyms merged into this tag.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_synonyms(&self, db: &PgPool) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM tags WHERE merged_into_id = $1 ORDER BY name",
+            self.id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn set_genre(&mut self, db: &PgPool, is_genre: bool) -> ModelResult<()> {
+        self.is_genre = is_genre;
+        sqlx::query!(
+            "UPDATE tags SET is_genre = $1 WHERE id = $2",
+            is_genre,
+            self.id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// Makes this tag a synonym of `target`, moving its sets and synonyms
+    /// over.
+    ///
+    /// # Errors
+    ///
+    /// When `target` is this tag or a synonym itself, or DB query error
+    pub async fn merge_into(&mut self, db: &PgPool, target: &Self) -> ModelResult<()> {
+        if target.id == self.id || target.merged_into_id.is_some() {
+            return Err(ModelError::Any(eyre::eyre!(
+                "Tags can only be merged into another tag which is not a synonym"
+            )));
+        }
+
+        let mut tx = db.begin().await?;
+        sqlx::query!(
+            r#"INSERT INTO set_tags (set_id, tag_id)
+            SELECT set_id, $2 FROM set_tags WHERE tag_id = $1
+            ON CONFLICT DO NOTHING"#,
+            self.id,
+            target.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query!("DELETE FROM set_tags WHERE tag_id = $1", self.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query!(
+            "UPDATE tags SET merged_into_id = $2 WHERE id = $1 OR merged_into_id = $1",
+            self.id,
+            target.id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        self.merged_into_id = Some(target.id);
+        Ok(())
+    }
+}
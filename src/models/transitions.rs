@@ -0,0 +1,214 @@
+use loco_rs::model::{ModelError, ModelResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use crate::utils::slugify;
+
+/// A track, told apart from others by the slugs of its artist and title.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Track {
+    pub artist: String,
+    pub title: String,
+    pub artist_slug: String,
+    pub title_slug: String,
+    /// How often the track was played, in any set.
+    pub plays: i64,
+}
+
+/// A track played right before or after another.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Neighbour {
+    pub artist: String,
+    pub title: String,
+    pub artist_slug: String,
+    pub title_slug: String,
+    /// How often one was played right after the other.
+    pub transitions: i64,
+    /// In how many sets.
+    pub sets: i64,
+}
+
+/// An edge of the transition graph, from a track to the one played after it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Transition {
+    pub from_artist: String,
+    pub from_title: String,
+    pub from_artist_slug: String,
+    pub from_title_slug: String,
+    pub to_artist: String,
+    pub to_title: String,
+    pub to_artist_slug: String,
+    pub to_title_slug: String,
+    pub transitions: i64,
+    pub sets: i64,
+}
+
+/// Path of the page of an identified track, unless its artist or title has
+/// no slug.
+#[must_use]
+pub fn track_path(artist: &str, title: &str) -> Option<String> {
+    let (artist_slug, title_slug) = (slugify(artist), slugify(title));
+    (!artist_slug.is_empty() && !title_slug.is_empty())
+        .then(|| format!("/tracks/{artist_slug}/{title_slug}"))
+}
+
+/// Recomputes the transition graph from all tracklists.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn refresh(db: &PgPool) -> ModelResult<()> {
+    sqlx::query!("REFRESH MATERIALIZED VIEW CONCURRENTLY track_transitions")
+        .execute(db)
+        .await?;
+    Ok(())
+}
+
+/// # Errors
+///
+/// When no track has these slugs, or has DB query error
+pub async fn find_track(db: &PgPool, artist_slug: &str, title_slug: &str) -> ModelResult<Track> {
+    let track = sqlx::query_as!(
+        Track,
+        r#"SELECT min(btrim(track_artist)) AS "artist!", min(btrim(track_title)) AS "title!",
+        slugify(track_artist) AS "artist_slug!", slugify(track_title) AS "title_slug!",
+        count(*) AS "plays!"
+        FROM setlist_songs
+        WHERE slugify(track_artist) = $1 AND slugify(track_title) = $2
+            AND upper(btrim(track_artist)) NOT IN ('', 'ID')
+            AND upper(btrim(track_title)) NOT IN ('', 'ID')
+        GROUP BY 3, 4"#,
+        artist_slug,
+        title_slug
+    )
+    .fetch_optional(db)
+    .await?;
+    track.ok_or(ModelError::EntityNotFound)
+}
+
+/// Identified tracks whose artist or title contains `query`, the most played
+/// first.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn search_tracks(db: &PgPool, query: &str, limit: i64) -> ModelResult<Vec<Track>> {
+    let pattern = format!(
+        "%{}%",
+        query
+            .trim()
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    Ok(sqlx::query_as!(
+        Track,
+        r#"SELECT min(btrim(track_artist)) AS "artist!", min(btrim(track_title)) AS "title!",
+        slugify(track_artist) AS "artist_slug!", slugify(track_title) AS "title_slug!",
+        count(*) AS "plays!"
+        FROM setlist_songs
+        WHERE (track_artist ILIKE $1 OR track_title ILIKE $1)
+            AND upper(btrim(track_artist)) NOT IN ('', 'ID')
+            AND upper(btrim(track_title)) NOT IN ('', 'ID')
+            AND slugify(track_artist) <> '' AND slugify(track_title) <> ''
+        GROUP BY 3, 4
+        ORDER BY 5 DESC, 3, 4
+        LIMIT $2"#,
+        pattern,
+        limit
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// The tracks most often played right after a track.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn list_after(
+    db: &PgPool,
+    artist_slug: &str,
+    title_slug: &str,
+    limit: i64,
+) -> ModelResult<Vec<Neighbour>> {
+    Ok(sqlx::query_as!(
+        Neighbour,
+        r#"SELECT to_artist AS "artist!", to_title AS "title!",
+        to_artist_slug AS "artist_slug!", to_title_slug AS "title_slug!",
+        transitions AS "transitions!", sets AS "sets!"
+        FROM track_transitions
+        WHERE from_artist_slug = $1 AND from_title_slug = $2
+        ORDER BY transitions DESC, sets DESC, to_artist_slug, to_title_slug
+        LIMIT $3"#,
+        artist_slug,
+        title_slug,
+        limit
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// The tracks most often played right before a track.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn list_before(
+    db: &PgPool,
+    artist_slug: &str,
+    title_slug: &str,
+    limit: i64,
+) -> ModelResult<Vec<Neighbour>> {
+    Ok(sqlx::query_as!(
+        Neighbour,
+        r#"SELECT from_artist AS "artist!", from_title AS "title!",
+        from_artist_slug AS "artist_slug!", from_title_slug AS "title_slug!",
+        transitions AS "transitions!", sets AS "sets!"
+        FROM track_transitions
+        WHERE to_artist_slug = $1 AND to_title_slug = $2
+        ORDER BY transitions DESC, sets DESC, from_artist_slug, from_title_slug
+        LIMIT $3"#,
+        artist_slug,
+        title_slug,
+        limit
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// A page of the whole transition graph, the most common transitions first.
+///
+/// # Errors
+///
+/// when has DB query error
+pub async fn list_page(db: &PgPool, limit: i64, offset: i64) -> ModelResult<Vec<Transition>> {
+    Ok(sqlx::query_as!(
+        Transition,
+        r#"SELECT from_artist AS "from_artist!", from_title AS "from_title!",
+        from_artist_slug AS "from_artist_slug!", from_title_slug AS "from_title_slug!",
+        to_artist AS "to_artist!", to_title AS "to_title!",
+        to_artist_slug AS "to_artist_slug!", to_title_slug AS "to_title_slug!",
+        transitions AS "transitions!", sets AS "sets!"
+        FROM track_transitions
+        ORDER BY transitions DESC, sets DESC,
+            from_artist_slug, from_title_slug, to_artist_slug, to_title_slug
+        LIMIT $1 OFFSET $2"#,
+        limit,
+        offset
+    )
+    .fetch_all(db)
+    .await?)
+}
+
+/// # Errors
+///
+/// when has DB query error
+pub async fn count(db: &PgPool) -> ModelResult<i64> {
+    Ok(
+        sqlx::query_scalar!(r#"SELECT COUNT(*) AS "count!" FROM track_transitions"#)
+            .fetch_one(db)
+            .await?,
+    )
+}
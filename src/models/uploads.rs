@@ -0,0 +1,162 @@
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+use loco_rs::model::{ModelError, ModelResult};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+/// A file uploaded by a user. The content is kept in storage, see
+/// [`Model::storage_path`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub content_type: String,
+    pub size: i64,
+    /// Names of the files derived from the upload, see [`variant_path`].
+    pub variants: Vec<String>,
+}
+
+/// Where the content of the upload with `id` is stored.
+#[must_use]
+pub fn storage_path(id: Uuid) -> PathBuf {
+    PathBuf::from(id.to_string())
+}
+
+/// Where the variant named `variant` of the upload with `id` is stored, next
+/// to the upload.
+#[must_use]
+pub fn variant_path(id: Uuid, variant: &str) -> PathBuf {
+    PathBuf::from(format!("{id}.{variant}"))
+}
+
+impl Model {
+    /// Records a file stored at [`storage_path`] of `id`, as long as it fits
+    /// in the `quota` of the user.
+    ///
+    /// # Errors
+    ///
+    /// When the user would go over their quota or DB query error
+    pub async fn create(
+        db: &PgPool,
+        id: Uuid,
+        user_id: Uuid,
+        content_type: &str,
+        size: i64,
+        quota: i64,
+    ) -> ModelResult<Self> {
+        let mut tx = db.begin().await?;
+        // locking the user makes concurrent uploads of the same user wait for
+        // each other, so that they cannot go over the quota together
+        sqlx::query!("SELECT id FROM users WHERE id = $1 FOR UPDATE", user_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+        let item = sqlx::query_as!(
+            Self,
+            r#"INSERT INTO uploads (id, user_id, content_type, size)
+            SELECT $1, $2, $3, $4::bigint
+            WHERE (SELECT COALESCE(SUM(size), 0)::bigint FROM uploads WHERE user_id = $2) + $4 <= $5
+            RETURNING *"#,
+            id,
+            user_id,
+            content_type,
+            size,
+            quota
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        item.ok_or_else(|| ModelError::Any(eyre::eyre!("This upload would exceed your quota")))
+    }
+
+    /// # Errors
+    ///
+    /// When could not find the upload or DB query error
+    pub async fn find_by_id(db: &PgPool, id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(Self, "SELECT * FROM uploads WHERE id = $1", id)
+            .fetch_optional(db)
+            .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// Bytes the user's uploads take up.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn used_bytes(db: &PgPool, user_id: Uuid) -> ModelResult<i64> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(size), 0)::bigint AS "used!" FROM uploads WHERE user_id = $1"#,
+            user_id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// Uploads of the recordings and the flyer of a set.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn list_by_set_id(db: &PgPool, set_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM uploads
+            WHERE id IN (SELECT upload_id FROM recordings WHERE set_id = $1)
+            OR id = (SELECT flyer_upload_id FROM sets WHERE id = $1)"#,
+            set_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Records that the variant named `variant` was stored.
+    ///
+    /// # Errors
+    ///
+    /// When could not find the upload or DB query error
+    pub async fn add_variant(db: &PgPool, id: Uuid, variant: &str) -> ModelResult<()> {
+        let updated = sqlx::query!(
+            r#"UPDATE uploads
+            SET variants = CASE WHEN $2 = ANY(variants) THEN variants ELSE array_append(variants, $2) END
+            WHERE id = $1"#,
+            id,
+            variant
+        )
+        .execute(db)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(ModelError::EntityNotFound);
+        }
+        Ok(())
+    }
+
+    /// Deletes the record of the upload, and with it what links to it. The
+    /// content is left in storage.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn delete(db: &PgPool, id: Uuid) -> ModelResult<()> {
+        sqlx::query!("DELETE FROM uploads WHERE id = $1", id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn storage_path(&self) -> PathBuf {
+        storage_path(self.id)
+    }
+
+    #[must_use]
+    pub fn variant_path(&self, variant: &str) -> PathBuf {
+        variant_path(self.id, variant)
+    }
+
+    #[must_use]
+    pub fn has_variant(&self, variant: &str) -> bool {
+        self.variants.iter().any(|name| name == variant)
+    }
+}
@@ -0,0 +1,74 @@
+use loco_rs::model::{ModelError, ModelResult};
+use sqlx::{types::Uuid, PgPool};
+
+/// Follows between users, the follower sees the sets added by the followee
+/// in their feed.
+pub struct Model;
+
+impl Model {
+    /// Follows another user, doing nothing when already following them.
+    ///
+    /// # Errors
+    ///
+    /// When users try to follow themselves, or when has DB query error
+    pub async fn follow(db: &PgPool, follower_id: Uuid, followee_id: Uuid) -> ModelResult<()> {
+        if follower_id == followee_id {
+            return Err(ModelError::Any(eyre::eyre!("You cannot follow yourself")));
+        }
+        sqlx::query!(
+            r#"INSERT INTO user_follows (follower_id, followee_id) VALUES ($1, $2)
+            ON CONFLICT DO NOTHING"#,
+            follower_id,
+            followee_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn unfollow(db: &PgPool, follower_id: Uuid, followee_id: Uuid) -> ModelResult<()> {
+        sqlx::query!(
+            "DELETE FROM user_follows WHERE follower_id = $1 AND followee_id = $2",
+            follower_id,
+            followee_id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn is_following(
+        db: &PgPool,
+        follower_id: Uuid,
+        followee_id: Uuid,
+    ) -> ModelResult<bool> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM user_follows WHERE follower_id = $1 AND followee_id = $2
+            ) AS "exists!""#,
+            follower_id,
+            followee_id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// Usernames of the users followed by `follower_id`.
+    pub async fn list_followee_usernames(
+        db: &PgPool,
+        follower_id: Uuid,
+    ) -> ModelResult<Vec<String>> {
+        Ok(sqlx::query_scalar!(
+            r#"SELECT users.username FROM user_follows
+            JOIN users ON users.id = user_follows.followee_id
+            WHERE user_follows.follower_id = $1
+            ORDER BY users.username"#,
+            follower_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+}
@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use chrono::offset::Utc;
 use loco_rs::{auth::jwt, hash, prelude::*};
+use schemars::JsonSchema;
 use secrecy::Secret;
 use serde::{Deserialize, Serialize};
 use sqlx::{
@@ -8,6 +9,8 @@ use sqlx::{
     PgPool,
 };
 
+use super::api_keys;
+
 #[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct Model {
     pub created_at: NaiveDateTime,
@@ -15,26 +18,31 @@ pub struct Model {
     pub id: Uuid,
     pub email: String,
     pub password: String,
-    pub api_key: Uuid,
     pub username: String,
     pub reset_token: Option<Uuid>,
     pub reset_sent_at: Option<NaiveDateTime>,
     pub email_verification_token: Option<Uuid>,
     pub email_verification_sent_at: Option<NaiveDateTime>,
     pub email_verified_at: Option<NaiveDateTime>,
+    /// Secret part of the URL of the user's calendar of followed DJs.
+    pub calendar_token: Option<Uuid>,
+    /// Moderators curate shared data, like merging synonymous tags.
+    pub is_moderator: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct LoginParams {
     pub email: String,
     #[serde(serialize_with = "loco_rs::utils::stars")]
+    #[schemars(with = "String")]
     pub password: Secret<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct RegisterParams {
     pub email: String,
     #[serde(serialize_with = "loco_rs::utils::stars")]
+    #[schemars(with = "String")]
     pub password: Secret<String>,
     pub username: String,
 }
@@ -58,11 +66,10 @@ impl Validatable for Model {
 
 #[async_trait]
 impl Authenticable for Model {
-    async fn find_by_api_key(db: &PgPool, api_key: Uuid) -> ModelResult<Self> {
-        let user = sqlx::query_as!(Self, "SELECT * FROM users WHERE api_key = $1", api_key)
-            .fetch_optional(db)
-            .await?;
-        user.ok_or(ModelError::EntityNotFound)
+    async fn find_by_api_key(db: &PgPool, api_key: &str) -> ModelResult<ApiKeyGrant<Self>> {
+        let (user_id, scopes) = api_keys::Model::use_key(db, api_key).await?;
+        let user = Self::find_by_id(db, user_id).await?;
+        Ok(ApiKeyGrant { user, scopes })
     }
 
     async fn find_by_claims_key(db: &PgPool, claims_key: Uuid) -> ModelResult<Self> {
@@ -140,6 +147,31 @@ impl Model {
         user.ok_or(ModelError::EntityNotFound)
     }
 
+    /// finds a user by the secret token of their calendar URL
+    ///
+    /// # Errors
+    ///
+    /// When could not find user by the given token or DB query error
+    /// Users following any of the DJs with the slugs `dj_slugs`.
+    pub async fn list_following_djs(db: &PgPool, dj_slugs: &[String]) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM users
+            WHERE id IN (SELECT user_id FROM dj_follows WHERE dj_slug = ANY($1))"#,
+            dj_slugs
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    pub async fn find_by_calendar_token(db: &PgPool, token: Uuid) -> ModelResult<Self> {
+        let user = sqlx::query_as!(Self, "SELECT * FROM users WHERE calendar_token = $1", token)
+            .fetch_optional(db)
+            .await?;
+
+        user.ok_or(ModelError::EntityNotFound)
+    }
+
     /// Verifies whether the provided plain password matches the hashed password
     ///
     /// # Errors
@@ -160,12 +192,10 @@ impl Model {
         let password_hash =
             hash::hash_password(&params.password).map_err(|e| ModelError::Any(e.into()))?;
         let id = Uuid::now_v7();
-        let api_key = Uuid::new_v4();
         let user = Self {
             id,
             email: params.email.to_string(),
             password: password_hash,
-            api_key,
             username: params.username.to_string(),
             ..Default::default()
         };
@@ -173,14 +203,13 @@ impl Model {
 
         let user = sqlx::query_as!(
             Self,
-            r#"INSERT INTO users (id, email, password, api_key, username, reset_token,
+            r#"INSERT INTO users (id, email, password, username, reset_token,
             reset_sent_at, email_verification_token, email_verification_sent_at, email_verified_at)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
             RETURNING *"#,
             user.id,
             user.email,
             user.password,
-            user.api_key,
             user.username,
             user.reset_token,
             user.reset_sent_at,
@@ -218,10 +247,12 @@ impl Model {
         self.updated_at = Utc::now().naive_utc();
         sqlx::query!(
             r#"UPDATE users
-               SET email_verification_sent_at = $1, email_verification_token = $2, updated_at = $3"#,
+               SET email_verification_sent_at = $1, email_verification_token = $2, updated_at = $3
+               WHERE id = $4"#,
             self.email_verification_sent_at,
             self.email_verification_token,
-            self.updated_at
+            self.updated_at,
+            self.id
         )
         .execute(db)
         .await?;
@@ -245,10 +276,11 @@ impl Model {
         self.reset_token = Some(Uuid::new_v4());
         self.updated_at = Utc::now().naive_utc();
         sqlx::query!(
-            "UPDATE users SET reset_sent_at = $1, reset_token = $2, updated_at = $3",
+            "UPDATE users SET reset_sent_at = $1, reset_token = $2, updated_at = $3 WHERE id = $4",
             self.reset_sent_at,
             self.reset_token,
-            self.updated_at
+            self.updated_at,
+            self.id
         )
         .execute(db)
         .await?;
@@ -268,9 +300,10 @@ impl Model {
         self.email_verified_at = Some(Utc::now().naive_utc());
         self.updated_at = Utc::now().naive_utc();
         sqlx::query!(
-            "UPDATE users SET email_verified_at = $1, updated_at = $2",
+            "UPDATE users SET email_verified_at = $1, updated_at = $2 WHERE id = $3",
             self.email_verified_at,
-            self.updated_at
+            self.updated_at,
+            self.id
         )
         .execute(db)
         .await?;
@@ -295,11 +328,32 @@ impl Model {
         self.reset_sent_at = None;
         self.updated_at = Utc::now().naive_utc();
         sqlx::query!(
-            "UPDATE users SET password = $1, reset_token = $2, reset_sent_at = $3, updated_at = $4",
+            "UPDATE users SET password = $1, reset_token = $2, reset_sent_at = $3, updated_at = $4 WHERE id = $5",
             self.password,
             self.reset_token,
             self.reset_sent_at,
-            self.updated_at
+            self.updated_at,
+            self.id
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// Generates a new calendar token, so the previous calendar URL stops
+    /// working.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn reset_calendar_token(&mut self, db: &PgPool) -> ModelResult<()> {
+        self.calendar_token = Some(Uuid::new_v4());
+        self.updated_at = Utc::now().naive_utc();
+        sqlx::query!(
+            "UPDATE users SET calendar_token = $1, updated_at = $2 WHERE id = $3",
+            self.calendar_token,
+            self.updated_at,
+            self.id
         )
         .execute(db)
         .await?;
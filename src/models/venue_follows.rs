@@ -0,0 +1,58 @@
+use chrono::NaiveDateTime;
+use loco_rs::model::ModelResult;
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+use crate::utils::slugify;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub user_id: Uuid,
+    pub venue_slug: String,
+    pub venue_name: String,
+}
+
+impl Model {
+    /// Follows the venue, doing nothing when the user already follows it.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn follow(db: &PgPool, user_id: Uuid, venue_name: &str) -> ModelResult<()> {
+        sqlx::query!(
+            r#"INSERT INTO venue_follows (user_id, venue_slug, venue_name) VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING"#,
+            user_id,
+            slugify(venue_name),
+            venue_name
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn unfollow(db: &PgPool, user_id: Uuid, venue_slug: &str) -> ModelResult<()> {
+        sqlx::query!(
+            "DELETE FROM venue_follows WHERE user_id = $1 AND venue_slug = $2",
+            user_id,
+            venue_slug
+        )
+        .execute(db)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn list_by_user_id(db: &PgPool, user_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM venue_follows WHERE user_id = $1 ORDER BY venue_name",
+            user_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+}
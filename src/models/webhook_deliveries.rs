@@ -0,0 +1,164 @@
+use chrono::{NaiveDateTime, Utc};
+use loco_rs::model::{ModelError, ModelResult};
+use serde::{Deserialize, Serialize};
+use sqlx::{types::Uuid, PgPool};
+
+/// How many deliveries the delivery log shows.
+const LOG_LENGTH: i64 = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeliveryStatus {
+    /// Not delivered yet, an attempt is queued.
+    Pending,
+    Succeeded,
+    /// Every attempt failed, only a manual redelivery retries it.
+    Failed,
+}
+
+impl DeliveryStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Succeeded => "succeeded",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub response_status: Option<i32>,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<NaiveDateTime>,
+    pub delivered_at: Option<NaiveDateTime>,
+}
+
+/// The outcome of one delivery attempt.
+pub struct Attempt<'a> {
+    pub status: DeliveryStatus,
+    pub response_status: Option<i32>,
+    pub error: Option<&'a str>,
+    pub next_attempt_at: Option<NaiveDateTime>,
+}
+
+impl Model {
+    /// # Errors
+    ///
+    /// When could not save the delivery into the DB
+    pub async fn create(
+        db: &PgPool,
+        webhook_id: Uuid,
+        event: &str,
+        payload: &serde_json::Value,
+    ) -> ModelResult<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"INSERT INTO webhook_deliveries (id, webhook_id, event, payload)
+            VALUES ($1, $2, $3, $4)
+            RETURNING *"#,
+            Uuid::now_v7(),
+            webhook_id,
+            event,
+            payload
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    pub async fn find_by_id(db: &PgPool, id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(Self, "SELECT * FROM webhook_deliveries WHERE id = $1", id)
+            .fetch_optional(db)
+            .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    pub async fn find_by_id_and_webhook_id(
+        db: &PgPool,
+        id: Uuid,
+        webhook_id: Uuid,
+    ) -> ModelResult<Self> {
+        let item = sqlx::query_as!(
+            Self,
+            "SELECT * FROM webhook_deliveries WHERE id = $1 AND webhook_id = $2",
+            id,
+            webhook_id
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    /// The most recent deliveries of a webhook, newest first.
+    pub async fn list_recent_by_webhook_id(
+        db: &PgPool,
+        webhook_id: Uuid,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"SELECT * FROM webhook_deliveries WHERE webhook_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2"#,
+            webhook_id,
+            LOG_LENGTH
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Records an attempt to deliver the payload.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn record_attempt(&self, db: &PgPool, attempt: &Attempt<'_>) -> ModelResult<Self> {
+        let now = Utc::now().naive_utc();
+        let delivered_at = (attempt.status == DeliveryStatus::Succeeded).then_some(now);
+        Ok(sqlx::query_as!(
+            Self,
+            r#"UPDATE webhook_deliveries
+            SET updated_at = $1, status = $2, attempts = attempts + 1, response_status = $3,
+                error = $4, next_attempt_at = $5, delivered_at = $6
+            WHERE id = $7
+            RETURNING *"#,
+            now,
+            attempt.status.as_str(),
+            attempt.response_status,
+            attempt.error,
+            attempt.next_attempt_at,
+            delivered_at,
+            self.id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    /// Marks the delivery as pending again so it can be queued once more.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn reset(&self, db: &PgPool) -> ModelResult<Self> {
+        Ok(sqlx::query_as!(
+            Self,
+            r#"UPDATE webhook_deliveries
+            SET updated_at = $1, status = $2, next_attempt_at = NULL
+            WHERE id = $3
+            RETURNING *"#,
+            Utc::now().naive_utc(),
+            DeliveryStatus::Pending.as_str(),
+            self.id
+        )
+        .fetch_one(db)
+        .await?)
+    }
+}
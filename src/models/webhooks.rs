@@ -0,0 +1,246 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use chrono::NaiveDateTime;
+use hmac::{Hmac, Mac};
+use loco_rs::model::{ModelError, ModelResult};
+use rand::{distributions::Alphanumeric, Rng as _};
+use reqwest::Url;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use sqlx::{types::Uuid, PgPool};
+
+const SECRET_PREFIX: &str = "whsec_";
+const SECRET_LENGTH: usize = 32;
+
+/// Set lifecycle events a webhook can subscribe to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum WebhookEvent {
+    #[serde(rename = "set.created")]
+    SetCreated,
+    #[serde(rename = "set.updated")]
+    SetUpdated,
+    #[serde(rename = "set.deleted")]
+    SetDeleted,
+    #[serde(rename = "set.track_identified")]
+    TrackIdentified,
+}
+
+impl WebhookEvent {
+    pub const ALL: [Self; 4] = [
+        Self::SetCreated,
+        Self::SetUpdated,
+        Self::SetDeleted,
+        Self::TrackIdentified,
+    ];
+
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::SetCreated => "set.created",
+            Self::SetUpdated => "set.updated",
+            Self::SetDeleted => "set.deleted",
+            Self::TrackIdentified => "set.track_identified",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub struct Model {
+    pub created_at: NaiveDateTime,
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+}
+
+/// Whether `ip` is reachable from the internet, and not the server itself, a
+/// private network or a cloud metadata service (link-local 169.254.169.254).
+#[must_use]
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    const fn is_public_v4(ip: Ipv4Addr) -> bool {
+        let [a, b, ..] = ip.octets();
+        !(ip.is_loopback()
+            || ip.is_private()
+            || ip.is_link_local()
+            || ip.is_unspecified()
+            || ip.is_broadcast()
+            // shared address space of carrier-grade NAT, 100.64.0.0/10
+            || (a == 100 && b & 0xc0 == 64)
+            // "this network", 0.0.0.0/8
+            || a == 0)
+    }
+
+    fn is_public_v6(ip: Ipv6Addr) -> bool {
+        if let Some(ip) = ip.to_ipv4_mapped() {
+            return is_public_v4(ip);
+        }
+        let first = ip.segments()[0];
+        !(ip.is_loopback()
+            || ip.is_unspecified()
+            // unique local, fc00::/7
+            || first & 0xfe00 == 0xfc00
+            // link-local, fe80::/10
+            || first & 0xffc0 == 0xfe80)
+    }
+
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => is_public_v6(ip),
+    }
+}
+
+/// Resolves the host of a webhook URL, making sure every address it resolves
+/// to is public unless `allow_private` is set. Deliveries connect to the
+/// returned addresses only, so the host cannot be rebound to a private
+/// address after it was checked.
+///
+/// # Errors
+///
+/// When the URL has no host, the host cannot be resolved or resolves to a
+/// private address
+pub async fn resolve(url: &Url, allow_private: bool) -> ModelResult<Vec<SocketAddr>> {
+    let (Some(host), Some(port)) = (url.host_str(), url.port_or_known_default()) else {
+        return Err(ModelError::Any(eyre::eyre!("a webhook URL needs a host")));
+    };
+    // IPv6 hosts come in brackets
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let addrs = tokio::net::lookup_host((host, port))
+        .await
+        .map_err(|_| ModelError::Any(eyre::eyre!("the webhook host `{host}` was not found")))?
+        .collect::<Vec<_>>();
+    if addrs.is_empty() {
+        return Err(ModelError::Any(eyre::eyre!(
+            "the webhook host `{host}` was not found"
+        )));
+    }
+    if !allow_private && !addrs.iter().all(|addr| is_public_ip(addr.ip())) {
+        return Err(ModelError::Any(eyre::eyre!(
+            "a webhook cannot deliver to a local or private network address"
+        )));
+    }
+    Ok(addrs)
+}
+
+fn generate_secret() -> String {
+    let secret = rand::thread_rng()
+        .sample_iter(Alphanumeric)
+        .take(SECRET_LENGTH)
+        .map(char::from)
+        .collect::<String>();
+    format!("{SECRET_PREFIX}{secret}")
+}
+
+impl Model {
+    /// Registers a webhook with a fresh signing secret.
+    ///
+    /// # Errors
+    ///
+    /// When the URL is not a valid http(s) URL, resolves to a private address
+    /// and `allow_private` is not set, no event is given, or could not save
+    /// the webhook into the DB
+    pub async fn create(
+        db: &PgPool,
+        user_id: Uuid,
+        url: &str,
+        events: &[WebhookEvent],
+        allow_private: bool,
+    ) -> ModelResult<Self> {
+        let url = Url::parse(url.trim())
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .ok_or_else(|| ModelError::Any(eyre::eyre!("a webhook needs an http(s) URL")))?;
+        if events.is_empty() {
+            return Err(ModelError::Any(eyre::eyre!(
+                "a webhook needs at least one event"
+            )));
+        }
+        resolve(&url, allow_private).await?;
+
+        let events = events
+            .iter()
+            .map(|event| event.as_str().to_string())
+            .collect::<Vec<_>>();
+        Ok(sqlx::query_as!(
+            Self,
+            r#"INSERT INTO webhooks (id, user_id, url, secret, events)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING *"#,
+            Uuid::now_v7(),
+            user_id,
+            url.as_str(),
+            generate_secret(),
+            &events
+        )
+        .fetch_one(db)
+        .await?)
+    }
+
+    pub async fn find_by_id(db: &PgPool, id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(Self, "SELECT * FROM webhooks WHERE id = $1", id)
+            .fetch_optional(db)
+            .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    pub async fn find_by_id_and_user_id(db: &PgPool, id: Uuid, user_id: Uuid) -> ModelResult<Self> {
+        let item = sqlx::query_as!(
+            Self,
+            "SELECT * FROM webhooks WHERE id = $1 AND user_id = $2",
+            id,
+            user_id
+        )
+        .fetch_optional(db)
+        .await?;
+        item.ok_or(ModelError::EntityNotFound)
+    }
+
+    pub async fn list_by_user_id(db: &PgPool, user_id: Uuid) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM webhooks WHERE user_id = $1 ORDER BY created_at, id",
+            user_id
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Webhooks of `user_id` subscribed to `event`.
+    pub async fn list_subscribed(
+        db: &PgPool,
+        user_id: Uuid,
+        event: WebhookEvent,
+    ) -> ModelResult<Vec<Self>> {
+        Ok(sqlx::query_as!(
+            Self,
+            "SELECT * FROM webhooks WHERE user_id = $1 AND $2 = ANY(events) ORDER BY created_at, id",
+            user_id,
+            event.as_str()
+        )
+        .fetch_all(db)
+        .await?)
+    }
+
+    /// Removes the webhook together with its delivery log.
+    ///
+    /// # Errors
+    ///
+    /// when has DB query error
+    pub async fn delete(&self, db: &PgPool) -> ModelResult<()> {
+        sqlx::query!("DELETE FROM webhooks WHERE id = $1", self.id)
+            .execute(db)
+            .await?;
+        Ok(())
+    }
+
+    /// The `X-Setlist-Signature` header value of a payload: the hex encoded
+    /// HMAC-SHA256 of the request body, keyed with the webhook secret.
+    #[must_use]
+    pub fn sign(&self, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+}
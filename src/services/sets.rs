@@ -0,0 +1,128 @@
+//! Changes to sets, shared by the site and the API. The database writes of a
+//! change go in one transaction, and only once it is committed are sitemaps,
+//! similar sets, webhooks and notifications told about it.
+
+use loco_rs::prelude::*;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    controllers::{sitemaps, uploads},
+    mailers::notifications::NotificationMailer,
+    models::{sets, songs, tags, webhooks::WebhookEvent},
+    workers::{similarity, webhook},
+};
+
+/// Replaces the tracklist of a set and, when given, its tags.
+async fn replace_contents(
+    db: &mut PgConnection,
+    id: Uuid,
+    tracklist: &[songs::Model],
+    tags: Option<&[String]>,
+) -> Result<()> {
+    songs::Model::replace_for_setlist(db, id, tracklist).await?;
+    if let Some(tags) = tags {
+        tags::Model::replace_for_set(db, id, tags).await?;
+    }
+    Ok(())
+}
+
+/// Tells everything which follows a set's tracklist that it changed from
+/// `previous`. Returns the set as saved with its tracklist.
+async fn tracklist_changed(
+    ctx: &AppContext,
+    id: Uuid,
+    previous: &[songs::Model],
+) -> Result<(sets::Model, Vec<songs::Model>)> {
+    sitemaps::invalidate(ctx).await;
+    similarity::set_changed(ctx, id).await?;
+
+    let item = sets::Model::find_by_id(&ctx.db, id).await?;
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, id).await?;
+    webhook::set_changed(ctx, WebhookEvent::SetUpdated, &item, &tracklist).await?;
+    webhook::tracks_identified(ctx, &item, previous, &tracklist).await?;
+    NotificationMailer::tracks_identified(ctx, &item, previous, &tracklist).await?;
+    Ok((item, tracklist))
+}
+
+/// Saves a new set with its tracklist and tags. Returns the set as saved
+/// with its tracklist.
+///
+/// # Errors
+///
+/// When a tag name is invalid, or DB query error, or the jobs following the
+/// change could not be queued
+pub async fn create(
+    ctx: &AppContext,
+    item: &sets::Model,
+    tracklist: &[songs::Model],
+    tags: Option<&[String]>,
+) -> Result<(sets::Model, Vec<songs::Model>)> {
+    let mut tx = ctx.db.begin().await?;
+    item.insert(&mut tx).await?;
+    replace_contents(&mut tx, item.id, tracklist, tags).await?;
+    tx.commit().await?;
+    sitemaps::invalidate(ctx).await;
+    similarity::set_changed(ctx, item.id).await?;
+
+    let item = sets::Model::find_by_id(&ctx.db, item.id).await?;
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    webhook::set_changed(ctx, WebhookEvent::SetCreated, &item, &tracklist).await?;
+    NotificationMailer::set_created(ctx, &item).await?;
+    Ok((item, tracklist))
+}
+
+/// Saves the changes to a set, its tracklist and, when given, its tags.
+/// Returns the set as saved with its tracklist.
+///
+/// # Errors
+///
+/// When a tag name is invalid, or DB query error, or the jobs following the
+/// change could not be queued
+pub async fn update(
+    ctx: &AppContext,
+    item: &sets::Model,
+    tracklist: &[songs::Model],
+    tags: Option<&[String]>,
+) -> Result<(sets::Model, Vec<songs::Model>)> {
+    let previous = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    let mut tx = ctx.db.begin().await?;
+    item.update(&mut tx).await?;
+    replace_contents(&mut tx, item.id, tracklist, tags).await?;
+    tx.commit().await?;
+    tracklist_changed(ctx, item.id, &previous).await
+}
+
+/// Replaces the tracklist of a set, leaving the rest of it alone. Returns
+/// the tracklist as saved.
+///
+/// # Errors
+///
+/// When DB query error, or the jobs following the change could not be queued
+pub async fn replace_tracklist(
+    ctx: &AppContext,
+    item: &sets::Model,
+    tracklist: &[songs::Model],
+) -> Result<Vec<songs::Model>> {
+    let previous = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    let mut tx = ctx.db.begin().await?;
+    replace_contents(&mut tx, item.id, tracklist, None).await?;
+    tx.commit().await?;
+    let (_, tracklist) = tracklist_changed(ctx, item.id, &previous).await?;
+    Ok(tracklist)
+}
+
+/// Deletes a set with its uploads.
+///
+/// # Errors
+///
+/// When the uploads could not be removed, or DB query error, or the webhooks
+/// could not be queued
+pub async fn delete(ctx: &AppContext, item: &sets::Model) -> Result<()> {
+    let tracklist = songs::Model::list_by_setlist_id(&ctx.db, item.id).await?;
+    uploads::delete_for_set(ctx, item.id).await?;
+    sets::Model::delete_by_id(&mut *ctx.db.acquire().await?, item.id).await?;
+    sitemaps::invalidate(ctx).await;
+    webhook::set_changed(ctx, WebhookEvent::SetDeleted, item, &tracklist).await?;
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use loco_rs::{config::Config, Error, Result};
+use serde::Deserialize;
+
+/// The `settings` section of the configuration.
+#[derive(Clone, Debug, Deserialize)]
+pub struct Settings {
+    pub uploads: Uploads,
+    pub sitemaps: Sitemaps,
+    pub robots: Robots,
+    #[serde(default)]
+    pub webhooks: Webhooks,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Uploads {
+    /// Directory the uploaded files are stored in.
+    pub path: PathBuf,
+    /// Bytes each user can fill with uploads.
+    pub quota: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Sitemaps {
+    /// URLs each sitemap lists, at most 50000 as the protocol allows.
+    pub page_size: i64,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Robots {
+    /// Paths crawlers are asked to stay out of, in `robots.txt`.
+    pub disallow: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct Webhooks {
+    /// Lets webhooks deliver to loopback and private network addresses, only
+    /// meant for tests running a receiver on the same machine.
+    #[serde(default)]
+    pub allow_private_addresses: bool,
+}
+
+impl Settings {
+    /// # Errors
+    ///
+    /// When the configuration has no valid `settings` section
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let settings = config.settings.clone().unwrap_or_default();
+        let settings: Self = serde_json::from_value(settings).map_err(Error::JSON)?;
+        if settings.sitemaps.page_size <= 0 {
+            return Err(Error::string(
+                "settings.sitemaps.page_size must be a positive number",
+            ));
+        }
+        Ok(settings)
+    }
+}
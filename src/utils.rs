@@ -1,7 +1,7 @@
-use axum::http::uri::PathAndQuery;
+use axum::http::{uri::PathAndQuery, StatusCode};
 use axum_htmx::HX_REDIRECT;
 use cookie::Cookie;
-use loco_rs::prelude::*;
+use loco_rs::{controller::ErrorDetail, prelude::*};
 
 use crate::models::users;
 
@@ -16,6 +16,32 @@ pub fn hx_redirect(redirect_to: &PathAndQuery) -> Result<Response> {
         .empty()
 }
 
+/// Like [`hx_redirect`], to a path built from ids or slugs.
+pub fn hx_redirect_to(path: String) -> Result<Response> {
+    hx_redirect(&PathAndQuery::try_from(path).map_err(|err| Error::Message(err.to_string()))?)
+}
+
+/// Returned when an authenticated user acts on a resource they do not own.
+pub fn forbidden<T>() -> Result<T> {
+    Err(Error::CustomError(
+        StatusCode::FORBIDDEN,
+        ErrorDetail::new(
+            "forbidden",
+            "You do not have permission to modify this resource",
+        ),
+    ))
+}
+
+/// Models refuse invalid input with [`ModelError::Any`], which is the
+/// client's fault. Anything else is ours.
+#[must_use]
+pub fn model_error(err: ModelError) -> Error {
+    match err {
+        ModelError::Any(err) => Error::BadRequest(err.to_string()),
+        err => err.into(),
+    }
+}
+
 pub fn redirect_with_cookies(
     redirect_to: &PathAndQuery,
     cookies: &[Cookie<'_>],
@@ -28,3 +54,14 @@ pub fn redirect_with_cookies(
         builder.redirect(redirect_to.path())
     }
 }
+
+/// Turns a DJ or venue name into the slug used in URLs. Keep in sync with the
+/// `slugify` SQL function.
+#[must_use]
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_lowercase() && !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
@@ -0,0 +1,261 @@
+use axum::http::StatusCode;
+use chrono::NaiveDateTime;
+use loco_rs::prelude::*;
+use schemars::JsonSchema;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    controllers::sets::Song,
+    models::{api_keys, sets, songs, transitions, users, webhooks},
+};
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Pagination {
+    pub page: i64,
+    pub per_page: i64,
+    pub total_items: i64,
+    pub total_pages: i64,
+}
+
+impl Pagination {
+    #[must_use]
+    pub const fn new(page: i64, per_page: i64, total_items: i64) -> Self {
+        Self {
+            page,
+            per_page,
+            total_items,
+            total_pages: (total_items + per_page - 1) / per_page,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Page<T> {
+    pub data: Vec<T>,
+    pub pagination: Pagination,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct SetResponse {
+    #[serde(flatten)]
+    pub set: sets::Model,
+    pub tracklist: Vec<Song>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[schemars(rename = "User")]
+pub struct UserResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<users::Model> for UserResponse {
+    fn from(user: users::Model) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// An API key, told apart by its prefix. The key itself is never stored.
+#[derive(Debug, Serialize, JsonSchema)]
+#[schemars(rename = "ApiKey")]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub name: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: NaiveDateTime,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub expires_at: Option<NaiveDateTime>,
+}
+
+impl From<api_keys::Model> for ApiKeyResponse {
+    fn from(item: api_keys::Model) -> Self {
+        Self {
+            id: item.id,
+            name: item.name,
+            prefix: item.prefix,
+            scopes: item.scopes,
+            created_at: item.created_at,
+            last_used_at: item.last_used_at,
+            expires_at: item.expires_at,
+        }
+    }
+}
+
+/// A new API key with the key itself, which is only ever shown this once.
+#[derive(Debug, Serialize, JsonSchema)]
+#[schemars(rename = "NewApiKey")]
+pub struct NewApiKeyResponse {
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+    pub key: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+#[schemars(rename = "Webhook")]
+pub struct WebhookResponse {
+    pub id: Uuid,
+    pub url: String,
+    pub events: Vec<String>,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<webhooks::Model> for WebhookResponse {
+    fn from(item: webhooks::Model) -> Self {
+        Self {
+            id: item.id,
+            url: item.url,
+            events: item.events,
+            created_at: item.created_at,
+        }
+    }
+}
+
+/// A new webhook with the secret its deliveries are signed with.
+#[derive(Debug, Serialize, JsonSchema)]
+#[schemars(rename = "NewWebhook")]
+pub struct NewWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    pub secret: String,
+}
+
+/// A track with the tracks most often played right before and after it.
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TrackTransitions {
+    pub track: transitions::Track,
+    pub before: Vec<transitions::Neighbour>,
+    pub after: Vec<transitions::Neighbour>,
+}
+
+/// Render one page of a paginated collection.
+///
+/// # Errors
+///
+/// When the items could not be serialized.
+pub fn page<T: Serialize>(data: Vec<T>, pagination: Pagination) -> Result<Response> {
+    format::json(Page { data, pagination })
+}
+
+/// Render a single set with its tracklist.
+///
+/// # Errors
+///
+/// When the set could not be serialized.
+pub fn set(set: sets::Model, tracklist: Vec<songs::Model>) -> Result<Response> {
+    format::json(SetResponse {
+        set,
+        tracklist: tracklist.into_iter().map(Song::from).collect(),
+    })
+}
+
+/// Render a newly created set with its tracklist.
+///
+/// # Errors
+///
+/// When the set could not be serialized.
+pub fn created_set(set: sets::Model, tracklist: Vec<songs::Model>) -> Result<Response> {
+    format::render()
+        .status(StatusCode::CREATED)
+        .json(SetResponse {
+            set,
+            tracklist: tracklist.into_iter().map(Song::from).collect(),
+        })
+}
+
+/// Render a tracklist.
+///
+/// # Errors
+///
+/// When the tracklist could not be serialized.
+pub fn tracklist(tracklist: Vec<songs::Model>) -> Result<Response> {
+    format::json(tracklist.into_iter().map(Song::from).collect::<Vec<_>>())
+}
+
+/// Render a user's public profile.
+///
+/// # Errors
+///
+/// When the user could not be serialized.
+pub fn user(user: users::Model) -> Result<Response> {
+    format::json(UserResponse::from(user))
+}
+
+/// Render the transitions into and out of a track.
+///
+/// # Errors
+///
+/// When the transitions could not be serialized.
+pub fn track_transitions(
+    track: transitions::Track,
+    before: Vec<transitions::Neighbour>,
+    after: Vec<transitions::Neighbour>,
+) -> Result<Response> {
+    format::json(TrackTransitions {
+        track,
+        before,
+        after,
+    })
+}
+
+/// Render the API keys of a user.
+///
+/// # Errors
+///
+/// When the keys could not be serialized.
+pub fn api_keys(items: Vec<api_keys::Model>) -> Result<Response> {
+    format::json(
+        items
+            .into_iter()
+            .map(ApiKeyResponse::from)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Render a new API key along with the key itself, `201 Created` unless it
+/// replaces a rotated one.
+///
+/// # Errors
+///
+/// When the key could not be serialized.
+pub fn new_api_key(item: api_keys::Model, key: String, status: StatusCode) -> Result<Response> {
+    format::render().status(status).json(NewApiKeyResponse {
+        api_key: ApiKeyResponse::from(item),
+        key,
+    })
+}
+
+/// Render the webhooks of a user.
+///
+/// # Errors
+///
+/// When the webhooks could not be serialized.
+pub fn webhooks(items: Vec<webhooks::Model>) -> Result<Response> {
+    format::json(
+        items
+            .into_iter()
+            .map(WebhookResponse::from)
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Render a newly created webhook along with its secret.
+///
+/// # Errors
+///
+/// When the webhook could not be serialized.
+pub fn created_webhook(item: webhooks::Model) -> Result<Response> {
+    let secret = item.secret.clone();
+    format::render()
+        .status(StatusCode::CREATED)
+        .json(NewWebhookResponse {
+            webhook: WebhookResponse::from(item),
+            secret,
+        })
+}
@@ -0,0 +1,96 @@
+use loco_rs::prelude::*;
+use serde_json::json;
+
+use crate::{
+    models::{collections, sets},
+    views::share::{truncate, Meta},
+};
+
+/// Render the user's own collections, with a form to start a new one.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn index(
+    v: &impl ViewRenderer,
+    items: &[collections::Model],
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "collections/index.html",
+        json!({"items": items, "username": user_name}),
+    )
+}
+
+/// Render the page of a collection.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn show(
+    v: &impl ViewRenderer,
+    item: &collections::Model,
+    owner_name: &str,
+    sets: &[sets::Model],
+    is_owner: bool,
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "collections/show.html",
+        json!({
+            "item": item,
+            "owner_name": owner_name,
+            "sets": sets,
+            "is_owner": is_owner,
+            "meta": Meta {
+                title: item.name.clone(),
+                description: if item.description.is_empty() {
+                    format!("A collection of {} sets by {owner_name}.", sets.len())
+                } else {
+                    truncate(&item.description, 200)
+                },
+                path: format!("/collections/{}", item.id),
+                image: None,
+            },
+            "username": user_name,
+        }),
+    )
+}
+
+/// Render the sets of a collection after they were moved or removed, for its
+/// owner.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn sets(
+    v: &impl ViewRenderer,
+    item: &collections::Model,
+    sets: &[sets::Model],
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "collections/show.html:sets",
+        json!({"item": item, "sets": sets, "is_owner": true}),
+    )
+}
+
+/// Render the "add to collection" form of a set once it was added.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn added(
+    v: &impl ViewRenderer,
+    item: &sets::Model,
+    collections: &[collections::Model],
+    added_to: &collections::Model,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "sets/show.html:collect",
+        json!({"item": item, "collections": collections, "added_to": added_to}),
+    )
+}
@@ -0,0 +1,108 @@
+use loco_rs::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    models::{comments, sets, songs},
+    views::{markdown, sets::Viewer},
+};
+
+/// A comment with its replies, as shown to the viewer.
+#[derive(Serialize)]
+pub struct Thread<'a> {
+    #[serde(flatten)]
+    comment: &'a comments::Model,
+    body_html: String,
+    deleted: bool,
+    edited: bool,
+    /// "3. Jeff Mills – The Bells" for comments on a track.
+    track: Option<String>,
+    can_edit: bool,
+    can_delete: bool,
+    replies: Vec<Thread<'a>>,
+}
+
+impl<'a> Thread<'a> {
+    fn new(comment: &'a comments::Model, tracklist: &[songs::Model], viewer: &Viewer) -> Self {
+        let is_author = viewer.user_id == Some(comment.author_id);
+        let deleted = comment.deleted_at.is_some();
+        Self {
+            comment,
+            body_html: markdown::render(&comment.body),
+            deleted,
+            edited: comment.updated_at > comment.created_at && !deleted,
+            track: comment.track_number.map(|number| {
+                tracklist
+                    .iter()
+                    .find(|song| song.track_number == Some(number))
+                    .map_or_else(
+                        || format!("track {number}"),
+                        |song| format!("{number}. {} – {}", song.track_artist, song.track_title),
+                    )
+            }),
+            can_edit: is_author && !deleted,
+            // set owners moderate the discussion of their sets
+            can_delete: (is_author || viewer.is_owner) && !deleted,
+            replies: vec![],
+        }
+    }
+}
+
+/// Groups the comments of a set into threads of replies.
+#[must_use]
+pub fn threads<'a>(
+    comments: &'a [comments::Model],
+    tracklist: &[songs::Model],
+    viewer: &Viewer,
+) -> Vec<Thread<'a>> {
+    let mut threads = comments
+        .iter()
+        .filter(|comment| comment.parent_id.is_none())
+        .map(|comment| Thread::new(comment, tracklist, viewer))
+        .collect::<Vec<_>>();
+    for reply in comments
+        .iter()
+        .filter(|comment| comment.parent_id.is_some())
+    {
+        if let Some(thread) = threads
+            .iter_mut()
+            .find(|thread| Some(thread.comment.id) == reply.parent_id)
+        {
+            thread.replies.push(Thread::new(reply, tracklist, viewer));
+        }
+    }
+    threads
+}
+
+/// Render the discussion of a set.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn list(
+    v: &impl ViewRenderer,
+    item: &sets::Model,
+    tracklist: &[songs::Model],
+    comments: &[comments::Model],
+    viewer: &Viewer,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "sets/show.html:comments",
+        json!({
+            "item": item,
+            "tracklist": tracklist,
+            "comments": threads(comments, tracklist, viewer),
+            "username": viewer.user_name,
+        }),
+    )
+}
+
+/// Render the form editing a comment.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn edit(v: &impl ViewRenderer, comment: &comments::Model) -> Result<Response> {
+    format::render().view(v, "sets/comment_edit.html", json!({"comment": comment}))
+}
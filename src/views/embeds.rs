@@ -0,0 +1,122 @@
+use axum::http::header;
+use loco_rs::prelude::*;
+use minijinja::HtmlEscape;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    models::{recordings::format_offset, sets, songs},
+    views::{
+        feeds::set_title,
+        sets::date_and_place,
+        share::{IMAGE_HEIGHT, IMAGE_WIDTH},
+    },
+};
+
+/// Width embeds ask for, unless the consumer allows less.
+const EMBED_WIDTH: u32 = 480;
+/// Heights of the header and footer of an embed together, and of a track.
+const EMBED_CHROME_HEIGHT: u32 = 96;
+const EMBED_TRACK_HEIGHT: u32 = 28;
+/// Longer tracklists scroll within the embed.
+const EMBED_MAX_HEIGHT: u32 = 600;
+/// Anyone may frame the widget, which only links out and runs no scripts.
+const EMBED_CSP: &str = "default-src 'none'; style-src 'unsafe-inline'; frame-ancestors *";
+
+/// A rich oEmbed response, see <https://oembed.com>.
+#[derive(Debug, Serialize)]
+pub struct OEmbed {
+    pub version: &'static str,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub provider_name: &'static str,
+    pub provider_url: String,
+    pub title: String,
+    pub html: String,
+    pub width: u32,
+    pub height: u32,
+    pub thumbnail_url: String,
+    pub thumbnail_width: u32,
+    pub thumbnail_height: u32,
+}
+
+#[derive(Serialize)]
+struct Track<'a> {
+    #[serde(flatten)]
+    song: &'a songs::Model,
+    identified: bool,
+    start: Option<String>,
+}
+
+/// Render the compact tracklist of a set, to be framed by other sites.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn set(
+    v: &impl ViewRenderer,
+    item: &sets::Model,
+    tracklist: &[songs::Model],
+) -> Result<Response> {
+    let tracks = tracklist
+        .iter()
+        .map(|song| Track {
+            song,
+            identified: song.is_identified(),
+            start: song.track_start_time_offset_seconds.map(format_offset),
+        })
+        .collect::<Vec<_>>();
+    format::render()
+        .header(header::CONTENT_SECURITY_POLICY, EMBED_CSP)
+        .header(header::CACHE_CONTROL, "public, max-age=300")
+        .view(
+            v,
+            "embed/set.html",
+            json!({
+                "item": item,
+                "title": set_title(item),
+                "date_and_place": date_and_place(item),
+                "tracklist": tracks,
+            }),
+        )
+}
+
+/// Render how to embed a set, fitting within the size the consumer allows.
+///
+/// # Errors
+///
+/// When the response could not be serialized.
+pub fn oembed(
+    base_url: &str,
+    item: &sets::Model,
+    tracks: usize,
+    max_width: Option<u32>,
+    max_height: Option<u32>,
+) -> Result<Response> {
+    let tracks = u32::try_from(tracks).unwrap_or(u32::MAX);
+    let width = max_width.map_or(EMBED_WIDTH, |max| max.min(EMBED_WIDTH));
+    let height = EMBED_TRACK_HEIGHT
+        .saturating_mul(tracks)
+        .saturating_add(EMBED_CHROME_HEIGHT)
+        .min(EMBED_MAX_HEIGHT);
+    let height = max_height.map_or(height, |max| max.min(height));
+    let title = set_title(item);
+    let html = format!(
+        r#"<iframe src="{base_url}/embed/sets/{}" width="{width}" height="{height}" title="{}" style="border: 0" loading="lazy"></iframe>"#,
+        item.id,
+        HtmlEscape(&title),
+    );
+    format::json(OEmbed {
+        version: "1.0",
+        kind: "rich",
+        provider_name: "SetListList",
+        provider_url: base_url.to_string(),
+        title,
+        html,
+        width,
+        height,
+        thumbnail_url: format!("{base_url}/sets/{}/share.png", item.id),
+        thumbnail_width: IMAGE_WIDTH,
+        thumbnail_height: IMAGE_HEIGHT,
+    })
+}
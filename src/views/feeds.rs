@@ -0,0 +1,289 @@
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, StatusCode},
+};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use loco_rs::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest as _, Sha256};
+use uuid::Uuid;
+
+use crate::{
+    controllers::sets::Song,
+    models::{sets, songs},
+};
+
+const ATOM_CONTENT_TYPE: &str = "application/atom+xml; charset=utf-8";
+const CALENDAR_CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+/// Lines longer than this are folded, as required by RFC 5545.
+const CALENDAR_LINE_LENGTH: usize = 75;
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// What a feed is about.
+pub struct Feed {
+    pub title: String,
+    /// Path of the feed itself.
+    pub path: String,
+    /// Path of the HTML page showing the same sets.
+    pub alternate: String,
+}
+
+#[derive(Serialize)]
+struct Entry {
+    id: Uuid,
+    title: String,
+    updated: String,
+    published: String,
+    set: sets::Model,
+    tracklist: Vec<Song>,
+}
+
+pub fn rfc3339(datetime: NaiveDateTime) -> String {
+    datetime.and_utc().format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// "Jeff Mills B2B DJ Rolando at Tresor"
+#[must_use]
+pub fn set_title(set: &sets::Model) -> String {
+    let djs = set.dj_names.join(" B2B ");
+    match &set.venue {
+        Some(venue) if !venue.is_empty() => format!("{djs} at {venue}"),
+        _ => djs,
+    }
+}
+
+/// Validators of a response whose content only depends on `versions`, used
+/// for conditional GET.
+pub struct Validators {
+    pub etag: String,
+    pub last_modified: Option<NaiveDateTime>,
+}
+
+impl Validators {
+    pub fn new<'a>(
+        kind: &str,
+        versions: impl IntoIterator<Item = (&'a Uuid, &'a NaiveDateTime)>,
+    ) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(kind.as_bytes());
+        let mut last_modified = None;
+        for (id, updated_at) in versions {
+            hasher.update(id.as_bytes());
+            hasher.update(updated_at.and_utc().timestamp_micros().to_be_bytes());
+            last_modified = last_modified.max(Some(*updated_at));
+        }
+        Self {
+            etag: format!("\"{}\"", &hex::encode(hasher.finalize())[..32]),
+            last_modified,
+        }
+    }
+
+    /// Whether the client's cached copy is still fresh. `If-None-Match` takes
+    /// precedence over `If-Modified-Since`, as in RFC 9110.
+    #[must_use]
+    pub fn is_fresh(&self, headers: &HeaderMap) -> bool {
+        if let Some(if_none_match) = headers.get(header::IF_NONE_MATCH) {
+            return if_none_match.to_str().is_ok_and(|value| {
+                value
+                    .split(',')
+                    .map(|tag| tag.trim().trim_start_matches("W/"))
+                    .any(|tag| tag == "*" || tag == self.etag)
+            });
+        }
+
+        let if_modified_since = headers
+            .get(header::IF_MODIFIED_SINCE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| DateTime::parse_from_rfc2822(value).ok());
+        match (if_modified_since, self.last_modified) {
+            (Some(since), Some(last_modified)) => {
+                last_modified.and_utc().timestamp() <= since.timestamp()
+            }
+            _ => false,
+        }
+    }
+
+    /// Renders `content` with the validators, or `304 Not Modified` when the
+    /// client's copy is fresh.
+    ///
+    /// # Errors
+    ///
+    /// When the response could not be built.
+    pub fn respond<T: Into<Body>>(
+        &self,
+        headers: &HeaderMap,
+        content_type: &str,
+        content: impl FnOnce() -> Result<T>,
+    ) -> Result<Response> {
+        let mut builder = format::render().etag(&self.etag)?;
+        if let Some(last_modified) = self.last_modified {
+            builder = builder.header(
+                header::LAST_MODIFIED,
+                last_modified.format(HTTP_DATE_FORMAT).to_string(),
+            );
+        }
+
+        if self.is_fresh(headers) {
+            return builder.status(StatusCode::NOT_MODIFIED).empty();
+        }
+        Ok(builder
+            .header(header::CONTENT_TYPE, content_type)
+            .response()
+            .body(content()?.into())?)
+    }
+}
+
+/// Render an Atom feed of sets with their full tracklists.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn sets(
+    v: &impl ViewRenderer,
+    headers: &HeaderMap,
+    base_url: &str,
+    feed: &Feed,
+    items: Vec<sets::Model>,
+    tracklists: &[songs::Model],
+) -> Result<Response> {
+    let validators = Validators::new(
+        &feed.path,
+        items.iter().map(|item| (&item.id, &item.updated_at)),
+    );
+
+    validators.respond(headers, ATOM_CONTENT_TYPE, || {
+        let updated = validators
+            .last_modified
+            .unwrap_or_else(|| Utc::now().naive_utc());
+        let entries = items
+            .into_iter()
+            .map(|set| Entry {
+                id: set.id,
+                title: set_title(&set),
+                updated: rfc3339(set.updated_at),
+                published: rfc3339(set.created_at),
+                tracklist: tracklists
+                    .iter()
+                    .filter(|song| song.setlist_id == set.id)
+                    .cloned()
+                    .map(Song::from)
+                    .collect(),
+                set,
+            })
+            .collect::<Vec<_>>();
+
+        v.render(
+            "feeds/sets.xml",
+            json!({
+                "base_url": base_url,
+                "title": feed.title,
+                "path": feed.path,
+                "alternate": feed.alternate,
+                "updated": rfc3339(updated),
+                "entries": entries,
+            }),
+        )
+    })
+}
+
+/// Escapes a TEXT value of an iCalendar property. Carriage returns become
+/// line breaks too, so a value cannot end its content line early.
+fn calendar_text(value: &str) -> String {
+    value
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Appends a content line, folding it at [`CALENDAR_LINE_LENGTH`] octets
+/// without splitting characters.
+fn calendar_line(out: &mut String, name: &str, value: &str) {
+    let line = format!("{name}:{value}");
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > CALENDAR_LINE_LENGTH {
+            out.push_str("\r\n ");
+            length = 1;
+        }
+        out.push(c);
+        length += c.len_utf8();
+    }
+    out.push_str("\r\n");
+}
+
+/// Start times are local to the venue, so they are written as floating
+/// times which calendar apps show as is.
+fn calendar_local_time(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn calendar_utc_time(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn calendar_event(out: &mut String, base_url: &str, set: &sets::Model) {
+    calendar_line(out, "BEGIN", "VEVENT");
+    // stable across updates so calendar apps replace the event
+    calendar_line(out, "UID", &format!("{}@setlistlist", set.id));
+    calendar_line(out, "DTSTAMP", &calendar_utc_time(set.updated_at));
+    calendar_line(out, "LAST-MODIFIED", &calendar_utc_time(set.updated_at));
+    calendar_line(out, "DTSTART", &calendar_local_time(set.start_time));
+    if let Some(duration) = set.duration_seconds {
+        let end = set.start_time + chrono::Duration::seconds(i64::from(duration));
+        calendar_line(out, "DTEND", &calendar_local_time(end));
+    }
+    calendar_line(out, "SUMMARY", &calendar_text(&set_title(set)));
+    let location = [&set.venue, &set.city]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if !location.is_empty() {
+        calendar_line(out, "LOCATION", &calendar_text(&location));
+    }
+    if let Some(event_name) = set.event_name.as_deref().filter(|name| !name.is_empty()) {
+        calendar_line(out, "DESCRIPTION", &calendar_text(event_name));
+    }
+    calendar_line(out, "URL", &format!("{base_url}/sets/{}", set.id));
+    calendar_line(out, "END", "VEVENT");
+}
+
+/// Render an iCalendar feed of upcoming sets.
+///
+/// # Errors
+///
+/// When the response could not be built.
+pub fn calendar(
+    headers: &HeaderMap,
+    base_url: &str,
+    feed: &Feed,
+    items: &[sets::Model],
+) -> Result<Response> {
+    let validators = Validators::new(
+        &feed.path,
+        items.iter().map(|item| (&item.id, &item.updated_at)),
+    );
+
+    validators.respond(headers, CALENDAR_CONTENT_TYPE, || {
+        let mut out = String::new();
+        calendar_line(&mut out, "BEGIN", "VCALENDAR");
+        calendar_line(&mut out, "VERSION", "2.0");
+        calendar_line(&mut out, "PRODID", "-//SetListList//Upcoming sets//EN");
+        calendar_line(&mut out, "CALSCALE", "GREGORIAN");
+        calendar_line(&mut out, "METHOD", "PUBLISH");
+        calendar_line(&mut out, "X-WR-CALNAME", &calendar_text(&feed.title));
+        calendar_line(&mut out, "REFRESH-INTERVAL;VALUE=DURATION", "PT6H");
+        calendar_line(&mut out, "X-PUBLISHED-TTL", "PT6H");
+        for set in items {
+            calendar_event(&mut out, base_url, set);
+        }
+        calendar_line(&mut out, "END", "VCALENDAR");
+        Ok(out)
+    })
+}
@@ -0,0 +1,51 @@
+use loco_rs::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::utils::slugify;
+
+/// A follow button, following or unfollowing through `path`.
+#[derive(Debug, Serialize)]
+pub struct Follow {
+    pub path: String,
+    pub name: String,
+    pub followed: bool,
+}
+
+impl Follow {
+    #[must_use]
+    pub fn dj(name: &str, followed: bool) -> Self {
+        Self {
+            path: format!("/djs/{}/follow", slugify(name)),
+            name: name.to_string(),
+            followed,
+        }
+    }
+
+    #[must_use]
+    pub fn venue(name: &str, followed: bool) -> Self {
+        Self {
+            path: format!("/venues/{}/follow", slugify(name)),
+            name: name.to_string(),
+            followed,
+        }
+    }
+
+    #[must_use]
+    pub fn user(username: &str, followed: bool) -> Self {
+        Self {
+            path: format!("/user/{username}/follow"),
+            name: username.to_string(),
+            followed,
+        }
+    }
+}
+
+/// Render the button following or unfollowing a DJ, venue or user.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn button(v: &impl ViewRenderer, follow: &Follow) -> Result<Response> {
+    format::render().view(v, "follow_button.html", json!({"follow": follow}))
+}
@@ -1,9 +1,68 @@
 use axum::http::StatusCode;
 use loco_rs::prelude::*;
+use serde::Serialize;
 use serde_json::json;
 
-pub fn root(v: &impl ViewRenderer, user_name: &str) -> Result<impl IntoResponse> {
-    format::render().view(v, "index.html", json!({"username": user_name}))
+use crate::{
+    models::{dj_follows, sets, venue_follows},
+    views::{
+        api::Pagination,
+        share::{Meta, SITE_DESCRIPTION},
+    },
+};
+
+/// Everything a user follows.
+#[derive(Serialize)]
+pub struct Following {
+    pub djs: Vec<dj_follows::Model>,
+    pub venues: Vec<venue_follows::Model>,
+    pub usernames: Vec<String>,
+}
+
+/// Render the landing page, with the latest sets of the user's feed.
+pub fn root(
+    v: &impl ViewRenderer,
+    items: &[sets::Model],
+    user_name: &str,
+) -> Result<impl IntoResponse> {
+    format::render().view(
+        v,
+        "index.html",
+        json!({
+            "items": items,
+            "meta": Meta {
+                title: "SetListList".to_string(),
+                description: SITE_DESCRIPTION.to_string(),
+                path: "/".to_string(),
+                image: None,
+            },
+            "username": user_name,
+        }),
+    )
+}
+
+/// Render a page of the user's feed.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn feed(
+    v: &impl ViewRenderer,
+    items: &[sets::Model],
+    pagination: &Pagination,
+    following: &Following,
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "feed.html",
+        json!({
+            "items": items,
+            "pagination": pagination,
+            "following": following,
+            "username": user_name,
+        }),
+    )
 }
 
 pub fn not_found(v: &impl ViewRenderer, user_name: &str) -> Result<Response> {
@@ -0,0 +1,128 @@
+//! A small Markdown subset for user content: paragraphs, line breaks, quotes,
+//! lists, fenced code, `code`, **bold**, *italic* and http(s) links. All other
+//! text, including HTML, is escaped.
+
+fn escape(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#x27;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// Splits `[text](url)` off the start of `rest`, for http(s) URLs only.
+fn link(rest: &str) -> Option<(&str, &str, &str)> {
+    let (text, rest) = rest.strip_prefix('[')?.split_once("](")?;
+    let (url, rest) = rest.split_once(')')?;
+    let safe = url.starts_with("https://") || url.starts_with("http://");
+    (safe && !text.is_empty() && !url.contains(char::is_whitespace)).then_some((text, url, rest))
+}
+
+/// Splits the text enclosed by `marker` off the start of `rest`.
+fn enclosed<'a>(rest: &'a str, marker: &str) -> Option<(&'a str, &'a str)> {
+    let (inner, rest) = rest.strip_prefix(marker)?.split_once(marker)?;
+    (!inner.trim().is_empty()).then_some((inner, rest))
+}
+
+fn inline(out: &mut String, mut rest: &str) {
+    while let Some(c) = rest.chars().next() {
+        if let Some((code, after)) = enclosed(rest, "`") {
+            out.push_str("<code>");
+            escape(out, code);
+            out.push_str("</code>");
+            rest = after;
+        } else if let Some((inner, after)) = enclosed(rest, "**") {
+            out.push_str("<strong>");
+            inline(out, inner);
+            out.push_str("</strong>");
+            rest = after;
+        } else if let Some((inner, after)) = enclosed(rest, "*") {
+            out.push_str("<em>");
+            inline(out, inner);
+            out.push_str("</em>");
+            rest = after;
+        } else if let Some((text, url, after)) = link(rest) {
+            out.push_str("<a href=\"");
+            escape(out, url);
+            out.push_str("\" rel=\"nofollow ugc noopener\">");
+            inline(out, text);
+            out.push_str("</a>");
+            rest = after;
+        } else {
+            escape(out, &rest[..c.len_utf8()]);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+}
+
+fn lines(out: &mut String, lines: &[&str], separator: &str) {
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            out.push_str(separator);
+        }
+        inline(out, line.trim());
+    }
+}
+
+fn block(out: &mut String, block: &mut Vec<&str>) {
+    if block.is_empty() {
+        return;
+    }
+    let quoted = block
+        .iter()
+        .map(|line| line.trim_start().strip_prefix('>'))
+        .collect::<Option<Vec<_>>>();
+    let listed = block
+        .iter()
+        .map(|line| {
+            let line = line.trim_start();
+            line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))
+        })
+        .collect::<Option<Vec<_>>>();
+
+    if let Some(quoted) = quoted {
+        out.push_str("<blockquote><p>");
+        lines(out, &quoted, "<br>");
+        out.push_str("</p></blockquote>");
+    } else if let Some(listed) = listed {
+        out.push_str("<ul><li>");
+        lines(out, &listed, "</li><li>");
+        out.push_str("</li></ul>");
+    } else {
+        out.push_str("<p>");
+        lines(out, block, "<br>");
+        out.push_str("</p>");
+    }
+    block.clear();
+}
+
+/// Renders `text` to HTML which is safe to include in a page as is.
+#[must_use]
+pub fn render(text: &str) -> String {
+    let mut out = String::new();
+    let mut current = vec![];
+    let mut input = text.lines();
+    while let Some(line) = input.next() {
+        if line.trim_start().starts_with("```") {
+            block(&mut out, &mut current);
+            let code = input
+                .by_ref()
+                .take_while(|line| !line.trim_start().starts_with("```"))
+                .collect::<Vec<_>>();
+            out.push_str("<pre><code>");
+            escape(&mut out, &code.join("\n"));
+            out.push_str("</code></pre>");
+        } else if line.trim().is_empty() {
+            block(&mut out, &mut current);
+        } else {
+            current.push(line);
+        }
+    }
+    block(&mut out, &mut current);
+    out
+}
@@ -1,4 +1,19 @@
+pub mod api;
 pub mod auth;
+pub mod collections;
+pub mod comments;
+pub mod embeds;
+pub mod feeds;
+pub mod follows;
 pub mod index;
+pub mod markdown;
+pub mod notifications;
+pub mod set_flow;
 pub mod sets;
+pub mod settings;
+pub mod share;
+pub mod sitemaps;
+pub mod stats;
+pub mod tags;
+pub mod tracks;
 pub mod user;
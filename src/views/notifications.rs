@@ -0,0 +1,65 @@
+use axum_htmx::HX_TRIGGER;
+use loco_rs::prelude::*;
+use serde_json::json;
+
+use crate::{models::notifications, views::api::Pagination};
+
+/// Tells the bell in the layout to refresh its unread count.
+const READ_EVENT: &str = "notifications-read";
+
+/// Render a page of the user's notifications.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn index(
+    v: &impl ViewRenderer,
+    items: &[notifications::Model],
+    pagination: &Pagination,
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "notifications/index.html",
+        json!({"items": items, "pagination": pagination, "username": user_name}),
+    )
+}
+
+/// Render the list of notifications after they were all marked as read.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn list(
+    v: &impl ViewRenderer,
+    items: &[notifications::Model],
+    pagination: &Pagination,
+) -> Result<Response> {
+    format::render().header(HX_TRIGGER, READ_EVENT).view(
+        v,
+        "notifications/index.html:list",
+        json!({"items": items, "pagination": pagination}),
+    )
+}
+
+/// Render a notification after it was marked as read.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn item(v: &impl ViewRenderer, item: &notifications::Model) -> Result<Response> {
+    format::render().header(HX_TRIGGER, READ_EVENT).view(
+        v,
+        "notifications/item.html",
+        json!({"item": item}),
+    )
+}
+
+/// Render the bell with the number of unread notifications.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn bell(v: &impl ViewRenderer, unread: i64) -> Result<Response> {
+    format::render().view(v, "notifications/bell.html", json!({"unread": unread}))
+}
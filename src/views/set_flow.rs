@@ -0,0 +1,223 @@
+use serde::Serialize;
+
+use crate::{
+    models::{
+        recordings::format_offset,
+        set_flow::{self, Flow, Harmony},
+        songs,
+    },
+    views::sets::format_bpm,
+};
+
+/// Size of the flow chart, scaled down to fit by the browser.
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 180.0;
+/// Room left above and below the lines, and for the labels on the left.
+const CHART_MARGIN: f64 = 12.0;
+const AXIS_WIDTH: f64 = 48.0;
+
+#[derive(Debug, Serialize)]
+struct Point {
+    x: f64,
+    y: f64,
+    label: String,
+}
+
+/// Tempo and energy over the set drawn as lines, with a mark at every rough
+/// transition.
+#[derive(Debug, Serialize)]
+struct Chart {
+    width: f64,
+    height: f64,
+    /// Whether tracks are placed by when they start, or one after another.
+    timed: bool,
+    tempo: Vec<Point>,
+    energy: Vec<Point>,
+    /// Lowest and highest BPM, labelled on the left.
+    bpm_min: Option<String>,
+    bpm_max: Option<String>,
+    rough: Vec<f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Row {
+    /// "1. Artist – Title".
+    from: String,
+    to: String,
+    /// When the next track starts, if known.
+    at: Option<String>,
+    from_key: Option<String>,
+    to_key: Option<String>,
+    harmony: Option<Harmony>,
+    key_distance: Option<u8>,
+    /// "124 → 126 BPM (+1.6%)".
+    tempo: Option<String>,
+    /// "half time" or "double time".
+    time_change: Option<&'static str>,
+    tempo_jump: bool,
+    rough: bool,
+}
+
+/// The set flow report: how well each transition mixes, and how tempo and
+/// energy move over the set.
+#[derive(Debug, Serialize)]
+pub struct Report {
+    chart: Chart,
+    transitions: Vec<Row>,
+    rough: usize,
+    max_tempo_change: f64,
+}
+
+fn track_name(song: &songs::Model) -> String {
+    let name = format!("{} – {}", song.track_artist, song.track_title);
+    song.track_number
+        .map_or_else(|| name.clone(), |number| format!("{number}. {name}"))
+}
+
+/// Scales `values` to the height of the chart, the highest at the top.
+fn heights(values: &[f64]) -> impl Fn(f64) -> f64 {
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = if max > min { max - min } else { 1.0 };
+    let plot = CHART_MARGIN.mul_add(-2.0, CHART_HEIGHT);
+    move |value| {
+        // a flat line goes through the middle
+        let share = if max > min {
+            (value - min) / range
+        } else {
+            0.5
+        };
+        plot.mul_add(-share, CHART_HEIGHT - CHART_MARGIN)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn chart(flow: &Flow<'_>) -> Chart {
+    let starts = flow
+        .tracks
+        .iter()
+        .map(|track| track.song.track_start_time_offset_seconds)
+        .collect::<Option<Vec<_>>>();
+    let timed = starts
+        .as_ref()
+        .is_some_and(|starts| starts.iter().any(|start| *start > 0));
+    let plot_width = CHART_WIDTH - AXIS_WIDTH - CHART_MARGIN;
+    let last = flow.tracks.len().saturating_sub(1).max(1) as f64;
+    let x = |index: usize| {
+        let share = match &starts {
+            Some(starts) if timed => {
+                let end = starts.iter().copied().max().unwrap_or(1).max(1);
+                f64::from(starts[index]) / f64::from(end)
+            }
+            _ => index as f64 / last,
+        };
+        plot_width.mul_add(share, AXIS_WIDTH)
+    };
+
+    let bpms = flow
+        .tracks
+        .iter()
+        .filter_map(|track| track.song.track_bpm)
+        .collect::<Vec<_>>();
+    let tempo_y = heights(&bpms);
+    let tempo = flow
+        .tracks
+        .iter()
+        .enumerate()
+        .filter_map(|(index, track)| {
+            let bpm = track.song.track_bpm?;
+            Some(Point {
+                x: x(index),
+                y: tempo_y(bpm),
+                label: format!("{}: {} BPM", track_name(track.song), format_bpm(bpm)),
+            })
+        })
+        .collect();
+
+    let energies = flow
+        .tracks
+        .iter()
+        .map(|track| track.energy)
+        .collect::<Vec<_>>();
+    let energy_y = heights(&energies);
+    let energy = flow
+        .tracks
+        .iter()
+        .enumerate()
+        .map(|(index, track)| Point {
+            x: x(index),
+            y: energy_y(track.energy),
+            label: format!("{}: energy {:+.0}", track_name(track.song), track.energy),
+        })
+        .collect();
+
+    Chart {
+        width: CHART_WIDTH,
+        height: CHART_HEIGHT,
+        timed,
+        tempo,
+        energy,
+        bpm_min: bpms.iter().copied().reduce(f64::min).map(format_bpm),
+        bpm_max: bpms.iter().copied().reduce(f64::max).map(format_bpm),
+        rough: flow
+            .transitions
+            .iter()
+            .filter(|transition| transition.is_rough())
+            .map(|transition| x(transition.to))
+            .collect(),
+    }
+}
+
+/// The flow of a set, unless none of its transitions has keys or tempos on
+/// both sides.
+#[must_use]
+pub fn report(tracklist: &[songs::Model]) -> Option<Report> {
+    let flow = set_flow::analyze(tracklist);
+    if flow.is_empty() {
+        return None;
+    }
+    let transitions = flow
+        .transitions
+        .iter()
+        .map(|transition| {
+            let (from, to) = (&flow.tracks[transition.from], &flow.tracks[transition.to]);
+            let tempo = transition
+                .tempo
+                .zip(from.song.track_bpm.zip(to.song.track_bpm));
+            Row {
+                from: track_name(from.song),
+                to: track_name(to.song),
+                at: to.song.track_start_time_offset_seconds.map(format_offset),
+                from_key: from.key.map(|key| format!("{key} · {}", key.camelot())),
+                to_key: to.key.map(|key| format!("{key} · {}", key.camelot())),
+                harmony: transition.harmony,
+                key_distance: transition.key_distance,
+                tempo: tempo.map(|(change, (from_bpm, to_bpm))| {
+                    format!(
+                        "{} → {} BPM ({:+.1}%)",
+                        format_bpm(from_bpm),
+                        format_bpm(to_bpm),
+                        change.percent
+                    )
+                }),
+                time_change: transition.tempo.and_then(|change| {
+                    if change.ratio < 1.0 {
+                        Some("half time")
+                    } else if change.ratio > 1.0 {
+                        Some("double time")
+                    } else {
+                        None
+                    }
+                }),
+                tempo_jump: transition.tempo.is_some_and(|change| change.jump),
+                rough: transition.is_rough(),
+            }
+        })
+        .collect::<Vec<_>>();
+    Some(Report {
+        chart: chart(&flow),
+        rough: transitions.iter().filter(|row| row.rough).count(),
+        transitions,
+        max_tempo_change: set_flow::MAX_TEMPO_CHANGE,
+    })
+}
@@ -1,17 +1,463 @@
+use std::{cmp::Ordering, collections::HashMap};
+
 use loco_rs::prelude::*;
 
-use crate::models::sets;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        collections, comments, dj_follows, edit_proposals,
+        musical_keys::MusicalKey,
+        recordings::{self, format_offset, Provider},
+        set_similarities, sets, songs,
+        tags::{self, SetTag, TagCount},
+        transitions, venue_follows,
+    },
+    utils::slugify,
+    views::{
+        comments::threads,
+        feeds::set_title,
+        follows::Follow,
+        set_flow,
+        share::{truncate, Meta, IMAGE_HEIGHT, IMAGE_WIDTH, SITE_DESCRIPTION},
+    },
+    workers::waveform::Peaks,
+};
+
+/// Height of a drawn waveform, in the units of its width.
+const WAVEFORM_HEIGHT: f64 = 100.0;
+/// Tracks named in the description and share image of a set.
+const SHARED_TRACKS: usize = 5;
+
+/// What the logged in user, if any, has to do with a set.
+#[derive(Default)]
+pub struct Viewer {
+    pub user_name: String,
+    pub user_id: Option<Uuid>,
+    pub is_owner: bool,
+    pub dj_follows: Vec<dj_follows::Model>,
+    pub venue_follows: Vec<venue_follows::Model>,
+    /// Track numbers the user asked to be told about once identified.
+    pub id_requests: Vec<i32>,
+    pub reactions: sets::Reactions,
+    /// The user's collections the set can be added to.
+    pub collections: Vec<collections::Model>,
+}
+
+/// Everything shown about a set on its page.
+pub struct SetPage<'a> {
+    pub item: &'a sets::Model,
+    pub tracklist: &'a [songs::Model],
+    pub tags: &'a [tags::Model],
+    pub recordings: &'a [recordings::Model],
+    /// Peaks of uploaded recordings, by recording id.
+    pub waveforms: &'a HashMap<Uuid, Peaks>,
+    /// Open edit proposals, for the owner.
+    pub proposals: &'a [edit_proposals::Model],
+    pub comments: &'a [comments::Model],
+    pub stats: &'a sets::Stats,
+    /// The sets most like this one, the most similar first.
+    pub similarities: &'a [set_similarities::Model],
+    pub similar_sets: &'a [sets::Model],
+    /// How the tracklist is ordered.
+    pub sort: TrackSort,
+}
+
+/// The columns of a tracklist it can be sorted by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackColumn {
+    /// In the order the tracks were played.
+    #[default]
+    Number,
+    Artist,
+    Title,
+    Bpm,
+    /// Around the Camelot wheel, so that compatible keys are close.
+    Key,
+    Label,
+    Year,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackSort {
+    #[serde(default)]
+    pub sort: TrackColumn,
+    #[serde(default)]
+    pub desc: bool,
+}
+
+/// A key in all the notations DJs use.
+#[derive(Serialize)]
+struct Key {
+    name: String,
+    camelot: String,
+    open_key: String,
+    #[serde(skip)]
+    key: MusicalKey,
+}
+
+impl From<MusicalKey> for Key {
+    fn from(key: MusicalKey) -> Self {
+        Self {
+            name: key.to_string(),
+            camelot: key.camelot(),
+            open_key: key.open_key(),
+            key,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Track<'a> {
+    #[serde(flatten)]
+    song: &'a songs::Model,
+    identified: bool,
+    requested: bool,
+    /// "1:02:03" into the set.
+    start: Option<String>,
+    /// Plays the recording from the start of the track.
+    link: Option<String>,
+    /// What gets played before and after the track.
+    transitions: Option<String>,
+    /// "128" or "127.5".
+    bpm: Option<String>,
+    key: Option<Key>,
+}
+
+/// Orders missing values last, whichever way the rest is sorted.
+fn nones_last<T>(
+    a: Option<T>,
+    b: Option<T>,
+    desc: bool,
+    compare: impl FnOnce(&T, &T) -> Ordering,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if desc => compare(&b, &a),
+        (Some(a), Some(b)) => compare(&a, &b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Case-insensitive text to sort by, none when empty.
+fn sort_text(value: &str) -> Option<String> {
+    Some(value.trim().to_lowercase()).filter(|value| !value.is_empty())
+}
+
+/// Sorts `tracks` in place, keeping the order they were played in between
+/// equal ones.
+fn sort_tracks(tracks: &mut [Track<'_>], TrackSort { sort, desc }: TrackSort) {
+    tracks.sort_by(|a, b| {
+        let (a_song, b_song) = (a.song, b.song);
+        match sort {
+            TrackColumn::Number => {
+                nones_last(a_song.track_number, b_song.track_number, desc, Ord::cmp)
+            }
+            TrackColumn::Artist => nones_last(
+                sort_text(&a_song.track_artist),
+                sort_text(&b_song.track_artist),
+                desc,
+                Ord::cmp,
+            ),
+            TrackColumn::Title => nones_last(
+                sort_text(&a_song.track_title),
+                sort_text(&b_song.track_title),
+                desc,
+                Ord::cmp,
+            ),
+            TrackColumn::Bpm => {
+                nones_last(a_song.track_bpm, b_song.track_bpm, desc, f64::total_cmp)
+            }
+            TrackColumn::Key => {
+                let wheel = |track: &Track<'_>| {
+                    track
+                        .key
+                        .as_ref()
+                        .map(|key| (key.key.wheel_number(), key.key.is_minor()))
+                };
+                nones_last(wheel(a), wheel(b), desc, Ord::cmp)
+            }
+            TrackColumn::Label => nones_last(
+                a_song.track_label.as_deref().and_then(sort_text),
+                b_song.track_label.as_deref().and_then(sort_text),
+                desc,
+                Ord::cmp,
+            ),
+            TrackColumn::Year => nones_last(
+                a_song.track_release_year,
+                b_song.track_release_year,
+                desc,
+                Ord::cmp,
+            ),
+        }
+    });
+}
+
+/// BPM with as many decimals as needed, at most two.
+#[must_use]
+pub fn format_bpm(bpm: f64) -> String {
+    let formatted = format!("{bpm:.2}");
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+#[derive(Serialize)]
+struct Recording<'a> {
+    #[serde(flatten)]
+    recording: &'a recordings::Model,
+    provider_name: &'static str,
+    embed_url: Option<String>,
+    waveform: Option<Waveform>,
+}
+
+impl<'a> Recording<'a> {
+    fn new(recording: &'a recordings::Model, waveform: Option<Waveform>) -> Self {
+        Self {
+            recording,
+            provider_name: match recording.provider() {
+                Some(Provider::SoundCloud) => "SoundCloud",
+                Some(Provider::YouTube) => "YouTube",
+                Some(Provider::Mixcloud) => "Mixcloud",
+                Some(Provider::File | Provider::Upload) | None => "Audio file",
+            },
+            embed_url: recording.embed_url(),
+            waveform,
+        }
+    }
+}
+
+/// The peaks of a recording drawn as SVG, one unit wide per peak.
+#[derive(Serialize)]
+struct Waveform {
+    width: usize,
+    height: f64,
+    duration_seconds: f64,
+    /// A bar per peak, mirrored around the middle.
+    path: String,
+    markers: Vec<Marker>,
+}
+
+/// Where a track starts on a waveform.
+#[derive(Serialize)]
+struct Marker {
+    x: f64,
+    start: String,
+    title: String,
+}
+
+impl Waveform {
+    fn new(peaks: &Peaks, tracklist: &[songs::Model]) -> Self {
+        let middle = WAVEFORM_HEIGHT / 2.0;
+        let path = peaks
+            .peaks
+            .iter()
+            .enumerate()
+            .map(|(x, peak)| {
+                // silence still shows as a thin line
+                let half = (f64::from(*peak) / 255.0 * middle).max(0.5);
+                format!("M{x}.5 {:.1}V{:.1}", middle - half, middle + half)
+            })
+            .collect::<String>();
+        #[allow(clippy::cast_precision_loss)]
+        let width = peaks.peaks.len() as f64;
+        let markers = tracklist
+            .iter()
+            .filter_map(|song| {
+                let seconds = song.track_start_time_offset_seconds?;
+                let x = f64::from(seconds) / peaks.duration_seconds * width;
+                (x.is_finite() && x <= width).then(|| Marker {
+                    x,
+                    start: format_offset(seconds),
+                    title: format!("{} - {}", song.track_artist, song.track_title),
+                })
+            })
+            .collect();
+        Self {
+            width: peaks.peaks.len(),
+            height: WAVEFORM_HEIGHT,
+            duration_seconds: peaks.duration_seconds,
+            path,
+            markers,
+        }
+    }
+}
+
+/// "27 July 2024, Tresor, Berlin"
+#[must_use]
+pub fn date_and_place(item: &sets::Model) -> String {
+    let date = item.start_time.format("%-d %B %Y").to_string();
+    [Some(&date), item.venue.as_ref(), item.city.as_ref()]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn song_title(song: &songs::Model) -> String {
+    format!("{} - {}", song.track_artist, song.track_title)
+}
+
+/// A set like the one shown, with what they have in common.
+#[derive(Serialize)]
+struct Similar<'a> {
+    item: &'a sets::Model,
+    title: String,
+    /// "3 tracks in common", "same DJ"…
+    reasons: Vec<String>,
+}
+
+fn in_common(count: i64, singular: &str) -> Option<String> {
+    match count {
+        0 => None,
+        1 => Some(format!("1 {singular} in common")),
+        count => Some(format!("{count} {singular}s in common")),
+    }
+}
+
+fn similar<'a>(
+    similarities: &[set_similarities::Model],
+    similar_sets: &'a [sets::Model],
+) -> Vec<Similar<'a>> {
+    similarities
+        .iter()
+        .filter_map(|similarity| {
+            let item = similar_sets
+                .iter()
+                .find(|set| set.id == similarity.similar_set_id)?;
+            let reasons = [
+                in_common(similarity.shared_tracks, "track"),
+                (similarity.shared_djs > 0).then(|| "same DJ".to_string()),
+                in_common(similarity.shared_tags, "tag"),
+            ];
+            Some(Similar {
+                item,
+                title: set_title(item),
+                reasons: reasons.into_iter().flatten().collect(),
+            })
+        })
+        .collect()
+}
+
+/// How a set shows up in link previews: its flyer, or else an image drawn
+/// from its tracklist.
+fn meta(item: &sets::Model, tracklist: &[songs::Model]) -> Meta {
+    let mut description = date_and_place(item);
+    if !tracklist.is_empty() {
+        let tracks = tracklist
+            .iter()
+            .take(SHARED_TRACKS)
+            .map(song_title)
+            .collect::<Vec<_>>()
+            .join(", ");
+        let more = if tracklist.len() > SHARED_TRACKS {
+            ", …"
+        } else {
+            ""
+        };
+        description = format!("{description}. {} tracks: {tracks}{more}", tracklist.len());
+    }
+    Meta {
+        title: set_title(item),
+        description,
+        path: format!("/sets/{}", item.id),
+        image: Some(item.flyer_upload_id.map_or_else(
+            || format!("/sets/{}/share.png", item.id),
+            |upload_id| format!("/uploads/{upload_id}/cover.jpg"),
+        )),
+    }
+}
 
 /// Render a list view of sets.
 ///
 /// # Errors
 ///
 /// When there is an issue with rendering the view.
-pub fn list(v: &impl ViewRenderer, items: &Vec<sets::Model>, user_name: &str) -> Result<Response> {
+pub fn list(
+    v: &impl ViewRenderer,
+    items: &Vec<sets::Model>,
+    stats: &[sets::Stats],
+    tags: &[SetTag],
+    popular_tags: &[TagCount],
+    tag_filter: Option<&tags::Model>,
+    user_name: &str,
+) -> Result<Response> {
+    let stats = stats
+        .iter()
+        .map(|stats| (stats.set_id.to_string(), stats))
+        .collect::<HashMap<_, _>>();
+    let mut tags_by_set = HashMap::<_, Vec<_>>::new();
+    for tag in tags {
+        tags_by_set
+            .entry(tag.set_id.to_string())
+            .or_default()
+            .push(tag);
+    }
     format::render().view(
         v,
         "sets/list.html",
-        serde_json::json!({"items": items, "username": user_name}),
+        serde_json::json!({
+            "items": items,
+            "stats": stats,
+            "tags": tags_by_set,
+            "popular_tags": popular_tags,
+            "tag_filter": tag_filter,
+            "meta": Meta {
+                title: tag_filter.map_or_else(
+                    || "Sets".to_string(),
+                    |tag| format!("Sets tagged {}", tag.name),
+                ),
+                description: SITE_DESCRIPTION.to_string(),
+                path: tag_filter.map_or_else(
+                    || "/sets".to_string(),
+                    |tag| format!("/sets?tag={}", tag.slug),
+                ),
+                image: None,
+            },
+            "username": user_name,
+        }),
+    )
+}
+
+/// The sets of a DJ, venue or event, on the page at `path`. Its feeds are
+/// found next to it, at `path` with an `.atom` or `.ics` extension.
+pub struct Group<'a> {
+    /// What the sets have in common, e.g. `Sets by DJ`.
+    pub title: String,
+    pub path: String,
+    /// Following the DJ or venue, events cannot be followed.
+    pub follow: Option<Follow>,
+    pub items: &'a [sets::Model],
+}
+
+/// Render the page of the sets of a DJ, venue or event.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn group(v: &impl ViewRenderer, group: &Group<'_>, user_name: &str) -> Result<Response> {
+    format::render().view(
+        v,
+        "sets/group.html",
+        serde_json::json!({
+            "title": group.title,
+            "path": group.path,
+            "follow": group.follow,
+            "sets": group.items,
+            "meta": Meta {
+                title: group.title.clone(),
+                description: format!("{} and their tracklists.", group.title),
+                path: group.path.clone(),
+                image: None,
+            },
+            "username": user_name,
+        }),
     )
 }
 
@@ -20,11 +466,217 @@ pub fn list(v: &impl ViewRenderer, items: &Vec<sets::Model>, user_name: &str) ->
 /// # Errors
 ///
 /// When there is an issue with rendering the view.
-pub fn show(v: &impl ViewRenderer, item: &sets::Model, user_name: &str) -> Result<Response> {
+pub fn show(v: &impl ViewRenderer, page: &SetPage<'_>, viewer: &Viewer) -> Result<Response> {
+    let SetPage {
+        item,
+        tracklist,
+        tags,
+        recordings,
+        waveforms,
+        proposals,
+        comments,
+        stats,
+        similarities,
+        similar_sets,
+        sort,
+    } = page;
+    let Viewer {
+        user_name,
+        is_owner,
+        dj_follows,
+        venue_follows,
+        id_requests,
+        reactions,
+        collections,
+        ..
+    } = viewer;
+    let comments = threads(comments, tracklist, viewer);
+    let djs = item
+        .dj_names
+        .iter()
+        .map(|name| {
+            let slug = slugify(name);
+            let followed = dj_follows.iter().any(|follow| follow.dj_slug == slug);
+            serde_json::json!({"name": name, "slug": slug, "follow": Follow::dj(name, followed)})
+        })
+        .collect::<Vec<_>>();
+    let venue = item
+        .venue
+        .as_deref()
+        .filter(|venue| !venue.is_empty())
+        .map(|name| {
+            let slug = slugify(name);
+            let followed = venue_follows.iter().any(|follow| follow.venue_slug == slug);
+            serde_json::json!({"name": name, "slug": slug, "follow": Follow::venue(name, followed)})
+        });
+    let mut tracks = tracklist
+        .iter()
+        .map(|song| Track {
+            song,
+            bpm: song.track_bpm.map(format_bpm),
+            key: song
+                .track_key
+                .as_deref()
+                .and_then(|key| key.parse::<MusicalKey>().ok())
+                .map(Key::from),
+            identified: song.is_identified(),
+            transitions: song
+                .is_identified()
+                .then(|| transitions::track_path(&song.track_artist, &song.track_title))
+                .flatten(),
+            requested: song
+                .track_number
+                .is_some_and(|number| id_requests.contains(&number)),
+            start: song.track_start_time_offset_seconds.map(format_offset),
+            // the first recording which can start at the track
+            link: song.track_start_time_offset_seconds.and_then(|seconds| {
+                recordings
+                    .iter()
+                    .find_map(|recording| recording.deep_link(seconds))
+            }),
+        })
+        .collect::<Vec<_>>();
+    sort_tracks(&mut tracks, *sort);
+    let details = tracklist.iter().any(|song| {
+        song.track_bpm.is_some()
+            || song.track_key.is_some()
+            || song.track_label.is_some()
+            || song.track_release_year.is_some()
+    });
+    let recordings = recordings
+        .iter()
+        .map(|recording| {
+            let waveform = waveforms
+                .get(&recording.id)
+                .map(|peaks| Waveform::new(peaks, tracklist));
+            Recording::new(recording, waveform)
+        })
+        .collect::<Vec<_>>();
     format::render().view(
         v,
         "sets/show.html",
-        serde_json::json!({"item": item, "username": user_name}),
+        serde_json::json!({
+            "item": item,
+            "djs": djs,
+            "venue": venue,
+            "tracklist": tracks,
+            "details": details,
+            "flow": set_flow::report(tracklist),
+            "sort": sort,
+            "tags": tags,
+            "recordings": recordings,
+            "proposals": proposals,
+            "comments": comments,
+            "stats": stats,
+            "similar": similar(similarities, similar_sets),
+            "reactions": reactions,
+            "collections": collections,
+            "is_owner": is_owner,
+            "meta": meta(item, tracklist),
+            "username": user_name,
+        }),
+    )
+}
+
+/// Render the image shown when a set without a flyer is shared, as SVG.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn share_image(
+    v: &impl ViewRenderer,
+    item: &sets::Model,
+    tracklist: &[songs::Model],
+) -> Result<String> {
+    let tracks = tracklist
+        .iter()
+        .take(SHARED_TRACKS)
+        .map(|song| truncate(&song_title(song), 60))
+        .collect::<Vec<_>>();
+    v.render(
+        "sets/share.svg",
+        serde_json::json!({
+            "width": IMAGE_WIDTH,
+            "height": IMAGE_HEIGHT,
+            "djs": truncate(&item.dj_names.join(" B2B "), 28),
+            "date_and_place": truncate(&date_and_place(item), 60),
+            "tracks": tracks,
+            "more": tracklist.len().saturating_sub(SHARED_TRACKS),
+        }),
+    )
+}
+
+/// Render the likes, attendance and rating of a set, with the user's own.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn reactions(
+    v: &impl ViewRenderer,
+    item: &sets::Model,
+    stats: &sets::Stats,
+    reactions: &sets::Reactions,
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "sets/show.html:reactions",
+        serde_json::json!({
+            "item": item,
+            "stats": stats,
+            "reactions": reactions,
+            "username": user_name,
+        }),
+    )
+}
+
+/// Render the open edit proposals of a set, for its owner.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn proposals(
+    v: &impl ViewRenderer,
+    item: &sets::Model,
+    proposals: &[edit_proposals::Model],
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "sets/show.html:proposals",
+        serde_json::json!({"item": item, "proposals": proposals}),
+    )
+}
+
+/// Render the confirmation of a proposed edit.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn proposal_sent(v: &impl ViewRenderer, item: &sets::Model) -> Result<Response> {
+    format::render().view(
+        v,
+        "sets/show.html:propose",
+        serde_json::json!({"item": item, "proposal_sent": true}),
+    )
+}
+
+/// Render the button of an unidentified track once the user asked about it.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn id_requested(
+    v: &impl ViewRenderer,
+    item: &sets::Model,
+    song: &songs::Model,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "sets/id_request.html",
+        serde_json::json!({
+            "item": item,
+            "track": {"track_number": song.track_number, "requested": true},
+        }),
     )
 }
 
@@ -46,10 +698,20 @@ pub fn create(v: &impl ViewRenderer, user_name: &str) -> Result<Response> {
 /// # Errors
 ///
 /// When there is an issue with rendering the view.
-pub fn edit(v: &impl ViewRenderer, item: &sets::Model, user_name: &str) -> Result<Response> {
+pub fn edit(
+    v: &impl ViewRenderer,
+    item: &sets::Model,
+    tags: &[tags::Model],
+    user_name: &str,
+) -> Result<Response> {
+    let tags = tags
+        .iter()
+        .map(|tag| tag.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
     format::render().view(
         v,
         "sets/edit.html",
-        serde_json::json!({"item": item, "username": user_name}),
+        serde_json::json!({"item": item, "tags": tags, "username": user_name}),
     )
 }
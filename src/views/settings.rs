@@ -0,0 +1,232 @@
+use loco_rs::prelude::*;
+use serde_json::json;
+
+use crate::models::{
+    api_keys, dj_follows,
+    email_preferences::{self, Topic},
+    users, webhook_deliveries, webhooks,
+};
+
+fn topics(prefs: &email_preferences::Model) -> Vec<serde_json::Value> {
+    Topic::ALL
+        .into_iter()
+        .map(|topic| {
+            json!({
+                "name": topic,
+                "description": topic.description(),
+                "enabled": prefs.is_enabled(topic),
+            })
+        })
+        .collect()
+}
+
+/// Render the API key settings page.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn api_keys(
+    v: &impl ViewRenderer,
+    keys: &[api_keys::Model],
+    scopes: &[api_keys::ApiScope],
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/api_keys.html",
+        json!({"keys": keys, "scopes": scopes, "username": user_name}),
+    )
+}
+
+/// Render the list of API keys, with a newly created key shown once.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn api_keys_list(
+    v: &impl ViewRenderer,
+    keys: &[api_keys::Model],
+    new_key: Option<&str>,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/api_keys.html:keys",
+        json!({"keys": keys, "new_key": new_key}),
+    )
+}
+
+/// Render the webhook settings page.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn webhooks(
+    v: &impl ViewRenderer,
+    items: &[webhooks::Model],
+    events: &[webhooks::WebhookEvent],
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/webhooks.html",
+        json!({"items": items, "events": events, "username": user_name}),
+    )
+}
+
+/// Render the list of webhooks.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn webhooks_list(v: &impl ViewRenderer, items: &[webhooks::Model]) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/webhooks.html:webhooks",
+        json!({"items": items}),
+    )
+}
+
+/// Render a webhook with its delivery log.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn webhook(
+    v: &impl ViewRenderer,
+    item: &webhooks::Model,
+    deliveries: &[webhook_deliveries::Model],
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/webhook.html",
+        json!({"item": item, "deliveries": deliveries, "username": user_name}),
+    )
+}
+
+/// Render the delivery log of a webhook.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn webhook_deliveries(
+    v: &impl ViewRenderer,
+    item: &webhooks::Model,
+    deliveries: &[webhook_deliveries::Model],
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/webhook.html:deliveries",
+        json!({"item": item, "deliveries": deliveries}),
+    )
+}
+
+/// Render the calendar settings page.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn calendar(
+    v: &impl ViewRenderer,
+    user: &users::Model,
+    follows: &[dj_follows::Model],
+    base_url: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/calendar.html",
+        json!({
+            "calendar_token": user.calendar_token,
+            "follows": follows,
+            "base_url": base_url,
+            "username": user.username,
+        }),
+    )
+}
+
+/// Render the address of a user's calendar.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn calendar_url(
+    v: &impl ViewRenderer,
+    user: &users::Model,
+    base_url: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/calendar.html:url",
+        json!({"calendar_token": user.calendar_token, "base_url": base_url}),
+    )
+}
+
+/// Render the list of followed DJs.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn calendar_follows(v: &impl ViewRenderer, follows: &[dj_follows::Model]) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/calendar.html:follows",
+        json!({"follows": follows}),
+    )
+}
+
+/// Render the email notification settings page.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn notifications(
+    v: &impl ViewRenderer,
+    prefs: &email_preferences::Model,
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/notifications.html",
+        json!({"topics": topics(prefs), "username": user_name}),
+    )
+}
+
+/// Render the email topics a user can turn on or off.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn notification_topics(
+    v: &impl ViewRenderer,
+    prefs: &email_preferences::Model,
+    saved: bool,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/notifications.html:topics",
+        json!({"topics": topics(prefs), "saved": saved}),
+    )
+}
+
+/// Render the page confirming an unsubscribe link, or that it was used.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn unsubscribe(
+    v: &impl ViewRenderer,
+    token: &str,
+    topic: Option<Topic>,
+    unsubscribed: bool,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "settings/unsubscribe.html",
+        json!({
+            "token": token,
+            "topic": topic,
+            "description": topic.map_or("All emails from SetListList", Topic::description),
+            "unsubscribed": unsubscribed,
+        }),
+    )
+}
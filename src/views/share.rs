@@ -0,0 +1,74 @@
+use std::sync::{Arc, OnceLock};
+
+use resvg::{
+    tiny_skia::{Pixmap, Transform},
+    usvg::{self, fontdb},
+};
+use serde::Serialize;
+
+/// What the whole site is about, for pages without more to say.
+pub const SITE_DESCRIPTION: &str = "Tracklists of DJ sets, identified together.";
+
+/// Size of generated share images, as recommended for Open Graph.
+pub const IMAGE_WIDTH: u32 = 1200;
+pub const IMAGE_HEIGHT: u32 = 630;
+
+/// How a page describes itself in link previews, rendered as `<meta>` tags by
+/// the layout.
+#[derive(Debug, Default, Serialize)]
+pub struct Meta {
+    pub title: String,
+    pub description: String,
+    /// Path of the page, made absolute with the base URL.
+    pub path: String,
+    /// Path of the preview image, if the page has one.
+    pub image: Option<String>,
+}
+
+/// Shortens `text` to at most `max` characters, ending with an ellipsis when
+/// cut, for text that has to fit on an image.
+#[must_use]
+pub fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut cut = text.chars().take(max - 1).collect::<String>();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+/// Fonts are looked up once, loading them takes longer than drawing.
+fn fonts() -> Arc<fontdb::Database> {
+    static FONTS: OnceLock<Arc<fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = fontdb::Database::new();
+            fonts.load_system_fonts();
+            Arc::new(fonts)
+        })
+        .clone()
+}
+
+/// Draws an SVG of [`IMAGE_WIDTH`] by [`IMAGE_HEIGHT`] as PNG. Text is set in
+/// the fonts installed on the system.
+///
+/// # Errors
+///
+/// When the SVG is invalid or the PNG could not be encoded
+pub fn rasterize(svg: &str) -> eyre::Result<Vec<u8>> {
+    let options = usvg::Options {
+        fontdb: fonts(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let mut pixmap =
+        Pixmap::new(IMAGE_WIDTH, IMAGE_HEIGHT).ok_or_else(|| eyre::eyre!("empty image"))?;
+    let size = tree.size();
+    let transform = Transform::from_scale(
+        IMAGE_WIDTH as f32 / size.width(),
+        IMAGE_HEIGHT as f32 / size.height(),
+    );
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    Ok(pixmap.encode_png()?)
+}
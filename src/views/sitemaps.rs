@@ -0,0 +1,68 @@
+use loco_rs::prelude::*;
+use reqwest::Url;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    models::sitemaps::{Entry, Section},
+    views::feeds::rfc3339,
+};
+
+pub const XML_CONTENT_TYPE: &str = "application/xml; charset=utf-8";
+pub const TEXT_CONTENT_TYPE: &str = "text/plain; charset=utf-8";
+
+#[derive(Serialize)]
+struct Location {
+    loc: String,
+    lastmod: String,
+}
+
+/// The absolute URL of `segments`, each percent-encoded as needed.
+fn location(base_url: &Url, segments: &[String]) -> String {
+    let mut url = base_url.clone();
+    if let Ok(mut path) = url.path_segments_mut() {
+        path.pop_if_empty().extend(segments);
+    }
+    url.to_string()
+}
+
+/// Render the sitemap index listing each page of sitemaps, see
+/// <https://www.sitemaps.org/protocol.html#index>.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn index(v: &impl ViewRenderer, pages: &[String]) -> Result<String> {
+    v.render("sitemaps/index.xml", json!({ "pages": pages }))
+}
+
+/// Render a page of sitemaps of a section.
+///
+/// # Errors
+///
+/// When the base URL is invalid or there is an issue with rendering the view.
+pub fn urlset(
+    v: &impl ViewRenderer,
+    base_url: &str,
+    section: Section,
+    entries: &[Entry],
+) -> Result<String> {
+    let base_url = Url::parse(base_url).map_err(Error::wrap)?;
+    let locations = entries
+        .iter()
+        .map(|entry| Location {
+            loc: location(&base_url, &section.path_segments(&entry.key)),
+            lastmod: rfc3339(entry.lastmod),
+        })
+        .collect::<Vec<_>>();
+    v.render("sitemaps/urlset.xml", json!({ "locations": locations }))
+}
+
+/// Render `robots.txt`, pointing crawlers to the sitemap index.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn robots(v: &impl ViewRenderer, disallow: &[String]) -> Result<String> {
+    v.render("robots.txt", json!({ "disallow": disallow }))
+}
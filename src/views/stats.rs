@@ -0,0 +1,243 @@
+use chrono::{Datelike, Months, NaiveDate};
+use loco_rs::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    models::stats::{Artist, Month, Ranked, Summary, Track, Window},
+    views::share::Meta,
+};
+
+/// Width of every chart, scaled down to fit by the browser.
+const CHART_WIDTH: f64 = 720.0;
+/// Height of the columns of a monthly chart at their tallest, and of the
+/// labels below them.
+const COLUMNS_HEIGHT: f64 = 160.0;
+const AXIS_HEIGHT: f64 = 20.0;
+/// Height of each row of a ranking, of which the bar takes the lower part.
+const ROW_HEIGHT: f64 = 36.0;
+const BAR_HEIGHT: f64 = 12.0;
+/// Space between columns, as a share of their width.
+const COLUMN_GAP: f64 = 0.2;
+
+#[derive(Debug, Serialize)]
+struct Bar {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+    /// What the bar stands for, shown next to it or on hover.
+    label: String,
+    value: String,
+    /// Whether the label is written below a column, not every one fits.
+    axis_label: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Chart {
+    title: String,
+    width: f64,
+    height: f64,
+    bars: Vec<Bar>,
+}
+
+/// Horizontal bars of `rows`, longest first, each labelled above its bar.
+#[allow(clippy::cast_precision_loss)]
+fn ranking(title: &str, rows: Vec<(String, i64)>, unit: &str) -> Chart {
+    let max = rows
+        .iter()
+        .map(|(_, value)| *value)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let bars = rows
+        .into_iter()
+        .enumerate()
+        .map(|(index, (label, value))| Bar {
+            x: 0.0,
+            y: (index as f64).mul_add(ROW_HEIGHT, ROW_HEIGHT - BAR_HEIGHT),
+            width: CHART_WIDTH * value as f64 / max as f64,
+            height: BAR_HEIGHT,
+            label,
+            value: format!("{value} {unit}"),
+            axis_label: true,
+        })
+        .collect::<Vec<_>>();
+    Chart {
+        title: title.to_string(),
+        width: CHART_WIDTH,
+        height: ROW_HEIGHT * bars.len() as f64,
+        bars,
+    }
+}
+
+/// Columns of a value per month, from the oldest to the newest of `values`.
+/// Only January and the oldest month are labelled below their column.
+#[allow(clippy::cast_precision_loss)]
+fn columns(title: &str, values: Vec<(NaiveDate, f64, String)>) -> Chart {
+    let max = values
+        .iter()
+        .map(|(_, value, _)| *value)
+        .fold(0.0, f64::max);
+    let max = if max > 0.0 { max } else { 1.0 };
+    let slot = CHART_WIDTH / values.len().max(1) as f64;
+    let bars = values
+        .into_iter()
+        .enumerate()
+        .map(|(index, (month, value, formatted))| {
+            let height = COLUMNS_HEIGHT * value / max;
+            Bar {
+                x: (index as f64).mul_add(slot, slot * COLUMN_GAP / 2.0),
+                y: COLUMNS_HEIGHT - height,
+                width: slot * (1.0 - COLUMN_GAP),
+                height,
+                label: month.format("%b %Y").to_string(),
+                value: formatted,
+                axis_label: index == 0 || month.month() == 1,
+            }
+        })
+        .collect();
+    Chart {
+        title: title.to_string(),
+        width: CHART_WIDTH,
+        height: COLUMNS_HEIGHT + AXIS_HEIGHT,
+        bars,
+    }
+}
+
+/// "1h 05m"
+#[allow(clippy::cast_possible_truncation)]
+fn format_duration(seconds: f64) -> String {
+    let minutes = (seconds / 60.0).round() as i64;
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// The first of the month `count - 1` months before the month of `today`,
+/// where the monthly charts start.
+#[must_use]
+pub fn months_since(today: NaiveDate, count: u32) -> NaiveDate {
+    let month = today.with_day(1).unwrap_or(today);
+    month
+        .checked_sub_months(Months::new(count.saturating_sub(1)))
+        .unwrap_or(month)
+}
+
+/// Every month from `since` to the month of `today`, with those in which no
+/// sets were played.
+fn every_month(
+    since: NaiveDate,
+    today: NaiveDate,
+    months: &[Month],
+) -> Vec<(NaiveDate, Option<&Month>)> {
+    let mut all = Vec::new();
+    let mut month = since;
+    while month <= today {
+        all.push((month, months.iter().find(|stats| stats.month == month)));
+        let Some(next) = month.checked_add_months(Months::new(1)) else {
+            break;
+        };
+        month = next;
+    }
+    all
+}
+
+/// Everything shown under `/stats`, for one window.
+pub struct Stats {
+    pub summary: Summary,
+    pub tracks: Vec<Track>,
+    pub artists: Vec<Artist>,
+    pub djs: Vec<Ranked>,
+    pub venues: Vec<Ranked>,
+    pub months: Vec<Month>,
+}
+
+/// Render the statistics of `window`, with the monthly charts since `since`.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+#[allow(clippy::cast_precision_loss)]
+pub fn index(
+    v: &impl ViewRenderer,
+    window: Window,
+    stats: Stats,
+    since: NaiveDate,
+    today: NaiveDate,
+    user_name: &str,
+) -> Result<Response> {
+    let months = every_month(since, today, &stats.months);
+    let sets_per_month = columns(
+        "Sets per month",
+        months
+            .iter()
+            .map(|(month, month_stats)| {
+                let sets = month_stats.map_or(0, |month_stats| month_stats.sets);
+                (*month, sets as f64, format!("{sets} sets"))
+            })
+            .collect(),
+    );
+    let length_per_month = columns(
+        "Average set length",
+        months
+            .iter()
+            .map(|(month, month_stats)| {
+                let seconds = month_stats
+                    .and_then(|month_stats| month_stats.average_duration_seconds)
+                    .unwrap_or(0.0);
+                (*month, seconds, format_duration(seconds))
+            })
+            .collect(),
+    );
+    let tracks = ranking(
+        "Most played tracks",
+        stats
+            .tracks
+            .into_iter()
+            .map(|track| (format!("{} – {}", track.artist, track.title), track.plays))
+            .collect(),
+        "plays",
+    );
+    let artists = ranking(
+        "Most played artists",
+        stats
+            .artists
+            .into_iter()
+            .map(|artist| (artist.artist, artist.plays))
+            .collect(),
+        "plays",
+    );
+    let djs = ranking(
+        "Most active DJs",
+        stats.djs.into_iter().map(|dj| (dj.name, dj.sets)).collect(),
+        "sets",
+    );
+    let venues = ranking(
+        "Busiest venues",
+        stats
+            .venues
+            .into_iter()
+            .map(|venue| (venue.name, venue.sets))
+            .collect(),
+        "sets",
+    );
+
+    format::render().view(
+        v,
+        "stats/index.html",
+        json!({
+            "window": window,
+            "windows": Window::ALL,
+            "sets": stats.summary.sets,
+            "average_duration": stats.summary.average_duration_seconds.map(format_duration),
+            "rankings": [tracks, artists, djs, venues],
+            "monthly": [sets_per_month, length_per_month],
+            "meta": Meta {
+                title: "Statistics".to_string(),
+                description: "The most played tracks and artists, the most active DJs and the busiest venues.".to_string(),
+                path: "/stats".to_string(),
+                image: None,
+            },
+            "username": user_name,
+        }),
+    )
+}
@@ -0,0 +1,57 @@
+use loco_rs::prelude::*;
+use serde_json::json;
+
+use crate::{
+    models::{sets, tags},
+    views::share::Meta,
+};
+
+/// Render the page of a tag with its sets.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn show(
+    v: &impl ViewRenderer,
+    tag: &tags::Model,
+    synonyms: &[tags::Model],
+    sets: &[sets::Model],
+    is_moderator: bool,
+    user_name: &str,
+) -> Result<Response> {
+    format::render().view(
+        v,
+        "tags/show.html",
+        json!({
+            "tag": tag,
+            "synonyms": synonyms,
+            "sets": sets,
+            "is_moderator": is_moderator,
+            "meta": Meta {
+                title: format!("Sets tagged {}", tag.name),
+                description: format!("{} DJ sets tagged {} and their tracklists.", sets.len(), tag.name),
+                path: format!("/tags/{}", tag.slug),
+                image: None,
+            },
+            "username": user_name,
+        }),
+    )
+}
+
+/// Render the options completing the last tag of a comma separated `input`.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn suggestions(v: &impl ViewRenderer, input: &str, tags: &[tags::Model]) -> Result<Response> {
+    // the tags before the one being typed are kept in each option
+    let before = input
+        .rsplit_once(',')
+        .map(|(before, _)| format!("{before}, "))
+        .unwrap_or_default();
+    let options = tags
+        .iter()
+        .map(|tag| format!("{before}{}", tag.name))
+        .collect::<Vec<_>>();
+    format::render().view(v, "tags/suggestions.html", json!({"options": options}))
+}
@@ -0,0 +1,229 @@
+use loco_rs::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::{
+    models::transitions::{Neighbour, Track, Transition},
+    views::share::{truncate, Meta},
+};
+
+/// Size of the transition graph of a track, drawn with the tracks played
+/// before it on the left and those played after it on the right.
+const GRAPH_WIDTH: f64 = 800.0;
+const GRAPH_MARGIN: f64 = 24.0;
+const NODE_SPACING: f64 = 40.0;
+/// Where the labels of the neighbours end, or start on the right.
+const LABEL_WIDTH: f64 = 220.0;
+/// Characters of a label that fit next to the graph.
+const LABEL_LENGTH: usize = 32;
+/// The stroke of the most common transition, others are thinner.
+const MAX_STROKE_WIDTH: f64 = 10.0;
+
+#[derive(Debug, Serialize)]
+struct Node {
+    x: f64,
+    y: f64,
+    /// "Artist – Title", shortened to fit.
+    label: String,
+    name: String,
+    path: String,
+    transitions: i64,
+    sets: i64,
+}
+
+#[derive(Debug, Serialize)]
+struct Edge {
+    x1: f64,
+    y1: f64,
+    x2: f64,
+    y2: f64,
+    width: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct Graph {
+    width: f64,
+    height: f64,
+    center_x: f64,
+    center_y: f64,
+    before: Vec<Node>,
+    after: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+fn name(artist: &str, title: &str) -> String {
+    format!("{artist} – {title}")
+}
+
+fn path(artist_slug: &str, title_slug: &str) -> String {
+    format!("/tracks/{artist_slug}/{title_slug}")
+}
+
+/// Nodes of `neighbours` stacked at `x`, centered vertically on the graph.
+#[allow(clippy::cast_precision_loss)]
+fn column(neighbours: &[Neighbour], x: f64, height: f64) -> Vec<Node> {
+    let top = (height - NODE_SPACING * neighbours.len().saturating_sub(1) as f64) / 2.0;
+    neighbours
+        .iter()
+        .enumerate()
+        .map(|(index, neighbour)| {
+            let name = name(&neighbour.artist, &neighbour.title);
+            Node {
+                x,
+                y: (index as f64).mul_add(NODE_SPACING, top),
+                label: truncate(&name, LABEL_LENGTH),
+                name,
+                path: path(&neighbour.artist_slug, &neighbour.title_slug),
+                transitions: neighbour.transitions,
+                sets: neighbour.sets,
+            }
+        })
+        .collect()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn graph(before: &[Neighbour], after: &[Neighbour]) -> Graph {
+    let rows = before.len().max(after.len()).max(1);
+    let height = NODE_SPACING.mul_add(rows.saturating_sub(1) as f64, GRAPH_MARGIN * 2.0);
+    let (center_x, center_y) = (GRAPH_WIDTH / 2.0, height / 2.0);
+    let before = column(before, LABEL_WIDTH, height);
+    let after = column(after, GRAPH_WIDTH - LABEL_WIDTH, height);
+    let max = before
+        .iter()
+        .chain(&after)
+        .map(|node| node.transitions)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+    let stroke =
+        |transitions: i64| (MAX_STROKE_WIDTH - 1.0).mul_add(transitions as f64 / max as f64, 1.0);
+    let edges = before
+        .iter()
+        .map(|node| Edge {
+            x1: node.x + 8.0,
+            y1: node.y,
+            x2: center_x - 12.0,
+            y2: center_y,
+            width: stroke(node.transitions),
+        })
+        .chain(after.iter().map(|node| Edge {
+            x1: center_x + 12.0,
+            y1: center_y,
+            x2: node.x - 8.0,
+            y2: node.y,
+            width: stroke(node.transitions),
+        }))
+        .collect();
+    Graph {
+        width: GRAPH_WIDTH,
+        height,
+        center_x,
+        center_y,
+        before,
+        after,
+        edges,
+    }
+}
+
+fn graph_context(track: &Track, before: &[Neighbour], after: &[Neighbour]) -> serde_json::Value {
+    json!({
+        "track": track,
+        "name": name(&track.artist, &track.title),
+        "path": path(&track.artist_slug, &track.title_slug),
+        "graph": graph(before, after),
+    })
+}
+
+/// Render the page of a track with the tracks commonly mixed into and out of
+/// it.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn show(
+    v: &impl ViewRenderer,
+    track: &Track,
+    before: &[Neighbour],
+    after: &[Neighbour],
+    user_name: &str,
+) -> Result<Response> {
+    let name = name(&track.artist, &track.title);
+    let mut context = graph_context(track, before, after);
+    context["meta"] = json!(Meta {
+        title: name.clone(),
+        description: format!("What DJs play before and after {name}."),
+        path: path(&track.artist_slug, &track.title_slug),
+        image: None,
+    });
+    context["username"] = json!(user_name);
+    format::render().view(v, "tracks/show.html", context)
+}
+
+/// Render the transitions of a track alone, replacing the previous one in the
+/// explorer.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn graph_fragment(
+    v: &impl ViewRenderer,
+    track: &Track,
+    before: &[Neighbour],
+    after: &[Neighbour],
+) -> Result<Response> {
+    format::render().view(v, "tracks/graph.html", graph_context(track, before, after))
+}
+
+/// Render the explorer, starting from the most common transitions.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn explorer(v: &impl ViewRenderer, top: &[Transition], user_name: &str) -> Result<Response> {
+    let top = top
+        .iter()
+        .map(|transition| {
+            json!({
+                "from": name(&transition.from_artist, &transition.from_title),
+                "from_path": path(&transition.from_artist_slug, &transition.from_title_slug),
+                "to": name(&transition.to_artist, &transition.to_title),
+                "to_path": path(&transition.to_artist_slug, &transition.to_title_slug),
+                "transitions": transition.transitions,
+                "sets": transition.sets,
+            })
+        })
+        .collect::<Vec<_>>();
+    format::render().view(
+        v,
+        "tracks/explorer.html",
+        json!({
+            "top": top,
+            "meta": Meta {
+                title: "What gets played after this".to_string(),
+                description: "Explore which tracks DJs mix into each other.".to_string(),
+                path: "/transitions".to_string(),
+                image: None,
+            },
+            "username": user_name,
+        }),
+    )
+}
+
+/// Render the tracks matching a search of the explorer.
+///
+/// # Errors
+///
+/// When there is an issue with rendering the view.
+pub fn search_results(v: &impl ViewRenderer, tracks: &[Track]) -> Result<Response> {
+    let tracks = tracks
+        .iter()
+        .map(|track| {
+            json!({
+                "name": name(&track.artist, &track.title),
+                "path": path(&track.artist_slug, &track.title_slug),
+                "plays": track.plays,
+            })
+        })
+        .collect::<Vec<_>>();
+    format::render().view(v, "tracks/search.html", json!({ "tracks": tracks }))
+}
@@ -1,14 +1,46 @@
 use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::views::{follows::Follow, share::Meta};
+
+/// The lists of sets on a profile page.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Tab {
+    /// Sets the user added.
+    #[default]
+    Sets,
+    /// Sets the user checked in to, only shown to the user.
+    Attended,
+    Liked,
+    Collections,
+}
 
 pub fn sets(
     v: &impl ViewRenderer,
     username: &str,
     sets: &Vec<crate::models::sets::Model>,
+    collections: &[crate::models::collections::Model],
+    tab: Tab,
+    follow: Option<&Follow>,
     own_user: &str,
 ) -> Result<Response> {
     format::render().view(
         v,
         "user/user.html",
-        serde_json::json!({"page_user": username, "sets": sets, "username": own_user}),
+        serde_json::json!({
+            "page_user": username,
+            "sets": sets,
+            "collections": collections,
+            "tab": tab,
+            "follow": follow,
+            "meta": Meta {
+                title: username.to_string(),
+                description: format!("DJ sets added by {username} on SetListList."),
+                path: format!("/user/{username}"),
+                image: None,
+            },
+            "username": own_user,
+        }),
     )
 }
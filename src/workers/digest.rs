@@ -0,0 +1,65 @@
+use chrono::{Duration, Utc};
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    mailers::notifications::NotificationMailer,
+    models::{email_preferences, sets, users},
+};
+
+/// When the digest goes out, in the six-field cron syntax of
+/// `sidekiq::periodic`: Mondays at 08:00 UTC.
+pub const SCHEDULE: &str = "0 0 8 * * Mon";
+/// How many sets a digest lists at most.
+const DIGEST_LENGTH: i64 = 20;
+
+pub struct DigestWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct DigestWorkerArgs {}
+
+impl AppWorker<DigestWorkerArgs> for DigestWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+}
+
+#[async_trait]
+impl worker::Worker<DigestWorkerArgs> for DigestWorker {
+    /// Sends the weekly digest to every user who opted in and has not had one
+    /// in the past week, listing what changed in their feed since the last
+    /// one.
+    async fn perform(&self, _args: DigestWorkerArgs) -> worker::Result<()> {
+        let db = &self.ctx.db;
+        let now = Utc::now().naive_utc();
+        let due = email_preferences::Model::list_due_for_digest(db, now)
+            .await
+            .map_err(Box::from)?;
+
+        for mut preferences in due {
+            let since = preferences
+                .last_digest_at
+                .unwrap_or_else(|| now - Duration::weeks(1));
+            let user = users::Model::find_by_id(db, preferences.user_id)
+                .await
+                .map_err(Box::from)?;
+            let items =
+                sets::Model::list_followed_by_updated_since(db, user.id, since, DIGEST_LENGTH)
+                    .await
+                    .map_err(Box::from)?;
+            // nothing new is not worth an email
+            if !items.is_empty() {
+                NotificationMailer::digest(&self.ctx, &user, &preferences, &items)
+                    .await
+                    .map_err(Box::from)?;
+            }
+            preferences
+                .set_digest_sent(db, now)
+                .await
+                .map_err(Box::from)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::io::Cursor;
+
+use axum::body::Bytes;
+use image::{
+    codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageFormat, ImageReader,
+    ImageResult, Limits,
+};
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{controllers::uploads::STORE, models::uploads};
+
+/// Widths images are resized to, by name. Smaller images are not enlarged.
+pub const SIZES: [(&str, u32); 2] = [("thumb", 400), ("cover", 1200)];
+/// Images larger than this either way are not decoded.
+const MAX_DIMENSION: u32 = 6000;
+/// Memory decoding an image may take, which small files of huge images would
+/// otherwise blow up to hundreds of megabytes.
+const MAX_DECODED_BYTES: u64 = 64 * 1024 * 1024;
+const JPEG_QUALITY: u8 = 85;
+const WEBP_QUALITY: f32 = 80.0;
+
+/// The type of an image in a format that can be uploaded, told by its first
+/// bytes rather than by what the browser claims.
+#[must_use]
+pub fn content_type(content: &[u8]) -> Option<&'static str> {
+    match image::guess_format(content).ok()? {
+        format @ (ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP) => {
+            Some(format.to_mime_type())
+        }
+        _ => None,
+    }
+}
+
+/// Resizes an image to each of [`SIZES`], as lossy WebP for browsers which
+/// support it and as JPEG for everything else. Returns the content by variant
+/// name, like "thumb.webp".
+///
+/// # Errors
+///
+/// When the image cannot be decoded or is too large
+pub fn resize(content: &[u8]) -> ImageResult<Vec<(String, Vec<u8>)>> {
+    let mut reader = ImageReader::new(Cursor::new(content)).with_guessed_format()?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODED_BYTES);
+    reader.limits(limits);
+    let image = reader.decode()?;
+
+    let mut variants = vec![];
+    for (name, width) in SIZES {
+        let resized =
+            (image.width() > width).then(|| image.resize(width, u32::MAX, FilterType::Lanczos3));
+        let resized = resized.as_ref().unwrap_or(&image);
+
+        // libwebp takes 8 bit RGB(A) only, and keeps the alpha channel
+        let webp = if resized.color().has_alpha() {
+            let rgba = resized.to_rgba8();
+            webp::Encoder::from_rgba(&rgba, rgba.width(), rgba.height()).encode(WEBP_QUALITY)
+        } else {
+            let rgb = resized.to_rgb8();
+            webp::Encoder::from_rgb(&rgb, rgb.width(), rgb.height()).encode(WEBP_QUALITY)
+        };
+        variants.push((format!("{name}.webp"), webp.to_vec()));
+
+        let mut jpeg = vec![];
+        let encoder = JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY);
+        match resized {
+            DynamicImage::ImageRgb8(_) | DynamicImage::ImageLuma8(_) => {
+                resized.write_with_encoder(encoder)?;
+            }
+            // JPEG has no alpha channel nor more than 8 bits per channel
+            _ => resized.to_rgb8().write_with_encoder(encoder)?,
+        }
+        variants.push((format!("{name}.jpg"), jpeg));
+    }
+    Ok(variants)
+}
+
+pub struct ImageWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct ImageWorkerArgs {
+    pub upload_id: Uuid,
+}
+
+impl AppWorker<ImageWorkerArgs> for ImageWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+}
+
+#[async_trait]
+impl worker::Worker<ImageWorkerArgs> for ImageWorker {
+    async fn perform(&self, args: ImageWorkerArgs) -> worker::Result<()> {
+        let upload = uploads::Model::find_by_id(&self.ctx.db, args.upload_id)
+            .await
+            .map_err(Box::from)?;
+        let store = self.ctx.storage.as_store_err(STORE).map_err(Box::from)?;
+        let content = store
+            .get(&upload.storage_path())
+            .await
+            .map_err(Box::from)?
+            .bytes()
+            .await
+            .map_err(Box::from)?;
+
+        let resized = tokio::task::spawn_blocking(move || resize(&content))
+            .await
+            .map_err(Box::from)?;
+        let variants = match resized {
+            Ok(variants) => variants,
+            Err(err) => {
+                // the original is shown in place of the variants
+                tracing::warn!(upload_id = %upload.id, error = %err, "could not resize upload");
+                return Ok(());
+            }
+        };
+
+        for (name, content) in variants {
+            store
+                .upload(&upload.variant_path(&name), &Bytes::from(content))
+                .await
+                .map_err(Box::from)?;
+            uploads::Model::add_variant(&self.ctx.db, upload.id, &name)
+                .await
+                .map_err(Box::from)?;
+        }
+        Ok(())
+    }
+}
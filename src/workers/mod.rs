@@ -1 +1,6 @@
-
+pub mod digest;
+pub mod images;
+pub mod similarity;
+pub mod stats;
+pub mod waveform;
+pub mod webhook;
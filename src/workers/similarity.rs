@@ -0,0 +1,69 @@
+use eyre::Report;
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{set_similarities, sets};
+
+/// When the similarities of all sets are recomputed, in the six-field cron
+/// syntax of `sidekiq::periodic`: every night at 4. Tag merges and sets
+/// falling out of each other's most similar ones are only caught up then.
+pub const SCHEDULE: &str = "0 0 4 * * *";
+
+pub struct SimilarityWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct SimilarityWorkerArgs {
+    /// The set whose tracklist, DJs or tags changed, or every set when none.
+    pub set_id: Option<Uuid>,
+}
+
+impl AppWorker<SimilarityWorkerArgs> for SimilarityWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+}
+
+#[async_trait]
+impl worker::Worker<SimilarityWorkerArgs> for SimilarityWorker {
+    /// Recomputes which sets are like the changed ones, which is too costly
+    /// to do on each page load.
+    async fn perform(&self, args: SimilarityWorkerArgs) -> worker::Result<()> {
+        let ids = match args.set_id {
+            Some(set_id) => vec![set_id],
+            None => sets::Model::list_ids(&self.ctx.db)
+                .await
+                .map_err(Box::from)?,
+        };
+        for set_id in ids {
+            // a set deleted since takes its similarities along
+            let candidates = set_similarities::Model::list_candidates(&self.ctx.db, set_id)
+                .await
+                .map_err(Box::from)?;
+            set_similarities::Model::replace_for_set(&self.ctx.db, set_id, &candidates)
+                .await
+                .map_err(Box::from)?;
+        }
+        Ok(())
+    }
+}
+
+/// Queues recomputing which sets are like the set `set_id`, after its
+/// tracklist, DJs or tags changed.
+///
+/// # Errors
+///
+/// When the job could not be queued
+pub async fn set_changed(ctx: &AppContext, set_id: Uuid) -> Result<()> {
+    SimilarityWorker::perform_later(
+        ctx,
+        SimilarityWorkerArgs {
+            set_id: Some(set_id),
+        },
+    )
+    .await
+    .map_err(Report::new)?;
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use loco_rs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{stats, transitions};
+
+/// When the statistics are recomputed, in the six-field cron syntax of
+/// `sidekiq::periodic`: every hour on the hour.
+pub const SCHEDULE: &str = "0 0 * * * *";
+
+pub struct StatsWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct StatsWorkerArgs {}
+
+impl AppWorker<StatsWorkerArgs> for StatsWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+}
+
+#[async_trait]
+impl worker::Worker<StatsWorkerArgs> for StatsWorker {
+    /// Recomputes the statistics shown under `/stats` and the transitions
+    /// between tracks, which are too costly to aggregate on each page load.
+    async fn perform(&self, _args: StatsWorkerArgs) -> worker::Result<()> {
+        stats::refresh(&self.ctx.db).await.map_err(Box::from)?;
+        transitions::refresh(&self.ctx.db)
+            .await
+            .map_err(Box::from)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    io::{self, Read},
+    sync::Mutex,
+};
+
+use axum::body::Bytes;
+use futures_util::TryStreamExt as _;
+use loco_rs::{prelude::*, storage::drivers::StoreDriver};
+use serde::{Deserialize, Serialize};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::DecoderOptions,
+    errors::Error as DecodeError,
+    formats::FormatOptions,
+    io::{MediaSourceStream, ReadOnlySource},
+    meta::MetadataOptions,
+    probe::Hint,
+};
+use tokio_util::io::{StreamReader, SyncIoBridge};
+use uuid::Uuid;
+
+use crate::{controllers::uploads::STORE, models::uploads};
+
+/// Peaks measured per second of audio before they are merged down.
+const WINDOWS_PER_SECOND: u32 = 10;
+/// Most peaks kept for a recording, about one per pixel of the page.
+const MAX_PEAKS: usize = 1000;
+
+/// The loudness over the length of a recording, for drawing its waveform.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Peaks {
+    pub duration_seconds: f64,
+    /// Loudest sample of each slice of the recording, from 0 to 255.
+    pub peaks: Vec<u8>,
+}
+
+/// Name of the upload variant the peaks are stored as.
+pub const PEAKS: &str = "peaks.json";
+
+impl Peaks {
+    /// Peaks of an upload, once the worker measured them.
+    pub async fn load(store: &dyn StoreDriver, upload_id: Uuid) -> Option<Self> {
+        let content = store
+            .get(&uploads::variant_path(upload_id, PEAKS))
+            .await
+            .ok()?;
+        serde_json::from_slice(&content.bytes().await.ok()?).ok()
+    }
+
+    /// Decodes the audio from `source` to measure its peaks.
+    ///
+    /// # Errors
+    ///
+    /// When the audio cannot be decoded
+    pub fn measure(
+        source: impl Read + Send + Sync + 'static,
+        content_type: &str,
+    ) -> Result<Self, DecodeError> {
+        let source =
+            MediaSourceStream::new(Box::new(ReadOnlySource::new(source)), Default::default());
+        let mut hint = Hint::new();
+        hint.mime_type(content_type);
+        let mut format = symphonia::default::get_probe()
+            .format(
+                &hint,
+                source,
+                &FormatOptions::default(),
+                &MetadataOptions::default(),
+            )?
+            .format;
+        let track = format
+            .default_track()
+            .ok_or(DecodeError::Unsupported("no audio track"))?;
+        let track_id = track.id;
+        let sample_rate = track
+            .codec_params
+            .sample_rate
+            .ok_or(DecodeError::Unsupported("no sample rate"))?;
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+
+        let window = usize::try_from((sample_rate / WINDOWS_PER_SECOND).max(1)).unwrap_or(1);
+        let mut windows = vec![];
+        let (mut peak, mut in_window, mut frames) = (0f32, 0, 0u64);
+        let mut buffer: Option<SampleBuffer<f32>> = None;
+        loop {
+            let packet = match format.next_packet() {
+                Ok(packet) => packet,
+                Err(DecodeError::IoError(err)) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                    break
+                }
+                Err(err) => return Err(err),
+            };
+            if packet.track_id() != track_id {
+                continue;
+            }
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // a damaged packet leaves a gap rather than failing the whole file
+                Err(DecodeError::DecodeError(_)) => continue,
+                Err(err) => return Err(err),
+            };
+            let spec = *decoded.spec();
+            let channels = spec.channels.count();
+            let samples = decoded.capacity() * channels;
+            let buffer = match &mut buffer {
+                Some(buffer) if buffer.capacity() >= samples => buffer,
+                buffer => buffer.insert(SampleBuffer::new(decoded.capacity() as u64, spec)),
+            };
+            buffer.copy_interleaved_ref(decoded);
+            for frame in buffer.samples().chunks(channels) {
+                peak = frame
+                    .iter()
+                    .fold(peak, |peak, sample| peak.max(sample.abs()));
+                in_window += 1;
+                frames += 1;
+                if in_window == window {
+                    windows.push(peak);
+                    (peak, in_window) = (0.0, 0);
+                }
+            }
+        }
+        if in_window > 0 {
+            windows.push(peak);
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let duration_seconds = frames as f64 / f64::from(sample_rate);
+        Ok(Self {
+            duration_seconds,
+            peaks: merge(&windows, MAX_PEAKS),
+        })
+    }
+}
+
+/// Merges the peaks of windows into at most `count` peaks, scaled to bytes.
+fn merge(windows: &[f32], count: usize) -> Vec<u8> {
+    let slices = windows.len().min(count);
+    (0..slices)
+        .map(|slice| {
+            let slice =
+                &windows[slice * windows.len() / slices..(slice + 1) * windows.len() / slices];
+            let peak = slice.iter().fold(0f32, |peak, window| peak.max(*window));
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let peak = (peak.min(1.0) * 255.0).round() as u8;
+            peak
+        })
+        .collect()
+}
+
+/// Gives decoding, which wants to share its source between threads, the
+/// stream of the upload it reads alone.
+struct Exclusive<R>(Mutex<R>);
+
+impl<R: Read> Read for Exclusive<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0
+            .get_mut()
+            .map_err(|_| io::Error::other("poisoned reader"))?
+            .read(buf)
+    }
+}
+
+pub struct WaveformWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct WaveformWorkerArgs {
+    pub upload_id: Uuid,
+}
+
+impl AppWorker<WaveformWorkerArgs> for WaveformWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+}
+
+#[async_trait]
+impl worker::Worker<WaveformWorkerArgs> for WaveformWorker {
+    async fn perform(&self, args: WaveformWorkerArgs) -> worker::Result<()> {
+        let upload = uploads::Model::find_by_id(&self.ctx.db, args.upload_id)
+            .await
+            .map_err(Box::from)?;
+        let store = self.ctx.storage.as_store_err(STORE).map_err(Box::from)?;
+        let content = store
+            .get(&upload.storage_path())
+            .await
+            .map_err(Box::from)?
+            .into_stream()
+            .map_err(io::Error::other);
+        let source = Exclusive(Mutex::new(SyncIoBridge::new(StreamReader::new(content))));
+
+        let content_type = upload.content_type.clone();
+        let measured = tokio::task::spawn_blocking(move || Peaks::measure(source, &content_type))
+            .await
+            .map_err(Box::from)?;
+        let peaks = match measured {
+            Ok(peaks) => peaks,
+            Err(err) => {
+                // the file stays playable, only without a waveform
+                tracing::warn!(upload_id = %upload.id, error = %err, "could not decode upload");
+                return Ok(());
+            }
+        };
+
+        let content = Bytes::from(serde_json::to_vec(&peaks).map_err(Box::from)?);
+        store
+            .upload(&upload.variant_path(PEAKS), &content)
+            .await
+            .map_err(Box::from)?;
+        uploads::Model::add_variant(&self.ctx.db, upload.id, PEAKS)
+            .await
+            .map_err(Box::from)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,240 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use eyre::Report;
+use loco_rs::prelude::*;
+use reqwest::{redirect::Policy, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    controllers::sets::Song,
+    models::{
+        sets, songs,
+        webhook_deliveries::{self, Attempt, DeliveryStatus},
+        webhooks::{self, WebhookEvent},
+    },
+    settings::Settings,
+    views::api::SetResponse,
+};
+
+/// Attempts made by the worker before a delivery is marked as failed.
+pub const MAX_ATTEMPTS: i32 = 5;
+/// Delay before the first retry, doubled after every failed attempt.
+const BASE_DELAY: Duration = Duration::from_secs(30);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct WebhookWorker {
+    pub ctx: AppContext,
+}
+
+#[derive(Deserialize, Debug, Serialize)]
+pub struct WebhookWorkerArgs {
+    pub delivery_id: Uuid,
+}
+
+impl AppWorker<WebhookWorkerArgs> for WebhookWorker {
+    fn build(ctx: &AppContext) -> Self {
+        Self { ctx: ctx.clone() }
+    }
+}
+
+/// Delay before the attempt following `attempts` failed ones.
+#[must_use]
+pub fn backoff(attempts: i32) -> Duration {
+    BASE_DELAY * 2u32.pow(attempts.clamp(0, MAX_ATTEMPTS).unsigned_abs())
+}
+
+/// The body sent to the receiver.
+#[must_use]
+pub fn request_body(delivery: &webhook_deliveries::Model) -> serde_json::Value {
+    json!({
+        "id": delivery.id,
+        "event": delivery.event,
+        "created_at": delivery.created_at,
+        "data": delivery.payload,
+    })
+}
+
+impl WebhookWorker {
+    /// A client connecting to the addresses the webhook host was just checked
+    /// to resolve to, and not following redirects, which could point anywhere.
+    async fn client(
+        &self,
+        webhook: &webhooks::Model,
+    ) -> std::result::Result<reqwest::Client, String> {
+        let settings = Settings::from_config(&self.ctx.config).map_err(|err| err.to_string())?;
+        let url = Url::parse(&webhook.url).map_err(|err| err.to_string())?;
+        let addrs = webhooks::resolve(&url, settings.webhooks.allow_private_addresses)
+            .await
+            .map_err(|err| err.to_string())?;
+        reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .redirect(Policy::none())
+            .resolve_to_addrs(url.host_str().unwrap_or_default(), &addrs)
+            .build()
+            .map_err(|err| err.to_string())
+    }
+
+    /// Posts the payload, returning the response status and an error message
+    /// when the receiver did not accept it. Only the status of a response is
+    /// kept, its body could be anything the receiver hands out.
+    async fn send(
+        &self,
+        webhook: &webhooks::Model,
+        delivery: &webhook_deliveries::Model,
+    ) -> (Option<i32>, Option<String>) {
+        let client = match self.client(webhook).await {
+            Ok(client) => client,
+            Err(err) => return (None, Some(err)),
+        };
+        let body = request_body(delivery).to_string();
+        let response = client
+            .post(&webhook.url)
+            .header("Content-Type", "application/json")
+            .header("User-Agent", "SetListList-Webhooks")
+            .header("X-Setlist-Event", &delivery.event)
+            .header("X-Setlist-Delivery", delivery.id.to_string())
+            .header("X-Setlist-Signature", webhook.sign(body.as_bytes()))
+            .body(body)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => {
+                (Some(i32::from(response.status().as_u16())), None)
+            }
+            Ok(response) => (
+                Some(i32::from(response.status().as_u16())),
+                Some(format!(
+                    "The receiver responded with HTTP {}",
+                    response.status().as_u16()
+                )),
+            ),
+            Err(err) => (None, Some(err.without_url().to_string())),
+        }
+    }
+}
+
+#[async_trait]
+impl worker::Worker<WebhookWorkerArgs> for WebhookWorker {
+    async fn perform(&self, args: WebhookWorkerArgs) -> worker::Result<()> {
+        let db = &self.ctx.db;
+        let delivery = webhook_deliveries::Model::find_by_id(db, args.delivery_id)
+            .await
+            .map_err(Box::from)?;
+        if delivery.status != DeliveryStatus::Pending.as_str() {
+            return Ok(());
+        }
+        let webhook = webhooks::Model::find_by_id(db, delivery.webhook_id)
+            .await
+            .map_err(Box::from)?;
+
+        let (response_status, error) = self.send(&webhook, &delivery).await;
+        let retry = error.is_some() && delivery.attempts + 1 < MAX_ATTEMPTS;
+        let delay = backoff(delivery.attempts);
+        let status = match (&error, retry) {
+            (None, _) => DeliveryStatus::Succeeded,
+            (Some(_), true) => DeliveryStatus::Pending,
+            (Some(_), false) => DeliveryStatus::Failed,
+        };
+        let next_attempt_at = retry
+            .then(|| chrono::Duration::from_std(delay).ok())
+            .flatten()
+            .map(|delay| Utc::now().naive_utc() + delay);
+
+        delivery
+            .record_attempt(
+                db,
+                &Attempt {
+                    status,
+                    response_status,
+                    error: error.as_deref(),
+                    next_attempt_at,
+                },
+            )
+            .await
+            .map_err(Box::from)?;
+
+        if retry {
+            Self::perform_after(&self.ctx, delay, args).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Queues a delivery of `payload` to every webhook of `user_id` subscribed to
+/// `event`.
+///
+/// # Errors
+///
+/// When the deliveries could not be saved or queued
+pub async fn dispatch(
+    ctx: &AppContext,
+    user_id: Uuid,
+    event: WebhookEvent,
+    payload: &impl Serialize,
+) -> Result<()> {
+    let webhooks = webhooks::Model::list_subscribed(&ctx.db, user_id, event).await?;
+    if webhooks.is_empty() {
+        return Ok(());
+    }
+
+    let payload = serde_json::to_value(payload)?;
+    for webhook in webhooks {
+        let delivery =
+            webhook_deliveries::Model::create(&ctx.db, webhook.id, event.as_str(), &payload)
+                .await?;
+        WebhookWorker::perform_later(
+            ctx,
+            WebhookWorkerArgs {
+                delivery_id: delivery.id,
+            },
+        )
+        .await
+        .map_err(Report::new)?;
+    }
+    Ok(())
+}
+
+/// Notifies the set creator's webhooks that a set was created, updated or
+/// deleted.
+///
+/// # Errors
+///
+/// When the deliveries could not be saved or queued
+pub async fn set_changed(
+    ctx: &AppContext,
+    event: WebhookEvent,
+    set: &sets::Model,
+    tracklist: &[songs::Model],
+) -> Result<()> {
+    let payload = SetResponse {
+        set: set.clone(),
+        tracklist: tracklist.iter().cloned().map(Song::from).collect(),
+    };
+    dispatch(ctx, set.creator_id, event, &payload).await
+}
+
+/// Notifies the set creator's webhooks about every track of `after` that was
+/// unidentified in `before`.
+///
+/// # Errors
+///
+/// When the deliveries could not be saved or queued
+pub async fn tracks_identified(
+    ctx: &AppContext,
+    set: &sets::Model,
+    before: &[songs::Model],
+    after: &[songs::Model],
+) -> Result<()> {
+    for song in songs::Model::newly_identified(before, after) {
+        let payload = json!({
+            "set_id": set.id,
+            "track": Song::from(song.clone()),
+        });
+        dispatch(ctx, set.creator_id, WebhookEvent::TrackIdentified, &payload).await?;
+    }
+    Ok(())
+}
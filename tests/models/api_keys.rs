@@ -0,0 +1,67 @@
+use chrono::{Duration, Utc};
+use loco_rs::testing;
+use setlist_list::{
+    app::App,
+    models::api_keys::{self, ApiScope, Model},
+};
+use sqlx::PgPool;
+use uuid::uuid;
+
+const USER_ID: uuid::Uuid = uuid!("11111111-1111-1111-1111-111111111111");
+
+#[sqlx::test(fixtures("users"))]
+async fn can_create_and_use_key(pool: PgPool) -> eyre::Result<()> {
+    let boot = testing::boot_test::<App>(pool).await?;
+    let db = &boot.app_context.db;
+
+    let (item, key) = Model::create(db, USER_ID, "cli", &[ApiScope::Read], None).await?;
+    assert!(key.starts_with(&item.prefix));
+    assert_eq!(item.key_hash, api_keys::hash_key(&key));
+    assert!(item.last_used_at.is_none());
+
+    let (user_id, scopes) = Model::use_key(db, &key).await?;
+    assert_eq!(user_id, USER_ID);
+    assert_eq!(scopes, vec!["read"]);
+
+    let item = Model::find_by_id_and_user_id(db, item.id, USER_ID).await?;
+    assert!(item.last_used_at.is_some());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("users"))]
+async fn cannot_use_expired_key(pool: PgPool) -> eyre::Result<()> {
+    let boot = testing::boot_test::<App>(pool).await?;
+    let db = &boot.app_context.db;
+
+    let expires_at = Utc::now().naive_utc() - Duration::minutes(1);
+    let (item, key) =
+        Model::create(db, USER_ID, "old", &[ApiScope::Read], Some(expires_at)).await?;
+    assert!(item.is_expired());
+    assert!(Model::use_key(db, &key).await.is_err());
+
+    Ok(())
+}
+
+#[sqlx::test(fixtures("users"))]
+async fn cannot_create_key_without_scopes(pool: PgPool) -> eyre::Result<()> {
+    let boot = testing::boot_test::<App>(pool).await?;
+
+    assert!(
+        Model::create(&boot.app_context.db, USER_ID, "none", &[], None)
+            .await
+            .is_err()
+    );
+
+    Ok(())
+}
+
+#[test]
+fn broader_scopes_grant_narrower_ones() {
+    let scopes = |scopes: &[&str]| scopes.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+    assert!(ApiScope::Read.is_granted(&scopes(&["sets:write"])));
+    assert!(ApiScope::WriteSets.is_granted(&scopes(&["admin"])));
+    assert!(!ApiScope::WriteSets.is_granted(&scopes(&["read"])));
+    assert!(!ApiScope::Admin.is_granted(&scopes(&["read", "sets:write"])));
+}
@@ -0,0 +1,19 @@
+INSERT INTO
+    setlist_songs (
+        setlist_id,
+        track_title,
+        track_artist,
+        track_number
+    )
+VALUES (
+        '33333333-3333-3333-3333-333333333333',
+        'The Bells',
+        'Jeff Mills',
+        1
+    ),
+    (
+        '33333333-3333-3333-3333-333333333333',
+        'Your Love',
+        'Frankie Knuckles & Jamie Principle',
+        2
+    )
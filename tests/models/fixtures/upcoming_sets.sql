@@ -0,0 +1,37 @@
+INSERT INTO
+    sets (
+        created_at,
+        updated_at,
+        id,
+        creator_id,
+        dj_names,
+        venue,
+        city,
+        event_name,
+        start_time,
+        duration_seconds
+    )
+VALUES (
+        '2024-09-01T10:00:00.000',
+        '2024-09-01T10:00:00.000',
+        '44444444-4444-4444-4444-444444444444',
+        '11111111-1111-1111-1111-111111111111',
+        ARRAY ['Jeff Mills'],
+        'Tresor',
+        'Berlin',
+        'Tresor 100, the night that goes on and on; with a description long enough to fold',
+        '2100-01-01T23:00',
+        '7200'
+    ),
+    (
+        '2024-09-02T10:00:00.000',
+        '2024-09-02T10:00:00.000',
+        '55555555-5555-5555-5555-555555555555',
+        '11111111-1111-1111-1111-111111111111',
+        ARRAY ['DJ Stingray 313'],
+        'Marble Bar',
+        'Detroit',
+        NULL,
+        '2100-02-01T22:00',
+        NULL
+    )
@@ -1,3 +1,7 @@
+mod api_keys;
+mod musical_keys;
+mod recordings;
+mod set_similarities;
 mod users;
 
 mod sets;
@@ -0,0 +1,50 @@
+use setlist_list::models::musical_keys::MusicalKey;
+
+fn key(value: &str) -> MusicalKey {
+    value.parse().unwrap_or_else(|err| panic!("{err}"))
+}
+
+#[test]
+fn converts_between_notations() {
+    for (name, camelot, open_key) in [
+        ("Am", "8A", "1m"),
+        ("C", "8B", "1d"),
+        ("Em", "9A", "2m"),
+        ("G#m", "1A", "6m"),
+        ("B", "1B", "6d"),
+        ("Db", "3B", "8d"),
+        ("Ebm", "2A", "7m"),
+        ("F", "7B", "12d"),
+    ] {
+        for notation in [name, camelot, open_key] {
+            let parsed = key(notation);
+            assert_eq!(parsed.to_string(), name, "{notation}");
+            assert_eq!(parsed.camelot(), camelot, "{notation}");
+            assert_eq!(parsed.open_key(), open_key, "{notation}");
+        }
+    }
+}
+
+#[test]
+fn parses_spellings() {
+    assert_eq!(key("a minor"), key("Am"));
+    assert_eq!(key(" C# "), key("Db"));
+    assert_eq!(key("Bbmin"), key("A#m"));
+    assert_eq!(key("E♭"), key("Eb"));
+    assert_eq!(key("bm"), key("10A"));
+    assert_eq!(key("12m"), key("Dm"));
+    for invalid in ["", "H", "Am7", "13A", "0B", "8C", "8"] {
+        assert!(invalid.parse::<MusicalKey>().is_err(), "{invalid}");
+    }
+}
+
+#[test]
+fn measures_wheel_distance() {
+    assert_eq!(key("8A").wheel_distance(key("8A")), 0);
+    assert_eq!(key("8A").wheel_distance(key("9A")), 1);
+    assert_eq!(key("8A").wheel_distance(key("8B")), 1);
+    assert_eq!(key("12A").wheel_distance(key("1A")), 1);
+    assert_eq!(key("1A").wheel_steps(key("12A")), -1);
+    assert_eq!(key("8A").wheel_distance(key("9B")), 2);
+    assert_eq!(key("9A").wheel_distance(key("3B")), 7);
+}
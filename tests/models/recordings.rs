@@ -0,0 +1,74 @@
+use loco_rs::testing;
+use setlist_list::{
+    app::App,
+    models::recordings::{format_offset, Model},
+};
+use sqlx::PgPool;
+use uuid::uuid;
+
+const SET_ID: uuid::Uuid = uuid!("33333333-3333-3333-3333-333333333333");
+
+#[sqlx::test(fixtures("users", "sets"))]
+async fn can_link_recordings(pool: PgPool) -> eyre::Result<()> {
+    let boot = testing::boot_test::<App>(pool).await?;
+    let db = &boot.app_context.db;
+
+    for url in [
+        "ftp://example.com/set.mp3",
+        "https://example.com/set.html",
+        "not a url",
+    ] {
+        assert!(Model::create(db, SET_ID, url).await.is_err(), "{url}");
+    }
+
+    let youtube = Model::create(
+        db,
+        SET_ID,
+        "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=5",
+    )
+    .await?;
+    assert_eq!(youtube.provider, "you_tube");
+    assert_eq!(
+        youtube.deep_link(754).as_deref(),
+        Some("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=754")
+    );
+    assert_eq!(
+        youtube.embed_url().as_deref(),
+        Some("https://www.youtube-nocookie.com/embed/dQw4w9WgXcQ")
+    );
+    assert!(Model::create(
+        db,
+        SET_ID,
+        "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=5"
+    )
+    .await
+    .is_err());
+
+    let soundcloud = Model::create(db, SET_ID, "https://soundcloud.com/jeffmills/live").await?;
+    assert_eq!(
+        soundcloud.deep_link(3723).as_deref(),
+        Some("https://soundcloud.com/jeffmills/live#t=1:02:03")
+    );
+    let mixcloud = Model::create(db, SET_ID, "https://www.mixcloud.com/jeffmills/live/").await?;
+    assert_eq!(mixcloud.deep_link(60), None);
+    let file = Model::create(db, SET_ID, "https://example.com/sets/live.MP3").await?;
+    assert_eq!(file.provider, "file");
+    assert_eq!(
+        file.deep_link(60).as_deref(),
+        Some("https://example.com/sets/live.MP3#t=60")
+    );
+
+    assert_eq!(Model::list_by_set_id(db, SET_ID).await?.len(), 4);
+    Model::delete(db, SET_ID, file.id).await?;
+    assert!(Model::delete(db, SET_ID, file.id).await.is_err());
+    assert_eq!(Model::list_by_set_id(db, SET_ID).await?.len(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn formats_offsets() {
+    assert_eq!(format_offset(5), "0:05");
+    assert_eq!(format_offset(754), "12:34");
+    assert_eq!(format_offset(3723), "1:02:03");
+}
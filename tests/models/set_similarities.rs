@@ -0,0 +1,80 @@
+use loco_rs::testing;
+use setlist_list::{
+    app::App,
+    models::{
+        set_similarities::{Candidate, Model},
+        sets,
+    },
+};
+use sqlx::PgPool;
+use uuid::{uuid, Uuid};
+
+const SET_ID: Uuid = uuid!("33333333-3333-3333-3333-333333333333");
+const CREATOR_ID: Uuid = uuid!("11111111-1111-1111-1111-111111111111");
+
+/// A candidate sharing `shared_tracks` of the one track both sets played, and
+/// `shared_djs` of the two DJs both sets have.
+fn candidate(set_id: Uuid, shared_tracks: i64, shared_djs: i64) -> Candidate {
+    Candidate {
+        set_id,
+        shared_tracks,
+        tracks: 1,
+        own_tracks: 1,
+        shared_djs,
+        djs: 2,
+        own_djs: 2,
+        shared_tags: 0,
+        tags: 0,
+        own_tags: 0,
+    }
+}
+
+async fn similar_set_ids(db: &PgPool, set_id: Uuid) -> eyre::Result<Vec<Uuid>> {
+    Ok(Model::list_by_set_id(db, set_id, 50)
+        .await?
+        .into_iter()
+        .map(|similarity| similarity.similar_set_id)
+        .collect())
+}
+
+#[sqlx::test(fixtures("users", "sets"))]
+async fn keeps_the_similarities_of_other_sets(pool: PgPool) -> eyre::Result<()> {
+    let boot = testing::boot_test::<App>(pool).await?;
+    let db = &boot.app_context.db;
+
+    let mut ids = vec![];
+    for _ in 0..21 {
+        let item = sets::Model {
+            id: Uuid::now_v7(),
+            creator_id: CREATOR_ID,
+            ..Default::default()
+        };
+        item.insert(&mut *db.acquire().await?).await?;
+        ids.push(item.id);
+    }
+    let (like, closer) = (ids[0], &ids[1..]);
+
+    Model::replace_for_set(db, like, &[candidate(SET_ID, 0, 1)]).await?;
+    assert_eq!(similar_set_ids(db, like).await?, vec![SET_ID]);
+    assert_eq!(similar_set_ids(db, SET_ID).await?, vec![like]);
+
+    // the set has closer ones to keep, but is still among those of `like`
+    let mut candidates = closer
+        .iter()
+        .map(|id| candidate(*id, 1, 0))
+        .collect::<Vec<_>>();
+    candidates.push(candidate(like, 0, 1));
+    Model::replace_for_set(db, SET_ID, &candidates).await?;
+    assert_eq!(similar_set_ids(db, SET_ID).await?.len(), 20);
+    assert!(!similar_set_ids(db, SET_ID).await?.contains(&like));
+    assert_eq!(similar_set_ids(db, like).await?, vec![SET_ID]);
+    assert_eq!(similar_set_ids(db, closer[0]).await?, vec![SET_ID]);
+
+    // until they have nothing in common anymore
+    candidates.pop();
+    Model::replace_for_set(db, SET_ID, &candidates).await?;
+    assert!(similar_set_ids(db, like).await?.is_empty());
+    assert_eq!(similar_set_ids(db, closer[0]).await?, vec![SET_ID]);
+
+    Ok(())
+}
@@ -0,0 +1,376 @@
+use axum::http::StatusCode;
+use insta::assert_debug_snapshot;
+use loco_rs::testing;
+use serde_json::{json, Value};
+use setlist_list::{
+    app::App,
+    models::api_keys::{self, ApiScope},
+};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+const TRACKS: [(&str, &str); 2] = [
+    ("Jeff Mills", "The Bells"),
+    ("Rhythim Is Rhythim", "Strings of Life"),
+];
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        settings.set_snapshot_suffix("api_request");
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[sqlx::test]
+async fn rejects_missing_api_key(pool: PgPool) -> eyre::Result<()> {
+    configure_insta!();
+
+    testing::request::<App, _, _>(pool, |request, _ctx| async move {
+        let response = request.get("/api/v1/sets").await;
+        assert_debug_snapshot!((response.status_code(), response.text()));
+
+        let response = request
+            .get("/api/v1/sets")
+            .authorization_bearer("not-a-key")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn can_create_and_fetch_set(pool: PgPool) -> eyre::Result<()> {
+    configure_insta!();
+
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&prepare_data::set_payload().tracks(&TRACKS))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let created = response.json::<Value>();
+        assert_eq!(created["creator_id"], json!(user.user.id));
+        assert_eq!(created["tracklist"][1]["track_number"], json!(2));
+
+        let id = created["id"].as_str().unwrap_or_default().to_string();
+        let response = request
+            .get(&format!("/api/v1/sets/{id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.json::<Value>(), created);
+
+        let response = request
+            .put(&format!("/api/v1/sets/{id}/tracklist"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!([{"track_title": "Jaguar", "track_artist": "DJ Rolando"}]))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(
+            response.json::<Value>(),
+            json!([{
+                "track_title": "Jaguar",
+                "track_artist": "DJ Rolando",
+                "track_number": 1,
+                "track_start_time_offset_seconds": null,
+                "track_duration_seconds": null,
+                "track_bpm": null,
+                "track_key": null,
+                "track_label": null,
+                "track_release_year": null,
+                "track_remixer": null
+            }])
+        );
+
+        let response = request
+            .get(&format!("/api/v1/users/{username}/sets"))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(response.json::<Value>()["data"][0]["id"], json!(id));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn can_paginate_sets_and_djs(pool: PgPool) -> eyre::Result<()> {
+    configure_insta!();
+
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+
+        for dj_name in ["Jeff Mills", "Jeff Mills", "DJ Stingray"] {
+            request
+                .post("/api/v1/sets")
+                .add_header(auth_key.clone(), auth_value.clone())
+                .json(&prepare_data::set_payload().dj(dj_name).tracks(&TRACKS))
+                .await;
+        }
+
+        let response = request
+            .get("/api/v1/sets?page=2&per_page=2")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let page = response.json::<Value>();
+        assert_eq!(page["data"].as_array().map(Vec::len), Some(1));
+        assert_debug_snapshot!(page["pagination"]);
+
+        let response = request
+            .get("/api/v1/djs")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(
+            response.json::<Value>()["data"],
+            json!([
+                {"name": "DJ Stingray", "set_count": 1},
+                {"name": "Jeff Mills", "set_count": 2}
+            ])
+        );
+
+        let response = request
+            .get("/api/v1/djs/Jeff%20Mills/sets")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.json::<Value>()["pagination"]["total_items"], 2);
+
+        let response = request
+            .get(&format!("/api/v1/sets?page={}&per_page=100", i64::MAX))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), 200);
+        assert_eq!(response.json::<Value>()["data"], json!([]));
+
+        let response = request
+            .get("/api/v1/sets?page=0")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_debug_snapshot!((response.status_code(), response.text()));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn cannot_modify_other_users_set(pool: PgPool) -> eyre::Result<()> {
+    configure_insta!();
+
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let owner = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (owner_key, owner_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &owner.user, &[ApiScope::WriteSets]).await?,
+        )?;
+
+        let (username, email) = get_random_user_email();
+        let other = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (other_key, other_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &other.user, &[ApiScope::WriteSets]).await?,
+        )?;
+
+        let created = request
+            .post("/api/v1/sets")
+            .add_header(owner_key.clone(), owner_value.clone())
+            .json(&prepare_data::set_payload().tracks(&TRACKS))
+            .await
+            .json::<Value>();
+        let id = created["id"].as_str().unwrap_or_default().to_string();
+
+        let response = request
+            .delete(&format!("/api/v1/sets/{id}"))
+            .add_header(other_key, other_value)
+            .await;
+        assert_debug_snapshot!((response.status_code(), response.text()));
+
+        let response = request
+            .delete(&format!("/api/v1/sets/{id}"))
+            .add_header(owner_key.clone(), owner_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request
+            .get(&format!("/api/v1/sets/{id}"))
+            .add_header(owner_key, owner_value)
+            .await;
+        assert_debug_snapshot!((response.status_code(), response.text()));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn rejects_key_without_scope(pool: PgPool) -> eyre::Result<()> {
+    configure_insta!();
+
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::Read]).await?,
+        )?;
+
+        let response = request
+            .get("/api/v1/sets")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(auth_key, auth_value)
+            .json(&prepare_data::set_payload().tracks(&TRACKS))
+            .await;
+        assert_debug_snapshot!((response.status_code(), response.text()));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn rejects_rotated_and_revoked_keys(pool: PgPool) -> eyre::Result<()> {
+    configure_insta!();
+
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (item, key) =
+            api_keys::Model::create(&ctx.db, user.user.id, "test", &[ApiScope::Read], None).await?;
+
+        let (rotated, rotated_key) = item.rotate(&ctx.db).await?;
+        assert_eq!(rotated.scopes, item.scopes);
+
+        let response = request.get("/api/v1/sets").authorization_bearer(&key).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = request
+            .get("/api/v1/sets")
+            .authorization_bearer(&rotated_key)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        rotated.revoke(&ctx.db).await?;
+        let response = request
+            .get("/api/v1/sets")
+            .authorization_bearer(&rotated_key)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn admin_keys_manage_keys_and_webhooks(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let write_key =
+            prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        let admin_key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::Admin]).await?;
+
+        // writing sets is not enough
+        for url in ["/api/v1/keys", "/api/v1/webhooks"] {
+            let response = request.get(url).authorization_bearer(&write_key).await;
+            assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        }
+
+        let response = request
+            .post("/api/v1/keys")
+            .authorization_bearer(&admin_key)
+            .json(&json!({"name": "ci", "scopes": ["read"], "expires_in_days": 100_000_000}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let response = request
+            .post("/api/v1/keys")
+            .authorization_bearer(&admin_key)
+            .json(&json!({"name": "ci", "scopes": ["read"], "expires_in_days": 30}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let created = response.json::<Value>();
+        let key = created["key"].as_str().unwrap_or_default().to_string();
+        assert_eq!(created["scopes"], json!(["read"]));
+        assert!(created.get("key_hash").is_none());
+        let response = request.get("/api/v1/sets").authorization_bearer(&key).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request
+            .get("/api/v1/keys")
+            .authorization_bearer(&admin_key)
+            .await;
+        assert_eq!(response.json::<Value>().as_array().map(Vec::len), Some(3));
+
+        let response = request
+            .post(&format!(
+                "/api/v1/keys/{}/rotate",
+                created["id"].as_str().unwrap_or_default()
+            ))
+            .authorization_bearer(&admin_key)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let rotated = response.json::<Value>();
+        let response = request.get("/api/v1/sets").authorization_bearer(&key).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = request
+            .delete(&format!(
+                "/api/v1/keys/{}",
+                rotated["id"].as_str().unwrap_or_default()
+            ))
+            .authorization_bearer(&admin_key)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = request
+            .get("/api/v1/sets")
+            .authorization_bearer(rotated["key"].as_str().unwrap_or_default())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        let response = request
+            .post("/api/v1/webhooks")
+            .authorization_bearer(&admin_key)
+            .json(&json!({"url": "http://localhost:9/hook", "events": ["set.created"]}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let webhook = response.json::<Value>();
+        assert!(webhook["secret"]
+            .as_str()
+            .is_some_and(|secret| secret.starts_with("whsec_")));
+        let response = request
+            .get("/api/v1/webhooks")
+            .authorization_bearer(&admin_key)
+            .await;
+        let listed = response.json::<Value>();
+        assert_eq!(listed[0]["events"], json!(["set.created"]));
+        assert!(listed[0].get("secret").is_none());
+        let response = request
+            .delete(&format!(
+                "/api/v1/webhooks/{}",
+                webhook["id"].as_str().unwrap_or_default()
+            ))
+            .authorization_bearer(&admin_key)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        Ok(())
+    })
+    .await
+}
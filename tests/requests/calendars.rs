@@ -0,0 +1,210 @@
+use axum::http::{header, StatusCode};
+use loco_rs::testing;
+use setlist_list::{app::App, models::users};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+/// Unfolds the content lines of an iCalendar body.
+fn unfold(body: &str) -> String {
+    body.replace("\r\n ", "")
+}
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/upcoming_sets.sql"
+))]
+async fn can_get_upcoming_sets_calendar(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, _ctx| async move {
+        let response = request.get("/sets.ics").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(
+            response.header(header::CONTENT_TYPE),
+            "text/calendar; charset=utf-8"
+        );
+
+        let body = response.text();
+        assert!(body.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(body.ends_with("END:VCALENDAR\r\n"));
+        assert!(body.split("\r\n").all(|line| line.len() <= 75));
+        assert_eq!(body.matches("BEGIN:VEVENT").count(), 2);
+        // sets that already happened are left out
+        assert!(!body.contains("33333333-3333-3333-3333-333333333333"));
+
+        let body = unfold(&body);
+        for line in [
+            "UID:44444444-4444-4444-4444-444444444444@setlistlist",
+            "DTSTART:21000101T230000",
+            "DTEND:21000102T010000",
+            "SUMMARY:Jeff Mills at Tresor",
+            "LOCATION:Tresor\\, Berlin",
+            "DESCRIPTION:Tresor 100\\, the night that goes on and on\\; with a description long enough to fold",
+            "URL:http://localhost:5150/sets/44444444-4444-4444-4444-444444444444",
+            "UID:55555555-5555-5555-5555-555555555555@setlistlist",
+        ] {
+            assert!(body.contains(&format!("{line}\r\n")), "{line}");
+        }
+        // without a duration there is no end
+        assert_eq!(body.matches("DTEND").count(), 1);
+
+        let etag = response.header(header::ETAG);
+        let response = request
+            .get("/sets.ics")
+            .add_header(header::IF_NONE_MATCH, etag)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_MODIFIED);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql"))]
+async fn escapes_line_breaks_in_calendar_text(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        sqlx::query(
+            r"INSERT INTO sets (id, creator_id, dj_names, venue, event_name, start_time)
+            VALUES ('66666666-6666-6666-6666-666666666666',
+                '11111111-1111-1111-1111-111111111111', ARRAY ['Jeff Mills'],
+                E'Tresor\rSTATUS:CANCELLED', E'Night\r\nATTACH:http://example.com',
+                '2100-01-01T23:00')",
+        )
+        .execute(&ctx.db)
+        .await?;
+
+        let body = request.get("/sets.ics").await.text();
+        let lines = body.split("\r\n").collect::<Vec<_>>();
+        assert!(lines.iter().all(|line| !line.contains(['\r', '\n'])));
+        assert!(!lines
+            .iter()
+            .any(|line| line.starts_with("STATUS:") || line.starts_with("ATTACH:")));
+        let body = unfold(&body);
+        assert!(body.contains("LOCATION:Tresor\\nSTATUS:CANCELLED\r\n"));
+        assert!(body.contains("DESCRIPTION:Night\\nATTACH:http://example.com\r\n"));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/upcoming_sets.sql"
+))]
+async fn can_get_dj_and_venue_calendars(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, _ctx| async move {
+        for (path, name, id) in [
+            (
+                "/djs/jeff-mills.ics",
+                "Upcoming sets by Jeff Mills",
+                "44444444-4444-4444-4444-444444444444",
+            ),
+            (
+                "/venues/marble-bar.ics",
+                "Upcoming sets at Marble Bar",
+                "55555555-5555-5555-5555-555555555555",
+            ),
+        ] {
+            let response = request.get(path).await;
+            assert_eq!(response.status_code(), StatusCode::OK, "{path}");
+            let body = unfold(&response.text());
+            assert!(body.contains(&format!("X-WR-CALNAME:{name}\r\n")));
+            assert_eq!(body.matches("BEGIN:VEVENT").count(), 1);
+            assert!(body.contains(id));
+        }
+
+        // known but without upcoming sets
+        let response = request.get("/djs/frankie-knuckles.ics").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(!response.text().contains("BEGIN:VEVENT"));
+
+        for path in [
+            "/djs/nobody.ics",
+            "/venues/nowhere.ics",
+            "/djs/jeff-mills.ical",
+        ] {
+            let response = request.get(path).await;
+            assert_eq!(response.status_code(), StatusCode::NOT_FOUND, "{path}");
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/upcoming_sets.sql"
+))]
+async fn can_subscribe_to_followed_djs(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        assert!(user.user.calendar_token.is_none());
+
+        let response = request
+            .post("/djs/jeff-mills/follow")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("Following"));
+
+        let response = request
+            .post("/djs/nobody/follow")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        let response = request
+            .post("/settings/calendar/token")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let token = users::Model::find_by_id(&ctx.db, user.user.id)
+            .await?
+            .calendar_token
+            .expect("calendar token");
+        assert!(response.text().contains(&token.to_string()));
+
+        // the calendar app has no login, the token is enough
+        let path = format!("/calendar/{token}.ics");
+        let response = request.get(&path).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let body = response.text();
+        assert!(body.contains("44444444-4444-4444-4444-444444444444"));
+        assert!(!body.contains("55555555-5555-5555-5555-555555555555"));
+
+        let response = request
+            .get("/settings/calendar")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("Jeff Mills"));
+
+        let response = request
+            .delete("/settings/calendar/djs/jeff-mills")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(!request.get(&path).await.text().contains("BEGIN:VEVENT"));
+
+        // resetting the token retires the old address
+        request
+            .post("/settings/calendar/token")
+            .add_header(auth_key, auth_value)
+            .await;
+        let response = request.get(&path).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        let response = request.get("/calendar/not-a-token.ics").await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,131 @@
+use axum::http::StatusCode;
+use loco_rs::testing;
+use serde_json::json;
+use setlist_list::{
+    app::App,
+    models::{collections, sets},
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+const SET: &str = "33333333-3333-3333-3333-333333333333";
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql", "../models/fixtures/sets.sql"))]
+async fn can_curate_collections(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let (other_username, email) = get_random_user_email();
+        let other = prepare_data::init_user_login(&request, &ctx, &other_username, &email).await?;
+        let (other_key, other_value) = prepare_data::auth_header(&other._token)?;
+
+        let response = request
+            .post("/collections")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"name": " "}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let response = request
+            .post("/collections")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"name": "Best of Berlin 2024", "description": "Warehouse nights"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let path = response.header("HX-Redirect").to_str()?.to_string();
+        let id = Uuid::parse_str(path.trim_start_matches("/collections/"))?;
+
+        let second = prepare_data::add_set(
+            &ctx,
+            sets::Model {
+                creator_id: other.user.id,
+                dj_names: vec!["DJ Rolando".to_string()],
+                ..Default::default()
+            },
+            &[],
+        )
+        .await?;
+        for set_id in [SET.to_string(), second.id.to_string()] {
+            let response = request
+                .post(&format!("/sets/{set_id}/collections"))
+                .add_header(auth_key.clone(), auth_value.clone())
+                .json(&json!({"collection_id": id}))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+            assert!(response.text().contains("Added to"));
+        }
+        // others cannot add to, or even see, a private collection
+        let response = request
+            .post(&format!("/sets/{SET}/collections"))
+            .add_header(other_key.clone(), other_value.clone())
+            .json(&json!({"collection_id": id}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        let response = request
+            .get(&path)
+            .add_header(other_key.clone(), other_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        let response = request.get(&format!("{path}/sets.atom")).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        let response = request
+            .post(&format!("{path}/sets/{}/move", second.id))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"offset": -1}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let collection = collections::Model::find_by_id(&ctx.db, id).await?;
+        let ids = collection
+            .list_sets(&ctx.db)
+            .await?
+            .into_iter()
+            .map(|set| set.id.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, [second.id.to_string(), SET.to_string()]);
+
+        let response = request
+            .put(&path)
+            .add_header(other_key.clone(), other_value.clone())
+            .json(&json!({"name": "Mine now", "visibility": "public"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        let response = request
+            .put(&path)
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"name": "Best of Berlin 2024", "visibility": "public"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request.get(&path).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("Best of Berlin 2024"));
+        let response = request.get(&format!("{path}/sets.atom")).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("DJ Rolando"));
+        let response = request
+            .get(&format!("/user/{username}?tab=collections"))
+            .await;
+        assert!(response.text().contains(&path));
+
+        let response = request
+            .delete(&format!("{path}/sets/{SET}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(!response.text().contains(SET));
+        let response = request
+            .delete(&path)
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = request.get(&path).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,141 @@
+use axum::http::StatusCode;
+use loco_rs::testing;
+use serde_json::json;
+use setlist_list::{
+    app::App,
+    models::{comments, notifications},
+};
+use sqlx::PgPool;
+use uuid::uuid;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+const SET: &str = "33333333-3333-3333-3333-333333333333";
+
+fn comment_ids(text: &str) -> Vec<String> {
+    text.split(r#"id="comment-"#)
+        .skip(1)
+        .filter_map(|rest| rest.split('"').next())
+        .filter(|id| !id.ends_with("-body"))
+        .map(str::to_string)
+        .collect()
+}
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn can_discuss_sets_and_tracks(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let author = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (author_key, author_value) = prepare_data::auth_header(&author._token)?;
+        let (username, email) = get_random_user_email();
+        let replier = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (replier_key, replier_value) = prepare_data::auth_header(&replier._token)?;
+
+        for (body, track_number) in [("", ""), ("Great track", "7")] {
+            let response = request
+                .post(&format!("/sets/{SET}/comments"))
+                .add_header(author_key.clone(), author_value.clone())
+                .json(&json!({"body": body, "track_number": track_number}))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::BAD_REQUEST, "{body}");
+        }
+
+        let response = request
+            .post(&format!("/sets/{SET}/comments"))
+            .add_header(author_key.clone(), author_value.clone())
+            .json(&json!({
+                "body": "What was **that** <script>alert(1)</script>\n\n\
+                    [full mix](https://example.com/mix?a=1&b=2) [x](javascript:alert(1))",
+                "track_number": "2",
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let text = response.text();
+        assert!(text.contains("<strong>that</strong>"));
+        assert!(text.contains("&lt;script&gt;"));
+        assert!(!text.contains("<script>alert"));
+        assert!(text.contains(r#"<a href="https://example.com/mix?a=1&amp;b=2" rel="nofollow ugc noopener">full mix</a>"#));
+        assert!(!text.contains("href=\"javascript"));
+        assert!(text.contains("Your Love"));
+        let comment_id = comment_ids(&text).remove(0);
+
+        let response = request
+            .post(&format!("/sets/{SET}/comments"))
+            .add_header(replier_key.clone(), replier_value.clone())
+            .json(&json!({"body": "Sounds like an edit", "parent_id": comment_id}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let reply_id = comment_ids(&response.text()).remove(1);
+
+        // replies to replies join the same thread
+        let response = request
+            .post(&format!("/sets/{SET}/comments"))
+            .add_header(author_key.clone(), author_value.clone())
+            .json(&json!({"body": "Maybe", "parent_id": reply_id}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let items = comments::Model::list_by_set_id(&ctx.db, SET.parse()?).await?;
+        assert_eq!(items.len(), 3);
+        assert!(items[1..]
+            .iter()
+            .all(|item| item.parent_id.map(|id| id.to_string()) == Some(comment_id.clone())
+                && item.track_number == Some(2)));
+
+        let owner_id = uuid!("11111111-1111-1111-1111-111111111111");
+        assert_eq!(notifications::Model::count_unread(&ctx.db, owner_id).await?, 1);
+        assert_eq!(
+            notifications::Model::count_unread(&ctx.db, author.user.id).await?,
+            1
+        );
+
+        let path = format!("/sets/{SET}/comments/{comment_id}");
+        let response = request
+            .put(&path)
+            .add_header(replier_key.clone(), replier_value.clone())
+            .json(&json!({"body": "Hijacked"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        let response = request
+            .delete(&path)
+            .add_header(replier_key.clone(), replier_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+        let response = request
+            .get(&format!("{path}/edit"))
+            .add_header(author_key.clone(), author_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("What was **that**"));
+
+        let response = request
+            .put(&path)
+            .add_header(author_key.clone(), author_value.clone())
+            .json(&json!({"body": "What was *that*?"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let text = response.text();
+        assert!(text.contains("<em>that</em>?"));
+        assert!(text.contains("edited"));
+
+        let response = request.get(&format!("/sets/{SET}")).await;
+        assert!(response.text().contains("Sounds like an edit"));
+
+        let response = request
+            .delete(&path)
+            .add_header(author_key.clone(), author_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let text = response.text();
+        assert!(text.contains("This comment was deleted."));
+        assert!(text.contains("Sounds like an edit"));
+
+        Ok(())
+    })
+    .await
+}
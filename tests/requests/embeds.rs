@@ -0,0 +1,79 @@
+use axum::http::{header, StatusCode};
+use loco_rs::testing;
+use serde_json::Value;
+use setlist_list::app::App;
+use sqlx::PgPool;
+
+const SET_URL: &str = "http://localhost:5150/sets/33333333-3333-3333-3333-333333333333";
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn can_embed_tracklists(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, _ctx| async move {
+        let response = request
+            .get("/embed/sets/33333333-3333-3333-3333-333333333333")
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response
+            .header(header::CONTENT_SECURITY_POLICY)
+            .to_str()?
+            .contains("frame-ancestors *"));
+        assert!(response.maybe_header(header::X_FRAME_OPTIONS).is_none());
+        let widget = response.text();
+        assert!(widget.contains("Jeff Mills – The Bells"));
+        assert!(widget.contains("Frankie Knuckles &amp; Jamie Principle – Your Love"));
+        assert!(!widget.contains("<script"));
+
+        let page = request
+            .get("/sets/33333333-3333-3333-3333-333333333333")
+            .await
+            .text();
+        assert!(page.contains("application/json+oembed"));
+        assert!(page.contains("/oembed?url=http%3A"));
+
+        let response = request.get(&format!("/oembed?url={SET_URL}")).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let oembed = response.json::<Value>();
+        assert_eq!(oembed["version"], "1.0");
+        assert_eq!(oembed["type"], "rich");
+        assert_eq!(
+            oembed["title"],
+            "Jeff Mills B2B Frankie Knuckles at Marble Bar"
+        );
+        assert_eq!(oembed["width"], 480);
+        // two tracks
+        assert_eq!(oembed["height"], 152);
+        assert!(oembed["html"].as_str().unwrap_or_default().starts_with(
+            "<iframe src=\"http://localhost:5150/embed/sets/33333333-3333-3333-3333-333333333333\""
+        ));
+        assert_eq!(oembed["thumbnail_width"], 1200);
+
+        let response = request
+            .get(&format!("/oembed?url={SET_URL}&maxwidth=300&maxheight=100"))
+            .await;
+        let oembed = response.json::<Value>();
+        assert_eq!(
+            (oembed["width"].as_u64(), oembed["height"].as_u64()),
+            (Some(300), Some(100))
+        );
+
+        let response = request
+            .get(&format!("/oembed?url={SET_URL}&format=xml"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_IMPLEMENTED);
+        for url in [
+            "http://example.com/sets/33333333-3333-3333-3333-333333333333",
+            "http://localhost:5150/sets/44444444-4444-4444-4444-444444444444",
+            "http://localhost:5150/tags/techno",
+        ] {
+            let response = request.get(&format!("/oembed?url={url}")).await;
+            assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        }
+
+        Ok(())
+    })
+    .await
+}
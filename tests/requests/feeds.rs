@@ -0,0 +1,230 @@
+use axum::http::{header, HeaderValue, StatusCode};
+use insta::assert_snapshot;
+use loco_rs::testing;
+use serde_json::{json, Value};
+use setlist_list::{app::App, models::api_keys::ApiScope, utils::slugify};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        settings.set_snapshot_suffix("feeds_request");
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[test]
+fn can_slugify_names() {
+    assert_eq!(slugify("Jeff Mills"), "jeff-mills");
+    assert_eq!(slugify("  Tresor / Globus "), "tresor-globus");
+    assert_eq!(slugify("DJ Stingray 313"), "dj-stingray-313");
+}
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn can_get_sets_feed(pool: PgPool) -> eyre::Result<()> {
+    configure_insta!();
+
+    testing::request::<App, _, _>(pool, |request, _ctx| async move {
+        let response = request.get("/sets.atom").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(
+            response.header(header::CONTENT_TYPE),
+            "application/atom+xml; charset=utf-8"
+        );
+        assert_eq!(
+            response.header(header::LAST_MODIFIED),
+            "Thu, 02 Jun 2022 11:20:00 GMT"
+        );
+        assert_snapshot!(response.text());
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn can_get_user_dj_venue_and_event_feeds(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, _ctx| async move {
+        for (path, title) in [
+            ("/user/user1/sets.atom", "Sets added by user1"),
+            ("/djs/frankie-knuckles.atom", "Sets by Frankie Knuckles"),
+            ("/venues/marble-bar.atom", "Sets at Marble Bar"),
+            (
+                "/events/movement-festival.atom",
+                "Sets at Movement Festival",
+            ),
+        ] {
+            let response = request.get(path).await;
+            assert_eq!(response.status_code(), StatusCode::OK, "{path}");
+            assert!(response.text().contains(&format!("<title>{title}</title>")));
+            assert!(response.text().contains("<li>Jeff Mills – The Bells</li>"));
+        }
+
+        for path in [
+            "/user/nobody/sets.atom",
+            "/djs/nobody.atom",
+            "/events/nowhere.atom",
+        ] {
+            let response = request.get(path).await;
+            assert_eq!(response.status_code(), StatusCode::NOT_FOUND, "{path}");
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql", "../models/fixtures/sets.sql"))]
+async fn can_get_dj_venue_and_event_pages(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        for (path, title) in [
+            ("/djs/jeff-mills", "Sets by Jeff Mills"),
+            ("/venues/marble-bar", "Sets at Marble Bar"),
+            ("/events/movement-festival", "Sets at Movement Festival"),
+        ] {
+            let response = request.get(path).await;
+            assert_eq!(response.status_code(), StatusCode::OK, "{path}");
+            let page = response.text().replace("&#x2f;", "/");
+            assert!(page.contains(&format!("<h1>{title}</h1>")));
+            assert!(page.contains(&format!("href=\"{path}.atom\"")));
+            assert!(page.contains("/sets/33333333-3333-3333-3333-333333333333"));
+            assert!(!page.contains("title=\"Follow"));
+        }
+
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        request
+            .post("/djs/jeff-mills/follow")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let page = request
+            .get("/djs/jeff-mills")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await
+            .text();
+        assert!(page.contains("Following"));
+        let page = request
+            .get("/venues/marble-bar")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await
+            .text();
+        assert!(page.contains("Follow Marble Bar"));
+        let page = request
+            .get("/events/movement-festival")
+            .add_header(auth_key, auth_value)
+            .await
+            .text();
+        assert!(!page.contains("title=\"Follow"));
+
+        for path in ["/djs/nobody", "/venues/nowhere", "/events/nothing"] {
+            let response = request.get(path).await;
+            assert_eq!(response.status_code(), StatusCode::NOT_FOUND, "{path}");
+        }
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn supports_conditional_get(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, _ctx| async move {
+        let response = request.get("/sets.atom").await;
+        let etag = response.header(header::ETAG);
+        let last_modified = response.header(header::LAST_MODIFIED);
+
+        let response = request
+            .get("/sets.atom")
+            .add_header(header::IF_NONE_MATCH, etag.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_MODIFIED);
+        assert!(response.text().is_empty());
+
+        let response = request
+            .get("/sets.atom")
+            .add_header(header::IF_MODIFIED_SINCE, last_modified)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_MODIFIED);
+
+        // If-None-Match wins over If-Modified-Since
+        let response = request
+            .get("/sets.atom")
+            .add_header(header::IF_NONE_MATCH, HeaderValue::from_static("\"stale\""))
+            .add_header(
+                header::IF_MODIFIED_SINCE,
+                HeaderValue::from_static("Fri, 01 Jan 2100 00:00:00 GMT"),
+            )
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request
+            .get("/sets.atom")
+            .add_header(
+                header::IF_MODIFIED_SINCE,
+                HeaderValue::from_static("Wed, 01 Jun 2022 00:00:00 GMT"),
+            )
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request
+            .get("/user/user1/sets.atom")
+            .add_header(header::IF_NONE_MATCH, etag)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn replacing_tracklist_changes_feed(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        let created = request
+            .post("/api/v1/sets")
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().tracks(&[("ID", "ID")]))
+            .await
+            .json::<Value>();
+        let id = created["id"].as_str().unwrap_or_default().to_string();
+
+        let path = format!("/user/{username}/sets.atom");
+        let etag = request.get(&path).await.header(header::ETAG);
+        request
+            .put(&format!("/api/v1/sets/{id}/tracklist"))
+            .authorization_bearer(&key)
+            .json(&json!([{"track_artist": "Jeff Mills", "track_title": "The Bells"}]))
+            .await;
+
+        let response = request
+            .get(&path)
+            .add_header(header::IF_NONE_MATCH, etag.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_ne!(response.header(header::ETAG), etag);
+        assert!(response.text().contains("Jeff Mills – The Bells"));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,173 @@
+use std::io::Cursor;
+
+use axum::http::{header, StatusCode};
+use axum_test::multipart::{MultipartForm, Part};
+use image::{error::ImageError, ImageBuffer, ImageFormat, Rgb, Rgba};
+use loco_rs::testing;
+use serde_json::Value;
+use setlist_list::{
+    app::App,
+    models::{api_keys::ApiScope, uploads},
+    workers::images,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+/// A 1300x200 gradient, wider than a cover.
+fn png_flyer() -> Vec<u8> {
+    #[allow(clippy::cast_possible_truncation)]
+    let image = ImageBuffer::from_fn(1300, 200, |x, y| Rgb([(x % 256) as u8, y as u8, 128]));
+    let mut content = Cursor::new(vec![]);
+    image
+        .write_to(&mut content, ImageFormat::Png)
+        .unwrap_or_else(|err| panic!("could not encode the flyer: {err}"));
+    content.into_inner()
+}
+
+#[test]
+fn refuses_to_decode_huge_images() -> eyre::Result<()> {
+    // a few hundred kilobytes of PNG which would decode to about 70 MB
+    let image = ImageBuffer::from_pixel(4200, 4200, Rgba([0u8, 0, 0, 255]));
+    let mut content = Cursor::new(vec![]);
+    image.write_to(&mut content, ImageFormat::Png)?;
+    assert!(matches!(
+        images::resize(content.get_ref()),
+        Err(ImageError::Limits(_))
+    ));
+    Ok(())
+}
+
+fn image_file(bytes: Vec<u8>, mime_type: &str) -> MultipartForm {
+    MultipartForm::new().add_part(
+        "file",
+        Part::bytes(bytes)
+            .file_name("flyer.png")
+            .mime_type(mime_type),
+    )
+}
+
+#[sqlx::test]
+async fn can_upload_flyers(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let (other_username, email) = get_random_user_email();
+        let other = prepare_data::init_user_login(&request, &ctx, &other_username, &email).await?;
+        let (other_key, other_value) = prepare_data::auth_header(&other._token)?;
+        let (api_key, api_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key, api_value)
+            .json(&prepare_data::set_payload().dj("DJ Rolando"))
+            .await;
+        let id = Uuid::parse_str(response.json::<Value>()["id"].as_str().unwrap_or_default())?;
+
+        let response = request
+            .post(&format!("/sets/{id}/flyer"))
+            .add_header(other_key, other_value)
+            .multipart(image_file(png_flyer(), "image/png"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        // told apart by content, not by the claimed type
+        let response = request
+            .post(&format!("/sets/{id}/flyer"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .multipart(image_file(b"<svg></svg>".to_vec(), "image/png"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .post(&format!("/sets/{id}/flyer"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .multipart(image_file(png_flyer(), "application/octet-stream"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        // the worker runs in the foreground in tests
+        let upload = uploads::Model::list_by_set_id(&ctx.db, id).await?.remove(0);
+        assert_eq!(upload.content_type, "image/png");
+        let mut variants = upload.variants.clone();
+        variants.sort();
+        assert_eq!(
+            variants,
+            ["cover.jpg", "cover.webp", "thumb.jpg", "thumb.webp"]
+        );
+
+        let response = request
+            .get(&format!("/uploads/{}/cover.jpg", upload.id))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header(header::CONTENT_TYPE), "image/jpeg");
+        assert_eq!(
+            response.header(header::CACHE_CONTROL),
+            "public, max-age=31536000, immutable"
+        );
+        let cover = image::load_from_memory(response.as_bytes())?;
+        assert_eq!((cover.width(), cover.height()), (1200, 185));
+        let response = request
+            .get(&format!("/uploads/{}/thumb.jpg", upload.id))
+            .await;
+        assert_eq!(response.header(header::CONTENT_TYPE), "image/jpeg");
+        let thumb = image::load_from_memory(response.as_bytes())?;
+        assert_eq!(thumb.width(), 400);
+        let response = request
+            .get(&format!("/uploads/{}/thumb.webp", upload.id))
+            .await;
+        assert_eq!(response.header(header::CONTENT_TYPE), "image/webp");
+        // lossy WebP is much smaller than the JPEG of the same photo
+        let webp = response.as_bytes().to_vec();
+        let thumb = image::load_from_memory(&webp)?;
+        assert_eq!((thumb.width(), thumb.height()), (400, 62));
+        let jpeg = request
+            .get(&format!("/uploads/{}/thumb.jpg", upload.id))
+            .await;
+        assert!(webp.len() < jpeg.as_bytes().len());
+        let response = request
+            .get(&format!("/uploads/{}/peaks.json", upload.id))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        let cover_path = format!("/uploads/{}/cover.jpg", upload.id);
+        let page = request.get(&format!("/sets/{id}")).await.text();
+        assert!(page.contains("property=\"og:image\""));
+        assert!(page.contains(&cover_path));
+        let page = request.get("/sets").await.text();
+        assert!(page.contains(&format!("/uploads/{}/thumb.jpg", upload.id)));
+        assert!(page.contains(&format!("/uploads/{}/thumb.webp", upload.id)));
+        let feed = request.get("/sets.atom").await.text();
+        assert!(feed.contains("rel=\"enclosure\""));
+        assert!(feed.contains(&cover_path));
+
+        // replacing the flyer deletes the previous one
+        let response = request
+            .post(&format!("/sets/{id}/flyer"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .multipart(image_file(png_flyer(), "image/png"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = request.get(&format!("/uploads/{}", upload.id)).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        let response = request
+            .delete(&format!("/sets/{id}/flyer"))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(uploads::Model::list_by_set_id(&ctx.db, id)
+            .await?
+            .is_empty());
+        // previews fall back to the drawn image
+        let page = request.get(&format!("/sets/{id}")).await.text();
+        assert!(!page.contains("cover.jpg"));
+        assert!(page.contains("share.png"));
+
+        Ok(())
+    })
+    .await
+}
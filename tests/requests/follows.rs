@@ -0,0 +1,139 @@
+use axum::http::StatusCode;
+use loco_rs::testing;
+use setlist_list::app::App;
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+const PAST_SET: &str = "33333333-3333-3333-3333-333333333333";
+const TRESOR_SET: &str = "44444444-4444-4444-4444-444444444444";
+const STINGRAY_SET: &str = "55555555-5555-5555-5555-555555555555";
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/upcoming_sets.sql"
+))]
+async fn can_follow_djs_venues_and_users(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+
+        let response = request
+            .get("/feed")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("Follow DJs and venues"));
+
+        for path in [
+            "/djs/dj-stingray-313/follow",
+            "/venues/tresor/follow",
+            "/user/user1/follow",
+        ] {
+            let response = request
+                .post(path)
+                .add_header(auth_key.clone(), auth_value.clone())
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK, "{path}");
+            assert!(response.text().contains("Following"));
+        }
+
+        let response = request
+            .get("/feed")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let text = response.text();
+        let positions = [STINGRAY_SET, TRESOR_SET, PAST_SET].map(|id| text.find(id));
+        // latest start time first
+        assert!(positions.iter().all(Option::is_some), "{positions:?}");
+        assert!(positions.is_sorted());
+        for name in ["DJ Stingray 313", "Tresor", "user1"] {
+            assert!(text.contains(name), "{name}");
+        }
+
+        let response = request
+            .get("/feed?page=2&per_page=1")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let text = response.text();
+        assert!(text.contains(TRESOR_SET));
+        assert!(!text.contains(STINGRAY_SET));
+        assert!(text.contains("Page 2 of 3"));
+
+        let response = request
+            .get("/")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert!(response.text().contains(STINGRAY_SET));
+
+        for path in ["/venues/tresor/follow", "/user/user1/follow"] {
+            let response = request
+                .delete(path)
+                .add_header(auth_key.clone(), auth_value.clone())
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK, "{path}");
+            assert!(!response.text().contains("Following"));
+        }
+        let response = request
+            .get("/feed")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        let text = response.text();
+        assert!(text.contains(STINGRAY_SET));
+        assert!(!text.contains(TRESOR_SET));
+        assert!(!text.contains(PAST_SET));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql", "../models/fixtures/sets.sql"))]
+async fn cannot_follow_unknown_or_self(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+
+        let response = request
+            .post(&format!("/user/{username}/follow"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        for path in [
+            "/user/nobody/follow",
+            "/djs/nobody/follow",
+            "/venues/nowhere/follow",
+        ] {
+            let response = request
+                .post(path)
+                .add_header(auth_key.clone(), auth_value.clone())
+                .await;
+            assert_eq!(response.status_code(), StatusCode::NOT_FOUND, "{path}");
+        }
+
+        // the user page offers to follow other users only
+        let response = request
+            .get(&format!("/user/{username}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert!(!response.text().contains("Follow"));
+        let response = request
+            .get("/user/user1")
+            .add_header(auth_key, auth_value)
+            .await;
+        assert!(response.text().contains("Follow user1"));
+
+        let response = request.get("/feed").await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = request.post("/user/user1/follow").await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        Ok(())
+    })
+    .await
+}
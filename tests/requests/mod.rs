@@ -1,2 +1,25 @@
+mod api;
 mod auth;
+mod calendars;
+mod collections;
+mod comments;
+mod embeds;
+mod feeds;
+mod flyers;
+mod follows;
+mod notifications;
+mod openapi;
 mod prepare_data;
+mod reactions;
+mod recordings;
+mod set_flow;
+mod set_visibility;
+mod settings;
+mod share;
+mod similar_sets;
+mod sitemaps;
+mod stats;
+mod tags;
+mod track_metadata;
+mod transitions;
+mod webhooks;
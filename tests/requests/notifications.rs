@@ -0,0 +1,412 @@
+use axum::http::StatusCode;
+use loco_rs::{prelude::*, testing, worker::Worker as _};
+use serde_json::{json, Value};
+use setlist_list::{
+    app::App,
+    models::{api_keys::ApiScope, email_preferences},
+    workers::digest::{DigestWorker, DigestWorkerArgs},
+};
+use sqlx::PgPool;
+
+use super::prepare_data;
+
+/// Emails sent so far whose raw message contains `needle`.
+fn sent_emails(ctx: &AppContext, needle: &str) -> Vec<String> {
+    ctx.mailer
+        .as_ref()
+        .map(|mailer| mailer.deliveries().messages)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|message| message.contains(needle))
+        .collect()
+}
+
+#[sqlx::test]
+async fn notifies_followers_of_new_sets(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (follower, (jwt_key, jwt_value), _) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+        let (_, _, (api_key, api_value)) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+
+        let payload = prepare_data::set_payload()
+            .dj("DJ Rolando")
+            .tracks(&[("DJ Rolando", "Jaguar")]);
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key.clone(), api_value.clone())
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        assert!(sent_emails(&ctx, "New set:").is_empty());
+
+        let response = request
+            .post("/djs/dj-rolando/follow")
+            .add_header(jwt_key.clone(), jwt_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key.clone(), api_value.clone())
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+
+        let emails = sent_emails(&ctx, "New set: DJ Rolando at Tresor");
+        assert_eq!(emails.len(), 1);
+        assert!(emails[0].contains(&follower.email));
+        let preferences = email_preferences::Model::find_or_create(&ctx.db, follower.id).await?;
+        assert!(emails[0].contains(&format!(
+            "List-Unsubscribe: <http://localhost:5150/unsubscribe/{}?topic=followed_sets>",
+            preferences.unsubscribe_token
+        )));
+        assert!(emails[0].contains("List-Unsubscribe-Post: List-Unsubscribe=One-Click"));
+
+        let response = request
+            .post("/settings/notifications")
+            .add_header(jwt_key.clone(), jwt_value.clone())
+            .json(&json!({"topics": "weekly_digest"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("saved"));
+
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key.clone(), api_value.clone())
+            .json(&payload)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        assert_eq!(sent_emails(&ctx, "New set: DJ Rolando").len(), 1);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn notifies_owner_of_edit_proposals(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (owner, (owner_key, owner_value), (api_key, api_value)) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+        let (proposer, (proposer_key, proposer_value), _) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+
+        let payload = prepare_data::set_payload().tracks(&[("Jeff Mills", "The Bells")]);
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key, api_value)
+            .json(&payload)
+            .await;
+        let id = response.json::<Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let response = request
+            .post(&format!("/sets/{id}/proposals"))
+            .add_header(proposer_key.clone(), proposer_value.clone())
+            .json(&json!({"message": " "}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .post(&format!("/sets/{id}/proposals"))
+            .add_header(owner_key.clone(), owner_value.clone())
+            .json(&json!({"message": "Track 2 is Strings of Life"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .post(&format!("/sets/{id}/proposals"))
+            .add_header(proposer_key.clone(), proposer_value.clone())
+            .json(&json!({"message": "Track 2 is Strings of Life"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("has been told about your edit"));
+
+        let emails = sent_emails(
+            &ctx,
+            &format!(
+                "{} proposed an edit to Jeff Mills at Tresor",
+                proposer.username
+            ),
+        );
+        assert_eq!(emails.len(), 1);
+        assert!(emails[0].contains(&owner.email));
+
+        let response = request
+            .get(&format!("/sets/{id}"))
+            .add_header(owner_key.clone(), owner_value.clone())
+            .await;
+        let text = response.text();
+        assert!(text.contains("Track 2 is Strings of Life"));
+        let proposal_id = text
+            .split(&format!("/sets/{id}/proposals/"))
+            .nth(1)
+            .and_then(|rest| rest.split('/').next())
+            .unwrap_or_default()
+            .to_string();
+
+        let response = request
+            .post(&format!("/sets/{id}/proposals/{proposal_id}/accept"))
+            .add_header(proposer_key, proposer_value)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+
+        let response = request
+            .post(&format!("/sets/{id}/proposals/{proposal_id}/accept"))
+            .add_header(owner_key, owner_value)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("No open proposals."));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn notifies_about_identified_tracks(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (_, _, (api_key, api_value)) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+        let (requester, (jwt_key, jwt_value), _) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+
+        let payload =
+            prepare_data::set_payload().tracks(&[("ID", "ID"), ("Jeff Mills", "The Bells")]);
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key.clone(), api_value.clone())
+            .json(&payload)
+            .await;
+        let id = response.json::<Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let response = request
+            .get(&format!("/sets/{id}"))
+            .add_header(jwt_key.clone(), jwt_value.clone())
+            .await;
+        assert!(response.text().contains("I want this ID"));
+
+        let response = request
+            .post(&format!("/sets/{id}/tracks/2/id_request"))
+            .add_header(jwt_key.clone(), jwt_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .post(&format!("/sets/{id}/tracks/1/id_request"))
+            .add_header(jwt_key.clone(), jwt_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("You will be told"));
+
+        let identified = json!([
+            {"track_title": "Jaguar", "track_artist": "DJ Rolando"},
+            {"track_title": "The Bells", "track_artist": "Jeff Mills"}
+        ]);
+        for _ in 0..2 {
+            let response = request
+                .put(&format!("/api/v1/sets/{id}/tracklist"))
+                .add_header(api_key.clone(), api_value.clone())
+                .json(&identified)
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+        }
+
+        // the request is used up by the first notification
+        let emails = sent_emails(&ctx, "IDs identified in Jeff Mills at Tresor");
+        assert_eq!(emails.len(), 1);
+        assert!(emails[0].contains(&requester.email));
+        assert!(emails[0].contains("Jaguar"));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn sends_weekly_digest(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (reader, (jwt_key, jwt_value), _) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+        let (_, _, (api_key, api_value)) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+
+        let payload = prepare_data::set_payload().tracks(&[("Jeff Mills", "The Bells")]);
+        request
+            .post("/api/v1/sets")
+            .add_header(api_key, api_value)
+            .json(&payload)
+            .await;
+        let response = request
+            .post("/djs/jeff-mills/follow")
+            .add_header(jwt_key.clone(), jwt_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        DigestWorker::build(&ctx)
+            .perform(DigestWorkerArgs {})
+            .await?;
+        assert!(sent_emails(&ctx, "Your week on SetListList").is_empty());
+
+        let response = request
+            .post("/settings/notifications")
+            .add_header(jwt_key, jwt_value)
+            .json(&json!({"topics": ["followed_sets", "weekly_digest"]}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        for _ in 0..2 {
+            DigestWorker::build(&ctx)
+                .perform(DigestWorkerArgs {})
+                .await?;
+        }
+        let emails = sent_emails(&ctx, "Your week on SetListList: 1 new sets");
+        assert_eq!(emails.len(), 1);
+        assert!(emails[0].contains(&reader.email));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn can_unsubscribe_in_one_click(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (user, (jwt_key, jwt_value), _) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+
+        let response = request
+            .get("/settings/notifications")
+            .add_header(jwt_key, jwt_value)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("New sets by DJs you follow"));
+
+        let token = email_preferences::Model::find_or_create(&ctx.db, user.id)
+            .await?
+            .unsubscribe_token;
+        let path = format!("/unsubscribe/{token}?topic=followed_sets");
+
+        let response = request.get(&path).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("Stop receiving these emails"));
+        let preferences = email_preferences::Model::find_or_create(&ctx.db, user.id).await?;
+        assert!(preferences.followed_sets);
+
+        let response = request.post(&path).text("List-Unsubscribe=One-Click").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let preferences = email_preferences::Model::find_or_create(&ctx.db, user.id).await?;
+        assert!(!preferences.followed_sets);
+        assert!(preferences.edit_proposals);
+
+        let response = request
+            .post(&format!("/unsubscribe/{}", uuid::Uuid::new_v4()))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn shows_notifications_in_app(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (_, (owner_key, owner_value), (api_key, api_value)) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+        let (proposer, (proposer_key, proposer_value), _) =
+            prepare_data::login(&request, &ctx, &[ApiScope::WriteSets]).await?;
+
+        let payload = prepare_data::set_payload().tracks(&[("Jeff Mills", "The Bells")]);
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key, api_value)
+            .json(&payload)
+            .await;
+        let id = response.json::<Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        for message in ["Track 2 is Strings of Life", "It started at midnight"] {
+            request
+                .post(&format!("/sets/{id}/proposals"))
+                .add_header(proposer_key.clone(), proposer_value.clone())
+                .json(&json!({"message": message}))
+                .await;
+        }
+
+        let response = request
+            .get("/notifications/bell")
+            .add_header(owner_key.clone(), owner_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response
+            .text()
+            .contains(r#"<span class="badge badge-secondary badge-sm">2</span>"#));
+
+        let response = request
+            .get("/notifications")
+            .add_header(owner_key.clone(), owner_value.clone())
+            .await;
+        let text = response.text();
+        assert!(text.contains(&format!(
+            "{} proposed an edit to Jeff Mills at Tresor",
+            proposer.username
+        )));
+        let notification_id = text
+            .split(r#"href="/notifications/"#)
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+            .unwrap_or_default()
+            .to_string();
+
+        let response = request
+            .post(&format!("/notifications/{notification_id}/read"))
+            .add_header(proposer_key.clone(), proposer_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        let response = request
+            .post(&format!("/notifications/{notification_id}/read"))
+            .add_header(owner_key.clone(), owner_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header("hx-trigger"), "notifications-read");
+        assert!(!response.text().contains("Mark as read"));
+
+        let response = request
+            .get("/notifications/bell")
+            .add_header(owner_key.clone(), owner_value.clone())
+            .await;
+        assert!(response.text().contains(">1</span>"));
+
+        let response = request
+            .get(&format!("/notifications/{notification_id}"))
+            .add_header(owner_key.clone(), owner_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::SEE_OTHER);
+        assert_eq!(response.header("location"), format!("/sets/{id}#proposals"));
+
+        let response = request
+            .post("/notifications/read")
+            .add_header(owner_key.clone(), owner_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(!response.text().contains("Mark as read"));
+
+        let response = request
+            .get("/notifications/bell")
+            .add_header(owner_key, owner_value)
+            .await;
+        assert!(!response.text().contains("badge"));
+
+        Ok(())
+    })
+    .await
+}
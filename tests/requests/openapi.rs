@@ -0,0 +1,53 @@
+use insta::assert_debug_snapshot;
+use loco_rs::testing;
+use serde_json::Value;
+use setlist_list::app::App;
+use sqlx::PgPool;
+
+macro_rules! configure_insta {
+    ($($expr:expr),*) => {
+        let mut settings = insta::Settings::clone_current();
+        settings.set_prepend_module_to_snapshot(false);
+        settings.set_snapshot_suffix("openapi_request");
+        let _guard = settings.bind_to_scope();
+    };
+}
+
+#[sqlx::test]
+async fn can_get_openapi_document(pool: PgPool) -> eyre::Result<()> {
+    configure_insta!();
+
+    testing::request::<App, _, _>(pool, |request, _ctx| async move {
+        let response = request.get("/_openapi.json").await;
+        assert_eq!(response.status_code(), 200);
+
+        let document = response.json::<Value>();
+        assert_eq!(document["openapi"], "3.1.0");
+
+        let operation = &document["paths"]["/api/v1/sets/{id}"]["put"];
+        assert_eq!(
+            operation["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/Params"
+        );
+        assert_eq!(
+            operation["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/SetResponse"
+        );
+        assert_eq!(operation["security"][0]["bearer"], serde_json::json!([]));
+
+        let login = &document["paths"]["/login"];
+        assert!(login["get"]["requestBody"].is_null());
+        assert_eq!(
+            login["post"]["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/LoginParams"
+        );
+
+        let schemas = document["components"]["schemas"]
+            .as_object()
+            .map(|schemas| schemas.keys().cloned().collect::<Vec<_>>());
+        assert_debug_snapshot!(schemas);
+
+        Ok(())
+    })
+    .await
+}
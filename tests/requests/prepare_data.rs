@@ -1,7 +1,18 @@
 use axum::http::{header, HeaderName, HeaderValue};
 use eyre::ContextCompat as _;
 use loco_rs::{app::AppContext, TestServer};
-use setlist_list::models::users;
+use serde::Serialize;
+use serde_json::{json, Value};
+use setlist_list::{
+    models::{
+        api_keys::{self, ApiScope},
+        sets, songs, users,
+    },
+    services,
+};
+use uuid::Uuid;
+
+use crate::utils::get_random_user_email;
 
 const USER_PASSWORD: &str = "1234";
 
@@ -62,8 +73,99 @@ pub async fn init_user_login(
     })
 }
 
-pub fn _auth_header(token: &str) -> eyre::Result<(HeaderName, HeaderValue)> {
+pub fn auth_header(token: &str) -> eyre::Result<(HeaderName, HeaderValue)> {
     let auth_header_value = HeaderValue::from_str(&format!("Bearer {}", &token))?;
 
     Ok((HeaderName::from_static("authorization"), auth_header_value))
 }
+
+pub async fn create_api_key(
+    ctx: &AppContext,
+    user: &users::Model,
+    scopes: &[ApiScope],
+) -> eyre::Result<String> {
+    let (_, key) = api_keys::Model::create(&ctx.db, user.id, "test", scopes, None).await?;
+    Ok(key)
+}
+
+/// Logs in a new user, returning it with a JWT header and the header of an
+/// API key with `scopes`.
+pub async fn login(
+    request: &TestServer,
+    ctx: &AppContext,
+    scopes: &[ApiScope],
+) -> eyre::Result<(
+    users::Model,
+    (HeaderName, HeaderValue),
+    (HeaderName, HeaderValue),
+)> {
+    let (username, email) = get_random_user_email();
+    let user = init_user_login(request, ctx, &username, &email).await?;
+    let jwt = auth_header(&user._token)?;
+    let api_key = auth_header(&create_api_key(ctx, &user.user, scopes).await?)?;
+    Ok((user.user, jwt, api_key))
+}
+
+/// The JSON body of a set to create or update, by default a set of Jeff Mills
+/// at Tresor without tracks.
+#[derive(Clone, Serialize)]
+#[serde(transparent)]
+pub struct SetPayload(Value);
+
+pub fn set_payload() -> SetPayload {
+    SetPayload(json!({
+        "dj_names": ["Jeff Mills"],
+        "venue": "Tresor",
+        "start_time": "2024-07-27T23:00",
+        "setlist": [],
+    }))
+}
+
+impl SetPayload {
+    #[must_use]
+    pub fn dj(self, name: &str) -> Self {
+        self.with("dj_names", json!([name]))
+    }
+
+    /// Sets the tracklist to tracks by artist and title.
+    #[must_use]
+    pub fn tracks(self, tracks: &[(&str, &str)]) -> Self {
+        let setlist = tracks
+            .iter()
+            .map(|(artist, title)| json!({"track_artist": artist, "track_title": title}))
+            .collect::<Vec<_>>();
+        self.with("setlist", setlist)
+    }
+
+    #[must_use]
+    pub fn with(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.0[key] = value.into();
+        self
+    }
+}
+
+/// Saves `item` with tracks by artist and title, numbered in order, the way
+/// the site and the API do.
+pub async fn add_set(
+    ctx: &AppContext,
+    item: sets::Model,
+    tracks: &[(&str, &str)],
+) -> eyre::Result<sets::Model> {
+    let item = sets::Model {
+        id: Uuid::now_v7(),
+        ..item
+    };
+    let tracklist = tracks
+        .iter()
+        .zip(1..)
+        .map(|((artist, title), number)| songs::Model {
+            setlist_id: item.id,
+            track_artist: (*artist).to_string(),
+            track_title: (*title).to_string(),
+            track_number: Some(number),
+            ..Default::default()
+        })
+        .collect::<Vec<_>>();
+    let (item, _) = services::sets::create(ctx, &item, &tracklist, None).await?;
+    Ok(item)
+}
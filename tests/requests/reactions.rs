@@ -0,0 +1,99 @@
+use axum::http::StatusCode;
+use loco_rs::testing;
+use serde_json::json;
+use setlist_list::{app::App, models::sets};
+use sqlx::PgPool;
+use uuid::uuid;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+const SET: &str = "33333333-3333-3333-3333-333333333333";
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql", "../models/fixtures/sets.sql"))]
+async fn can_like_rate_and_attend_sets(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let (other_username, email) = get_random_user_email();
+        let other = prepare_data::init_user_login(&request, &ctx, &other_username, &email).await?;
+        let (other_key, other_value) = prepare_data::auth_header(&other._token)?;
+
+        let response = request.post(&format!("/sets/{SET}/like")).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+
+        for path in ["like", "attendance"] {
+            let response = request
+                .post(&format!("/sets/{SET}/{path}"))
+                .add_header(auth_key.clone(), auth_value.clone())
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK, "{path}");
+        }
+        let response = request
+            .post(&format!("/sets/{SET}/rating"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"rating": 6}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        for (key, value, rating) in [(&auth_key, &auth_value, 4), (&other_key, &other_value, 5)] {
+            let response = request
+                .post(&format!("/sets/{SET}/rating"))
+                .add_header(key.clone(), value.clone())
+                .json(&json!({"rating": rating}))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+        }
+
+        let item =
+            sets::Model::find_by_id(&ctx.db, uuid!("33333333-3333-3333-3333-333333333333")).await?;
+        let stats = item.find_stats(&ctx.db).await?;
+        assert_eq!(
+            (
+                stats.likes,
+                stats.attendees,
+                stats.ratings,
+                stats.average_rating
+            ),
+            (1, 1, 2, Some(4.5))
+        );
+        let reactions = item.find_reactions(&ctx.db, user.user.id).await?;
+        assert_eq!(
+            (reactions.liked, reactions.attended, reactions.rating),
+            (true, true, Some(4))
+        );
+
+        let response = request.get(&format!("/sets/{SET}")).await;
+        assert!(response.text().contains("Rated 4.5 / 5"));
+        let response = request.get("/sets").await;
+        assert!(response.text().contains("1 likes"));
+
+        for (tab, listed) in [("sets", false), ("attended", true), ("liked", true)] {
+            let response = request
+                .get(&format!("/user/{username}?tab={tab}"))
+                .add_header(auth_key.clone(), auth_value.clone())
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+            assert_eq!(response.text().contains(SET), listed, "{tab}");
+        }
+        // attendance is private to the user
+        let response = request.get(&format!("/user/{username}")).await;
+        assert!(!response.text().contains("tab=attended"));
+        let response = request.get(&format!("/user/{username}?tab=attended")).await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        let response = request.get(&format!("/user/{username}?tab=bogus")).await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let response = request
+            .delete(&format!("/sets/{SET}/like"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("Like ·"));
+        let response = request.get(&format!("/user/{username}?tab=liked")).await;
+        assert!(!response.text().contains(SET));
+
+        Ok(())
+    })
+    .await
+}
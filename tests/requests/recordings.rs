@@ -0,0 +1,316 @@
+use axum::http::{header, HeaderValue, StatusCode};
+use axum_test::multipart::{MultipartForm, Part};
+use loco_rs::testing;
+use serde_json::{json, Value};
+use setlist_list::{
+    app::App,
+    controllers::uploads::STORE,
+    models::{api_keys::ApiScope, uploads},
+    workers::waveform::Peaks,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+#[sqlx::test]
+async fn can_deep_link_tracks_into_recordings(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let (api_key, api_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+        let (other_username, email) = get_random_user_email();
+        let other = prepare_data::init_user_login(&request, &ctx, &other_username, &email).await?;
+        let (other_key, other_value) = prepare_data::auth_header(&other._token)?;
+
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key, api_value)
+            .json(&prepare_data::set_payload().dj("DJ Rolando").with(
+                "setlist",
+                json!([{
+                    "track_title": "Jaguar",
+                    "track_artist": "DJ Rolando",
+                    "track_number": 1,
+                    "track_start_time_offset_seconds": 754,
+                }]),
+            ))
+            .await;
+        let id = response.json::<Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let recording = json!({"url": "https://www.youtube.com/watch?v=dQw4w9WgXcQ"});
+        let response = request
+            .post(&format!("/sets/{id}/recordings"))
+            .add_header(other_key, other_value)
+            .json(&recording)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        let response = request
+            .post(&format!("/sets/{id}/recordings"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"url": "https://example.com/set.html"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        let response = request
+            .post(&format!("/sets/{id}/recordings"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&recording)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(
+            response.header("HX-Redirect"),
+            format!("/sets/{id}").as_str()
+        );
+
+        let response = request.get(&format!("/sets/{id}")).await;
+        let text = response.text();
+        // URLs are HTML escaped, slashes included
+        assert!(text.contains("youtube-nocookie.com&#x2f;embed&#x2f;dQw4w9WgXcQ"));
+        assert!(text.contains("watch?v=dQw4w9WgXcQ&amp;t=754"));
+        assert!(text.contains("12:34"));
+
+        Ok(())
+    })
+    .await
+}
+
+fn audio_file(bytes: Vec<u8>) -> MultipartForm {
+    MultipartForm::new().add_part(
+        "file",
+        Part::bytes(bytes)
+            .file_name("set.mp3")
+            .mime_type("audio/mpeg"),
+    )
+}
+
+#[sqlx::test]
+async fn can_upload_and_seek_recordings(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let (api_key, api_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key, api_value)
+            .json(&prepare_data::set_payload().dj("DJ Rolando").with(
+                "setlist",
+                json!([{
+                    "track_title": "Jaguar",
+                    "track_artist": "DJ Rolando",
+                    "track_number": 1,
+                    "track_start_time_offset_seconds": 754,
+                }]),
+            ))
+            .await;
+        let id = response.json::<Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let response = request
+            .post(&format!("/sets/{id}/recordings/upload"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .multipart(MultipartForm::new().add_part(
+                "file",
+                Part::bytes(b"<html></html>".to_vec()).mime_type("text/html"),
+            ))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        // larger than the payload limit would allow buffering, the test
+        // config leaves 64 KiB per user
+        let content = (0..40_000).map(|i| (i % 251) as u8).collect::<Vec<_>>();
+        let response = request
+            .post(&format!("/sets/{id}/recordings/upload"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .multipart(audio_file(content.clone()))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = request
+            .post(&format!("/sets/{id}/recordings/upload"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .multipart(audio_file(content.clone()))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let page = request
+            .get(&format!("/sets/{id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await
+            .text();
+        let start = page.find("&#x2f;uploads&#x2f;").unwrap_or_default() + 19;
+        let upload_id = &page[start..start + 36];
+        assert!(page.contains(&format!("&#x2f;uploads&#x2f;{upload_id}#t=754")));
+        let url = format!("/uploads/{upload_id}");
+
+        let response = request.get(&url).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header(header::CONTENT_TYPE), "audio/mpeg");
+        assert_eq!(response.header(header::ACCEPT_RANGES), "bytes");
+        assert_eq!(response.header(header::X_CONTENT_TYPE_OPTIONS), "nosniff");
+        assert_eq!(response.as_bytes().as_ref(), content.as_slice());
+
+        let response = request
+            .get(&url)
+            .add_header(header::RANGE, HeaderValue::from_static("bytes=100-199"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            response.header(header::CONTENT_RANGE),
+            "bytes 100-199/40000"
+        );
+        assert_eq!(response.as_bytes().as_ref(), &content[100..200]);
+        let response = request
+            .get(&url)
+            .add_header(header::RANGE, HeaderValue::from_static("bytes=-10"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.as_bytes().as_ref(), &content[39_990..]);
+        let response = request
+            .get(&url)
+            .add_header(header::RANGE, HeaderValue::from_static("bytes=40000-"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(response.header(header::CONTENT_RANGE), "bytes */40000");
+
+        // only the owner can change or delete the set along with its uploads
+        let (other_name, other_email) = get_random_user_email();
+        let other =
+            prepare_data::init_user_login(&request, &ctx, &other_name, &other_email).await?;
+        let (other_key, other_value) = prepare_data::auth_header(&other._token)?;
+        let update = prepare_data::set_payload().dj("Someone");
+        let response = request.delete(&format!("/sets/{id}")).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = request.post(&format!("/sets/{id}")).json(&update).await;
+        assert_eq!(response.status_code(), StatusCode::UNAUTHORIZED);
+        let response = request
+            .delete(&format!("/sets/{id}"))
+            .add_header(other_key.clone(), other_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        let response = request
+            .post(&format!("/sets/{id}"))
+            .add_header(other_key, other_value)
+            .json(&update)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        assert_eq!(request.get(&url).await.status_code(), StatusCode::OK);
+
+        // removing the recording frees the quota
+        let recording_id = page
+            .split(&format!("hx-delete=\"/sets/{id}/recordings/"))
+            .nth(1)
+            .map(|rest| rest[..36].to_string())
+            .unwrap_or_default();
+        let response = request
+            .delete(&format!("/sets/{id}/recordings/{recording_id}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(request.get(&url).await.status_code(), StatusCode::NOT_FOUND);
+        let response = request
+            .post(&format!("/sets/{id}/recordings/upload"))
+            .add_header(auth_key, auth_value)
+            .multipart(audio_file(content))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        Ok(())
+    })
+    .await
+}
+
+/// Two seconds of a 16-bit mono 8 kHz tone at half volume, as a WAV file.
+fn wav_tone() -> Vec<u8> {
+    let samples = (0..16_000)
+        .flat_map(|i| {
+            let phase = f64::from(i) * 440.0 / 8000.0 * std::f64::consts::TAU;
+            #[allow(clippy::cast_possible_truncation)]
+            let sample = (phase.sin() * f64::from(i16::MAX) / 2.0) as i16;
+            sample.to_le_bytes()
+        })
+        .collect::<Vec<_>>();
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&8000u32.to_le_bytes());
+    wav.extend_from_slice(&16_000u32.to_le_bytes()); // bytes per second
+    wav.extend_from_slice(&2u16.to_le_bytes()); // bytes per frame
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&samples);
+    wav
+}
+
+#[sqlx::test]
+async fn can_draw_waveforms_of_uploads(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let (api_key, api_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key, api_value)
+            .json(&prepare_data::set_payload().dj("DJ Rolando").with(
+                "setlist",
+                json!([{
+                    "track_title": "Jaguar",
+                    "track_artist": "DJ Rolando",
+                    "track_number": 1,
+                    "track_start_time_offset_seconds": 1,
+                }]),
+            ))
+            .await;
+        let id = Uuid::parse_str(response.json::<Value>()["id"].as_str().unwrap_or_default())?;
+
+        let response = request
+            .post(&format!("/sets/{id}/recordings/upload"))
+            .add_header(auth_key, auth_value)
+            .multipart(
+                MultipartForm::new().add_part(
+                    "file",
+                    Part::bytes(wav_tone())
+                        .file_name("set.wav")
+                        .mime_type("audio/wav"),
+                ),
+            )
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        // the worker runs in the foreground in tests
+        let upload = uploads::Model::list_by_set_id(&ctx.db, id).await?;
+        let peaks = Peaks::load(ctx.storage.as_store_err(STORE)?, upload[0].id)
+            .await
+            .unwrap_or_else(|| panic!("peaks were not measured"));
+        assert!((peaks.duration_seconds - 2.0).abs() < 0.01);
+        assert_eq!(peaks.peaks.len(), 20);
+        assert!(peaks.peaks.iter().all(|peak| (126..=129).contains(peak)));
+
+        let page = request.get(&format!("/sets/{id}")).await.text();
+        assert!(page.contains("aria-label=\"Waveform\""));
+        // the track starts halfway through
+        assert!(page.contains("x1=\"10.0\""));
+        assert!(page.contains("0:01 DJ Rolando - Jaguar"));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,70 @@
+use loco_rs::testing;
+use serde_json::{json, Value};
+use setlist_list::{app::App, models::api_keys::ApiScope};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn reports_set_flow(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+
+        let track = |title: &str, key: &str, bpm: f64, start: i32| {
+            json!({
+                "track_artist": "Jeff Mills", "track_title": title,
+                "track_key": key, "track_bpm": bpm,
+                "track_start_time_offset_seconds": start
+            })
+        };
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(auth_key, auth_value)
+            .json(&prepare_data::set_payload().with(
+                "setlist",
+                json!([
+                    track("One", "Am", 124.0, 0),
+                    track("Two", "9A", 126.0, 300),
+                    track("Three", "3B", 140.0, 600),
+                    track("Four", "3A", 70.0, 900),
+                    {"track_artist": "ID", "track_title": "ID", "track_start_time_offset_seconds": 1200},
+                ]),
+            ))
+            .await;
+        let id = response.json::<Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        let page = request.get(&format!("/sets/{id}")).await.text();
+        assert!(page.contains("Set flow"));
+        assert!(page.contains("1 of 4 transitions clash in"));
+        assert!(page.contains("Em · 9A → Db · 3B:"));
+        assert!(page.contains("<b>clash</b>, 7 steps apart"));
+        assert!(page.contains("<b>126 → 140 BPM (+11.1%)</b>"));
+        assert!(page.contains("124 → 126 BPM (+1.6%)"));
+        assert!(page.contains("140 → 70 BPM (+0.0%)"));
+        assert!(page.contains(", double time"));
+        assert!(page.contains("over time"));
+        assert!(page.contains("<polyline"));
+
+        // nothing to analyse without keys or tempos
+        let page = request
+            .get("/sets/33333333-3333-3333-3333-333333333333")
+            .await
+            .text();
+        assert!(!page.contains("Set flow"));
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,157 @@
+use axum::http::StatusCode;
+use loco_rs::testing;
+use serde_json::Value;
+use setlist_list::{app::App, models::api_keys::ApiScope};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql", "../models/fixtures/sets.sql"))]
+async fn hides_unlisted_and_private_sets(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        let (other_username, email) = get_random_user_email();
+        let other = prepare_data::init_user_login(&request, &ctx, &other_username, &email).await?;
+        let (other_auth_key, other_auth_value) = prepare_data::auth_header(&other._token)?;
+        let other_key = prepare_data::create_api_key(&ctx, &other.user, &[ApiScope::Read]).await?;
+
+        let mut ids = vec![];
+        for (dj_name, visibility) in [("Hidden DJ", "unlisted"), ("Secret DJ", "private")] {
+            let response = request
+                .post("/api/v1/sets")
+                .authorization_bearer(&key)
+                .json(
+                    &prepare_data::set_payload()
+                        .dj(dj_name)
+                        .with("venue", "Hidden Bar")
+                        .with("visibility", visibility),
+                )
+                .await;
+            assert_eq!(response.status_code(), StatusCode::CREATED);
+            let created = response.json::<Value>();
+            assert_eq!(created["visibility"], visibility);
+            ids.push(created["id"].as_str().unwrap_or_default().to_string());
+        }
+        let (unlisted, private) = (&ids[0], &ids[1]);
+
+        // unlisted sets are shown to anyone with the link
+        let response = request.get(&format!("/sets/{unlisted}")).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response
+            .text()
+            .contains("<meta name=\"robots\" content=\"noindex\" />"));
+        for path in [
+            format!("/embed/sets/{unlisted}"),
+            format!("/sets/{unlisted}/share.png"),
+        ] {
+            assert_eq!(
+                request.get(&path).await.status_code(),
+                StatusCode::OK,
+                "{path}"
+            );
+        }
+
+        // private sets only to their creator
+        for path in [
+            format!("/sets/{private}"),
+            format!("/embed/sets/{private}"),
+            format!("/sets/{private}/share.png"),
+            format!("/sets/{private}/comments"),
+        ] {
+            assert_eq!(
+                request.get(&path).await.status_code(),
+                StatusCode::NOT_FOUND,
+                "{path}"
+            );
+        }
+        let response = request
+            .get(&format!("/sets/{private}"))
+            .add_header(other_auth_key.clone(), other_auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        let response = request
+            .post(&format!("/sets/{private}/like"))
+            .add_header(other_auth_key.clone(), other_auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        let response = request
+            .get(&format!("/api/v1/sets/{private}"))
+            .authorization_bearer(&other_key)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        let response = request
+            .get(&format!("/sets/{private}"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = request
+            .get(&format!("/api/v1/sets/{private}"))
+            .authorization_bearer(&key)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let response = request
+            .get(&format!("/sets/{private}/edit"))
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert!(response
+            .text()
+            .contains("<option value=\"private\" selected>"));
+
+        // neither is listed anywhere
+        for path in [
+            "/sets".to_string(),
+            "/sets.atom".to_string(),
+            "/sitemaps/sets-1.xml".to_string(),
+            format!("/user/{username}/sets.atom"),
+        ] {
+            let response = request.get(&path).await;
+            assert_eq!(response.status_code(), StatusCode::OK, "{path}");
+            let body = response.text();
+            assert!(!body.contains(unlisted.as_str()), "{path}");
+            assert!(!body.contains(private.as_str()), "{path}");
+        }
+        let listed = request
+            .get("/api/v1/sets")
+            .authorization_bearer(&key)
+            .await
+            .text();
+        assert!(!listed.contains(unlisted.as_str()));
+        assert!(!listed.contains(private.as_str()));
+        for path in ["/djs/hidden-dj", "/djs/secret-dj", "/venues/hidden-bar"] {
+            assert_eq!(
+                request.get(path).await.status_code(),
+                StatusCode::NOT_FOUND,
+                "{path}"
+            );
+        }
+        let djs = request.get("/sitemaps/djs-1.xml").await.text();
+        assert!(!djs.contains("hidden-dj"));
+        let response = request
+            .get(&format!("/user/{username}"))
+            .add_header(other_auth_key, other_auth_value)
+            .await;
+        assert!(!response.text().contains(unlisted.as_str()));
+        let response = request
+            .get(&format!("/user/{username}"))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert!(response.text().contains(unlisted.as_str()));
+        assert!(response.text().contains(private.as_str()));
+
+        // leaving the visibility out keeps it
+        let response = request
+            .put(&format!("/api/v1/sets/{private}"))
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().dj("Secret DJ"))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.json::<Value>()["visibility"], "private");
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,109 @@
+use axum::http::StatusCode;
+use loco_rs::testing;
+use serde_json::json;
+use setlist_list::{app::App, models::api_keys};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+#[sqlx::test]
+async fn can_create_and_revoke_api_key(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+
+        let response = request
+            .get("/settings/api_keys")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("No API keys yet."));
+
+        let response = request
+            .post("/settings/api_keys")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({
+                "name": "scripts",
+                "scopes": ["read", "sets:write"],
+                "expires_in_days": "30"
+            }))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+
+        let keys = api_keys::Model::list_by_user_id(&ctx.db, user.user.id).await?;
+        assert_eq!(keys.len(), 1);
+        assert_eq!(keys[0].scopes, vec!["read", "sets:write"]);
+        assert!(keys[0].expires_at.is_some());
+        // the full key is shown once, only its prefix is stored
+        assert!(response.text().contains(&keys[0].prefix));
+        assert!(!response.text().contains(&keys[0].key_hash));
+
+        let response = request
+            .post("/settings/api_keys")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"name": "no scopes", "expires_in_days": ""}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+        for expires_in_days in ["0", "3651", "100000000"] {
+            let response = request
+                .post("/settings/api_keys")
+                .add_header(auth_key.clone(), auth_value.clone())
+                .json(&json!({
+                    "name": "forever",
+                    "scopes": ["read"],
+                    "expires_in_days": expires_in_days
+                }))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::UNPROCESSABLE_ENTITY);
+        }
+        assert_eq!(
+            api_keys::Model::list_by_user_id(&ctx.db, user.user.id)
+                .await?
+                .len(),
+            1
+        );
+
+        let response = request
+            .delete(&format!("/settings/api_keys/{}", keys[0].id))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(api_keys::Model::list_by_user_id(&ctx.db, user.user.id)
+            .await?
+            .is_empty());
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn cannot_revoke_other_users_api_key(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let owner = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (item, _) = api_keys::Model::create(
+            &ctx.db,
+            owner.user.id,
+            "owner",
+            &[api_keys::ApiScope::Read],
+            None,
+        )
+        .await?;
+
+        let (username, email) = get_random_user_email();
+        let other = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&other._token)?;
+
+        let response = request
+            .delete(&format!("/settings/api_keys/{}", item.id))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,59 @@
+use axum::http::{header, HeaderValue, StatusCode};
+use loco_rs::testing;
+use setlist_list::{app::App, controllers::sets};
+use sqlx::PgPool;
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn can_preview_shared_sets(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let page = request
+            .get("/sets/33333333-3333-3333-3333-333333333333")
+            .await
+            .text();
+        assert!(page.contains(
+            "<meta property=\"og:title\" content=\"Jeff Mills B2B Frankie Knuckles at Marble Bar\" />"
+        ));
+        assert!(page.contains("27 May 2022, Marble Bar, Detroit. 2 tracks: "));
+        assert!(page.contains("<meta name=\"twitter:card\" content=\"summary_large_image\" />"));
+        // links are absolute, the slashes escaped like any other text
+        assert!(page.contains(
+            "&#x2f;sets&#x2f;33333333-3333-3333-3333-333333333333&#x2f;share.png"
+        ));
+        assert!(page.contains("content=\"http:&#x2f;&#x2f;localhost"));
+
+        let url = "/sets/33333333-3333-3333-3333-333333333333/share.png";
+        let response = request.get(url).await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header(header::CONTENT_TYPE), "image/png");
+        assert_eq!(
+            response.header(header::CACHE_CONTROL),
+            "public, max-age=86400"
+        );
+        let image = image::load_from_memory(response.as_bytes())?;
+        assert_eq!((image.width(), image.height()), (1200, 630));
+
+        let etag = response.header(header::ETAG);
+        // rendered once, then served from the cache
+        let key = sets::share_image_key(etag.to_str()?);
+        assert!(ctx.cache.get(&key).await?.is_some());
+        let cached = request.get(url).await;
+        assert_eq!(cached.as_bytes(), response.as_bytes());
+
+        let response = request
+            .get(url)
+            .add_header(header::IF_NONE_MATCH, HeaderValue::from_str(etag.to_str()?)?)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::NOT_MODIFIED);
+
+        let page = request.get("/sets").await.text();
+        assert!(page.contains("<meta property=\"og:title\" content=\"Sets\" />"));
+        assert!(page.contains("<meta name=\"twitter:card\" content=\"summary\" />"));
+
+        Ok(())
+    })
+    .await
+}
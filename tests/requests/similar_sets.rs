@@ -0,0 +1,102 @@
+use axum::http::StatusCode;
+use loco_rs::testing;
+use serde_json::Value;
+use setlist_list::{
+    app::App,
+    models::{api_keys::ApiScope, sets},
+};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+const FIXTURE_SET: &str = "/sets/33333333-3333-3333-3333-333333333333";
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn suggests_sets_like_this(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&prepare_data::set_payload().tracks(&[
+                ("Jeff Mills", "The Bells"),
+                ("Rhythim Is Rhythim", "Strings of Life"),
+            ]))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::CREATED);
+        let similar_id = response.json::<Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(
+                &prepare_data::set_payload()
+                    .dj("Nobody")
+                    .tracks(&[("ID", "ID")]),
+            )
+            .await;
+        let unlike_id = response.json::<Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+
+        // recomputed by the worker as soon as the set was created
+        let page = request.get(FIXTURE_SET).await.text();
+        assert!(page.contains("Sets like this"));
+        assert!(page.contains(&format!("/sets/{similar_id}")));
+        assert!(page.contains("1 track in common, same DJ"));
+        assert!(!page.contains(&format!("/sets/{unlike_id}")));
+        let page = request.get(&format!("/sets/{unlike_id}")).await.text();
+        assert!(!page.contains("Sets like this"));
+
+        // a set played by the same DJs with the same tracks comes first
+        let closest = prepare_data::add_set(
+            &ctx,
+            sets::Model {
+                creator_id: user.user.id,
+                dj_names: vec!["Frankie Knuckles".to_string(), "Jeff Mills".to_string()],
+                ..Default::default()
+            },
+            &[
+                ("Frankie Knuckles & Jamie Principle", "Your Love"),
+                ("jeff mills", "The Bells "),
+            ],
+        )
+        .await?;
+        let page = request.get(FIXTURE_SET).await.text();
+        let closest_at = page.find(&format!("/sets/{}", closest.id));
+        let similar_at = page.find(&format!("/sets/{similar_id}"));
+        assert!(closest_at.is_some() && similar_at.is_some());
+        assert!(closest_at < similar_at);
+        assert!(page.contains("2 tracks in common, same DJ"));
+
+        // and goes away once it has nothing in common anymore
+        let response = request
+            .put(&format!("/api/v1/sets/{similar_id}"))
+            .add_header(auth_key, auth_value)
+            .json(
+                &prepare_data::set_payload()
+                    .dj("Nobody")
+                    .tracks(&[("Nobody", "Nothing")]),
+            )
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let page = request.get(FIXTURE_SET).await.text();
+        assert!(!page.contains(&format!("/sets/{similar_id}")));
+
+        Ok(())
+    })
+    .await
+}
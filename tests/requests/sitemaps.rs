@@ -0,0 +1,175 @@
+use axum::http::{header, StatusCode};
+use loco_rs::testing;
+use serde_json::json;
+use setlist_list::{app::App, models::api_keys::ApiScope, settings::Settings};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql", "../models/fixtures/sets.sql"))]
+async fn can_list_public_pages(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let response = request.get("/robots.txt").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let robots = response.text();
+        assert!(robots.starts_with("User-agent: *\n"));
+        assert!(robots.contains("\nDisallow: /settings\n"));
+        assert!(robots.ends_with("\nSitemap: http://localhost:5150/sitemap.xml"));
+
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        for (name, visibility) in [("Hidden gems", "private"), ("Detroit", "public")] {
+            let response = request
+                .post("/collections")
+                .add_header(auth_key.clone(), auth_value.clone())
+                .json(&json!({"name": name, "visibility": visibility}))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::OK);
+        }
+
+        let response = request.get("/sitemap.xml").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response
+            .header(header::CONTENT_TYPE)
+            .to_str()?
+            .starts_with("application/xml"));
+        let index = response.text().replace("&#x2f;", "/");
+        for page in [
+            "sets-1",
+            "djs-1",
+            "venues-1",
+            "events-1",
+            "users-1",
+            "collections-1",
+        ] {
+            assert!(index.contains(&format!(
+                "<loc>http://localhost:5150/sitemaps/{page}.xml</loc>"
+            )));
+        }
+        assert!(!index.contains("sets-2"));
+
+        let sets = request
+            .get("/sitemaps/sets-1.xml")
+            .await
+            .text()
+            .replace("&#x2f;", "/");
+        assert!(sets.contains(
+            "<loc>http://localhost:5150/sets/33333333-3333-3333-3333-333333333333</loc>\n    \
+             <lastmod>2022-06-02T11:20:00Z</lastmod>"
+        ));
+        let djs = request
+            .get("/sitemaps/djs-1.xml")
+            .await
+            .text()
+            .replace("&#x2f;", "/");
+        for dj in ["frankie-knuckles", "jeff-mills"] {
+            assert!(djs.contains(&format!("<loc>http://localhost:5150/djs/{dj}</loc>")));
+        }
+        for (section, path) in [
+            ("venues", "/venues/marble-bar"),
+            ("events", "/events/movement-festival"),
+        ] {
+            let urls = request
+                .get(&format!("/sitemaps/{section}-1.xml"))
+                .await
+                .text()
+                .replace("&#x2f;", "/");
+            assert!(urls.contains(&format!(
+                "<loc>http://localhost:5150{path}</loc>\n    \
+                 <lastmod>2022-06-02T11:20:00Z</lastmod>"
+            )));
+        }
+        let collections = request.get("/sitemaps/collections-1.xml").await.text();
+        assert_eq!(collections.matches("<url>").count(), 1);
+        for missing in [
+            "/sitemaps/sets-2.xml",
+            "/sitemaps/djs-2.xml",
+            "/sitemaps/events-2.xml",
+            "/sitemaps/sets-1",
+        ] {
+            assert_eq!(
+                request.get(missing).await.status_code(),
+                StatusCode::NOT_FOUND
+            );
+        }
+
+        // created sets are listed, two to a page
+        let (api_key, api_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+        for dj_name in ["DJ Rolando", "Robert Hood"] {
+            let response = request
+                .post("/api/v1/sets")
+                .add_header(api_key.clone(), api_value.clone())
+                .json(&prepare_data::set_payload().dj(dj_name))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::CREATED);
+        }
+        let index = request.get("/sitemap.xml").await.text();
+        assert!(index.contains("sets-2"));
+        let users = request
+            .get("/sitemaps/users-1.xml")
+            .await
+            .text()
+            .replace("&#x2f;", "/");
+        assert!(users.contains(&format!("<loc>http://localhost:5150/user/{username}</loc>")));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn rejects_empty_sitemap_pages(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |_request, ctx| async move {
+        let mut config = ctx.config.clone();
+        let mut settings = config.settings.clone().unwrap_or_default();
+        settings["sitemaps"]["page_size"] = json!(0);
+        config.settings = Some(settings);
+        assert!(Settings::from_config(&config).is_err());
+        assert!(Settings::from_config(&ctx.config).is_ok());
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql", "../models/fixtures/sets.sql"))]
+async fn replacing_tracklist_updates_sitemaps(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        let response = request
+            .post("/api/v1/sets")
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().dj("DJ Rolando"))
+            .await;
+        let id = response.json::<serde_json::Value>()["id"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string();
+        // lastmod only has seconds
+        sqlx::query("UPDATE sets SET updated_at = '2024-07-28 12:00' WHERE id = $1::uuid")
+            .bind(&id)
+            .execute(&ctx.db)
+            .await?;
+
+        let before = request.get("/sitemaps/sets-1.xml").await.text();
+        assert!(before.contains("<lastmod>2024-07-28T12:00:00Z</lastmod>"));
+        let response = request
+            .put(&format!("/api/v1/sets/{id}/tracklist"))
+            .authorization_bearer(&key)
+            .json(&json!([{"track_artist": "Jeff Mills", "track_title": "The Bells"}]))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let after = request.get("/sitemaps/sets-1.xml").await.text();
+        assert!(after.contains(&id));
+        assert!(!after.contains("<lastmod>2024-07-28T12:00:00Z</lastmod>"));
+
+        Ok(())
+    })
+    .await
+}
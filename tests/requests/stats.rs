@@ -0,0 +1,66 @@
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use loco_rs::{prelude::*, testing, worker::Worker as _};
+use setlist_list::{
+    app::App,
+    models::sets,
+    workers::stats::{StatsWorker, StatsWorkerArgs},
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::prepare_data;
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn shows_precomputed_stats(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let recent = prepare_data::add_set(
+            &ctx,
+            sets::Model {
+                creator_id: Uuid::parse_str("11111111-1111-1111-1111-111111111111")?,
+                dj_names: vec!["DJ Rolando".to_string()],
+                venue: Some("Tresor".to_string()),
+                start_time: Utc::now().naive_utc() - Duration::days(3),
+                duration_seconds: Some(5400),
+                ..Default::default()
+            },
+            &[
+                ("DJ Rolando", "Jaguar"),
+                (" Jeff Mills ", "The Bells"),
+                ("ID", "ID"),
+            ],
+        )
+        .await?;
+
+        // nothing until the worker computes the stats
+        let response = request.get("/stats").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("0 sets played"));
+
+        StatsWorker::build(&ctx).perform(StatsWorkerArgs {}).await?;
+
+        let page = request.get("/stats").await.text();
+        assert!(page.contains("2 sets played"));
+        assert!(page.contains("Jeff Mills – The Bells · 2 plays"));
+        assert!(page.contains("Marble Bar · 1 sets"));
+        assert!(!page.contains("ID – ID"));
+        assert!(page.contains("<svg"));
+
+        let page = request.get("/stats?window=month").await.text();
+        assert!(page.contains("1 sets played, 1h 30m long on average"));
+        assert!(page.contains("DJ Rolando · 1 sets"));
+        assert!(!page.contains("Marble Bar"));
+        let month = recent.start_time.format("%b %Y");
+        assert!(page.contains(&format!("<title>{month}: 1 sets</title>")));
+
+        let response = request.get("/stats?window=decade").await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,130 @@
+use axum::http::StatusCode;
+use loco_rs::testing;
+use serde_json::{json, Value};
+use setlist_list::{
+    app::App,
+    models::{api_keys::ApiScope, sets, tags},
+    services,
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+#[sqlx::test]
+async fn can_tag_sets_and_merge_synonyms(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let (api_key, api_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+
+        let too_many = (0..11).map(|i| format!("tag {i}")).collect::<Vec<_>>();
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(api_key.clone(), api_value.clone())
+            .json(&prepare_data::set_payload().with("tags", too_many))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        let mut ids = vec![];
+        for (dj_name, tags) in [
+            ("Jeff Mills", json!(["dnb", "Techno"])),
+            ("Goldie", json!("Drum & Bass, acid")),
+        ] {
+            let response = request
+                .post("/api/v1/sets")
+                .add_header(api_key.clone(), api_value.clone())
+                .json(&prepare_data::set_payload().dj(dj_name).with("tags", tags))
+                .await;
+            assert_eq!(response.status_code(), StatusCode::CREATED);
+            ids.push(Uuid::parse_str(
+                response.json::<Value>()["id"].as_str().unwrap_or_default(),
+            )?);
+        }
+        let names = tags::Model::list_by_set_id(&ctx.db, ids[1])
+            .await?
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect::<Vec<_>>();
+        // curated genres come first
+        assert_eq!(names, ["Drum & Bass", "acid"]);
+
+        let response = request.get("/sets?tag=techno").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains(&ids[0].to_string()));
+        assert!(!response.text().contains(&ids[1].to_string()));
+        let response = request.get("/sets?tag=nope").await;
+        assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+
+        let response = request.get("/tags/suggest?tags=acid,%20dr").await;
+        assert!(response.text().contains("acid, Drum &amp; Bass"));
+
+        let response = request
+            .post("/tags/dnb/merge")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"into": "Drum & Bass"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::FORBIDDEN);
+        sqlx::query("UPDATE users SET is_moderator = true WHERE id = $1")
+            .bind(user.user.id)
+            .execute(&ctx.db)
+            .await?;
+        let response = request
+            .post("/tags/dnb/merge")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"into": "Drum & Bass"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert_eq!(response.header("HX-Redirect"), "/tags/drum-bass");
+
+        let response = request.get("/tags/dnb").await;
+        assert_eq!(response.header("location"), "/tags/drum-bass");
+        let response = request.get("/tags/drum-bass").await;
+        assert!(response.text().contains("Also known as"));
+        for id in &ids {
+            assert!(response.text().contains(&id.to_string()));
+        }
+        let response = request.get("/tags/suggest?tags=dn").await;
+        assert!(response.text().contains("Drum &amp; Bass"));
+
+        // tagging with a synonym tags with the tag it was merged into
+        let item = sets::Model::find_by_id(&ctx.db, ids[1]).await?;
+        tags::Model::replace_for_set(&mut *ctx.db.acquire().await?, item.id, &["dnb".to_string()])
+            .await?;
+        let tags = tags::Model::list_by_set_id(&ctx.db, item.id).await?;
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].slug, "drum-bass");
+
+        let response = request
+            .put("/tags/acid/genre")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&json!({"is_genre": true}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(tags::Model::find_by_slug(&ctx.db, "acid").await?.is_genre);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test(fixtures("../models/fixtures/users.sql"))]
+async fn leaves_no_set_behind_when_its_tags_fail(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |_request, ctx| async move {
+        let item = sets::Model {
+            id: Uuid::now_v7(),
+            creator_id: Uuid::parse_str("11111111-1111-1111-1111-111111111111")?,
+            dj_names: vec!["DJ Rolando".to_string()],
+            ..Default::default()
+        };
+        let created = services::sets::create(&ctx, &item, &[], Some(&["!!!".to_string()])).await;
+        assert!(created.is_err());
+        assert!(sets::Model::find_by_id(&ctx.db, item.id).await.is_err());
+        Ok(())
+    })
+    .await
+}
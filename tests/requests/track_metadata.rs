@@ -0,0 +1,89 @@
+use loco_rs::testing;
+use serde_json::{json, Value};
+use setlist_list::{app::App, models::api_keys::ApiScope};
+use sqlx::PgPool;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+/// Where each of `titles` shows up in the tracklist table of `page`.
+fn positions(page: &str, titles: &[&str]) -> Vec<Option<usize>> {
+    let table = &page[page.find("<h2>Tracklist</h2>").unwrap_or_default()..];
+    titles.iter().map(|title| table.find(title)).collect()
+}
+
+#[sqlx::test]
+async fn can_add_and_sort_track_metadata(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?,
+        )?;
+
+        // the tracklist editor sends strings, empty when left out
+        let setlist = json!([
+            {
+                "track_artist": "Jeff Mills", "track_title": "The Bells",
+                "track_bpm": "138", "track_key": "8A", "track_label": "Purpose Maker",
+                "track_release_year": "1997", "track_remixer": ""
+            },
+            {
+                "track_artist": "Rhythim Is Rhythim", "track_title": "Strings of Life",
+                "track_bpm": 124.504, "track_key": "1d", "track_label": "",
+                "track_release_year": null, "track_remixer": "Derrick May"
+            },
+            {"track_artist": "ID", "track_title": "ID"}
+        ]);
+        let response = request
+            .post("/api/v1/sets")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .json(&prepare_data::set_payload().with("setlist", setlist))
+            .await;
+        let created = response.json::<Value>();
+        assert_eq!(created["tracklist"][0]["track_bpm"], json!(138.0));
+        assert_eq!(created["tracklist"][0]["track_key"], json!("Am"));
+        assert_eq!(created["tracklist"][0]["track_remixer"], json!(null));
+        assert_eq!(created["tracklist"][1]["track_bpm"], json!(124.5));
+        assert_eq!(created["tracklist"][1]["track_key"], json!("C"));
+        assert_eq!(created["tracklist"][1]["track_label"], json!(null));
+        let id = created["id"].as_str().unwrap_or_default().to_string();
+
+        let page = request.get(&format!("/sets/{id}")).await.text();
+        assert!(page.contains("Am · 8A"));
+        assert!(page.contains("Open Key 1m"));
+        assert!(page.contains("C · 8B"));
+        assert!(page.contains("124.5"));
+        assert!(page.contains("Purpose Maker"));
+        assert!(page.contains("(Derrick May remix)"));
+
+        // slowest first, with unknown tempos last either way
+        let titles = ["Strings of Life", "The Bells", "ID – ID"];
+        let page = request.get(&format!("/sets/{id}?sort=bpm")).await.text();
+        let found = positions(&page, &titles);
+        assert!(found[0].is_some() && found[0] < found[1] && found[1] < found[2]);
+        let page = request
+            .get(&format!("/sets/{id}?sort=bpm&desc=true"))
+            .await
+            .text();
+        let found = positions(&page, &titles);
+        assert!(found[1] < found[0] && found[0] < found[2]);
+
+        for track in [
+            json!({"track_artist": "A", "track_title": "B", "track_key": "H minor"}),
+            json!({"track_artist": "A", "track_title": "B", "track_bpm": "fast"}),
+            json!({"track_artist": "A", "track_title": "B", "track_bpm": 0}),
+            json!({"track_artist": "A", "track_title": "B", "track_release_year": 97}),
+        ] {
+            let response = request
+                .post("/api/v1/sets")
+                .add_header(auth_key.clone(), auth_value.clone())
+                .json(&prepare_data::set_payload().with("setlist", json!([track])))
+                .await;
+            assert!(response.status_code().is_client_error());
+        }
+
+        Ok(())
+    })
+    .await
+}
@@ -0,0 +1,108 @@
+use axum::http::StatusCode;
+use loco_rs::{prelude::*, testing, worker::Worker as _};
+use serde_json::{json, Value};
+use setlist_list::{
+    app::App,
+    models::{api_keys::ApiScope, sets},
+    workers::stats::{StatsWorker, StatsWorkerArgs},
+};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+#[sqlx::test(fixtures(
+    "../models/fixtures/users.sql",
+    "../models/fixtures/sets.sql",
+    "../models/fixtures/setlist_songs.sql"
+))]
+async fn can_explore_transitions(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let item = sets::Model {
+            creator_id: Uuid::parse_str("11111111-1111-1111-1111-111111111111")?,
+            dj_names: vec!["DJ Rolando".to_string()],
+            ..Default::default()
+        };
+        prepare_data::add_set(
+            &ctx,
+            item.clone(),
+            &[
+                ("Jeff Mills", "The Bells"),
+                ("DJ Rolando", "Jaguar"),
+                ("ID", "ID"),
+                ("Rhythim Is Rhythim", "Strings of Life"),
+            ],
+        )
+        .await?;
+        prepare_data::add_set(
+            &ctx,
+            item,
+            &[
+                ("jeff mills", "The Bells "),
+                ("Frankie Knuckles & Jamie Principle", "Your Love"),
+            ],
+        )
+        .await?;
+        StatsWorker::build(&ctx).perform(StatsWorkerArgs {}).await?;
+
+        let response = request.get("/tracks/jeff-mills/the-bells").await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let page = response.text();
+        assert!(page.contains("Played 3 times."));
+        assert!(page.contains("Frankie Knuckles &amp; Jamie Principle – Your Love"));
+        assert!(page.contains("· 2 times in 2 sets"));
+        assert!(page.contains("<svg"));
+
+        let graph = request.get("/tracks/dj-rolando/jaguar/graph").await.text();
+        assert!(!graph.contains("<html"));
+        assert!(graph.contains("Jeff Mills – The Bells"));
+        assert!(graph.contains("No transitions out of it yet."));
+        assert!(!graph.contains("Strings of Life"));
+
+        for missing in ["/tracks/nobody/nothing", "/tracks/id/id"] {
+            let response = request.get(missing).await;
+            assert_eq!(response.status_code(), StatusCode::NOT_FOUND);
+        }
+
+        let explorer = request.get("/transitions").await.text();
+        assert!(explorer.contains("· 2 times in 2 sets"));
+        let results = request.get("/transitions/search?q=bell").await.text();
+        assert!(results.contains("Jeff Mills – The Bells"));
+        assert!(!results.contains("Jaguar"));
+
+        let set = request
+            .get("/sets/33333333-3333-3333-3333-333333333333")
+            .await
+            .text();
+        assert!(set.contains("&#x2f;tracks&#x2f;jeff-mills&#x2f;the-bells"));
+
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(
+            &prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::Read]).await?,
+        )?;
+        let response = request
+            .get("/api/v1/transitions")
+            .add_header(auth_key.clone(), auth_value.clone())
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let graph = response.json::<Value>();
+        assert_eq!(graph["pagination"]["total_items"], json!(2));
+        assert_eq!(graph["data"][0]["from_title_slug"], json!("the-bells"));
+        assert_eq!(graph["data"][0]["to_title"], json!("Your Love"));
+        assert_eq!(graph["data"][0]["transitions"], json!(2));
+
+        let response = request
+            .get("/api/v1/transitions/jeff-mills/the-bells")
+            .add_header(auth_key, auth_value)
+            .await;
+        let track = response.json::<Value>();
+        assert_eq!(track["track"]["plays"], json!(3));
+        assert_eq!(track["before"], json!([]));
+        assert_eq!(track["after"][1]["artist_slug"], json!("dj-rolando"));
+
+        Ok(())
+    })
+    .await
+}
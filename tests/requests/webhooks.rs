@@ -0,0 +1,372 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use loco_rs::testing;
+use serde_json::{json, Value};
+use setlist_list::{
+    app::App,
+    models::{
+        api_keys::ApiScope,
+        webhook_deliveries,
+        webhooks::{self, WebhookEvent},
+    },
+    workers::webhook::{backoff, MAX_ATTEMPTS},
+};
+use sqlx::PgPool;
+use tokio::net::TcpListener;
+
+use super::prepare_data;
+use crate::utils::get_random_user_email;
+
+/// A stand-in for a webhook receiver that records every request and rejects
+/// the first `failures` of them.
+#[derive(Clone, Default)]
+struct Receiver {
+    requests: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    failures: Arc<AtomicUsize>,
+}
+
+impl Receiver {
+    async fn start(failures: usize) -> eyre::Result<(Self, String)> {
+        async fn receive(
+            State(receiver): State<Receiver>,
+            headers: HeaderMap,
+            body: String,
+        ) -> StatusCode {
+            receiver
+                .requests
+                .lock()
+                .expect("receiver lock")
+                .push((headers, body));
+            let failures = receiver.failures.load(Ordering::SeqCst);
+            if failures > 0 {
+                receiver.failures.store(failures - 1, Ordering::SeqCst);
+                StatusCode::INTERNAL_SERVER_ERROR
+            } else {
+                StatusCode::NO_CONTENT
+            }
+        }
+
+        let receiver = Self {
+            failures: Arc::new(AtomicUsize::new(failures)),
+            ..Self::default()
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/hook", listener.local_addr()?);
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(receiver.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Ok((receiver, url))
+    }
+
+    fn requests(&self) -> Vec<(HeaderMap, String)> {
+        self.requests.lock().expect("receiver lock").clone()
+    }
+}
+
+#[test]
+fn backs_off_exponentially() {
+    assert_eq!(backoff(0), Duration::from_secs(30));
+    assert_eq!(backoff(1), Duration::from_secs(60));
+    assert_eq!(backoff(3), Duration::from_secs(240));
+}
+
+#[sqlx::test]
+async fn delivers_signed_payloads(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (receiver, url) = Receiver::start(0).await?;
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+
+        let response = request
+            .post("/settings/webhooks")
+            .add_header(auth_key, auth_value)
+            .json(&json!({"url": url, "events": ["set.created", "set.deleted"]}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        let webhook = webhooks::Model::list_by_user_id(&ctx.db, user.user.id)
+            .await?
+            .remove(0);
+
+        let key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        let created = request
+            .post("/api/v1/sets")
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().tracks(&[("Jeff Mills", "The Bells")]))
+            .await
+            .json::<Value>();
+        let id = created["id"].as_str().unwrap_or_default().to_string();
+        // not subscribed to updates
+        request
+            .put(&format!("/api/v1/sets/{id}"))
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().tracks(&[("Jeff Mills", "The Bells")]))
+            .await;
+        request
+            .delete(&format!("/api/v1/sets/{id}"))
+            .authorization_bearer(&key)
+            .await;
+
+        let requests = receiver.requests();
+        assert_eq!(requests.len(), 2);
+        let (headers, body) = &requests[0];
+        assert_eq!(headers["x-setlist-event"], "set.created");
+        assert_eq!(
+            headers["x-setlist-signature"].to_str()?,
+            webhook.sign(body.as_bytes())
+        );
+        let body = serde_json::from_str::<Value>(body)?;
+        assert_eq!(body["event"], "set.created");
+        assert_eq!(body["data"], created);
+        assert_eq!(requests[1].0["x-setlist-event"], "set.deleted");
+
+        let deliveries =
+            webhook_deliveries::Model::list_recent_by_webhook_id(&ctx.db, webhook.id).await?;
+        assert!(deliveries
+            .iter()
+            .all(|delivery| delivery.status == "succeeded" && delivery.attempts == 1));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn retries_failed_deliveries(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (receiver, url) = Receiver::start(2).await?;
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let webhook = webhooks::Model::create(
+            &ctx.db,
+            user.user.id,
+            &url,
+            &[WebhookEvent::SetCreated],
+            true,
+        )
+        .await?;
+
+        let key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        request
+            .post("/api/v1/sets")
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().tracks(&[("Jeff Mills", "The Bells")]))
+            .await;
+
+        // the test config runs workers in the foreground, so retries happen
+        // right away instead of after the backoff delay
+        assert_eq!(receiver.requests().len(), 3);
+        let delivery = webhook_deliveries::Model::list_recent_by_webhook_id(&ctx.db, webhook.id)
+            .await?
+            .remove(0);
+        assert_eq!(delivery.status, "succeeded");
+        assert_eq!(delivery.attempts, 3);
+        assert_eq!(delivery.response_status, Some(204));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn can_redeliver_failed_delivery(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let failures = usize::try_from(MAX_ATTEMPTS)?;
+        let (receiver, url) = Receiver::start(failures).await?;
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let webhook = webhooks::Model::create(
+            &ctx.db,
+            user.user.id,
+            &url,
+            &[WebhookEvent::SetCreated],
+            true,
+        )
+        .await?;
+
+        let key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        request
+            .post("/api/v1/sets")
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().tracks(&[("Jeff Mills", "The Bells")]))
+            .await;
+
+        assert_eq!(receiver.requests().len(), failures);
+        let delivery = webhook_deliveries::Model::list_recent_by_webhook_id(&ctx.db, webhook.id)
+            .await?
+            .remove(0);
+        assert_eq!(delivery.status, "failed");
+        assert_eq!(delivery.response_status, Some(500));
+        assert_eq!(
+            delivery.error.as_deref(),
+            Some("The receiver responded with HTTP 500")
+        );
+        assert!(delivery.next_attempt_at.is_none());
+
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+        let response = request
+            .post(&format!(
+                "/settings/webhooks/{}/deliveries/{}/redeliver",
+                webhook.id, delivery.id
+            ))
+            .add_header(auth_key, auth_value)
+            .await;
+        assert_eq!(response.status_code(), StatusCode::OK);
+        assert!(response.text().contains("succeeded"));
+        assert_eq!(receiver.requests().len(), failures + 1);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn notifies_identified_tracks(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (receiver, url) = Receiver::start(0).await?;
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        webhooks::Model::create(
+            &ctx.db,
+            user.user.id,
+            &url,
+            &[WebhookEvent::TrackIdentified],
+            true,
+        )
+        .await?;
+
+        let key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        let created = request
+            .post("/api/v1/sets")
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().tracks(&[("ID", "ID")]))
+            .await
+            .json::<Value>();
+        let id = created["id"].as_str().unwrap_or_default().to_string();
+        request
+            .put(&format!("/api/v1/sets/{id}/tracklist"))
+            .authorization_bearer(&key)
+            .json(&json!([{"track_title": "Jaguar", "track_artist": "DJ Rolando"}]))
+            .await;
+
+        let requests = receiver.requests();
+        assert_eq!(requests.len(), 1);
+        let body = serde_json::from_str::<Value>(&requests[0].1)?;
+        assert_eq!(body["event"], "set.track_identified");
+        assert_eq!(body["data"]["set_id"], json!(id));
+        assert_eq!(body["data"]["track"]["track_title"], "Jaguar");
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn rejects_invalid_webhook_url(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let (auth_key, auth_value) = prepare_data::auth_header(&user._token)?;
+
+        let response = request
+            .post("/settings/webhooks")
+            .add_header(auth_key, auth_value)
+            .json(&json!({"url": "ftp://example.com", "events": "set.created"}))
+            .await;
+        assert_eq!(response.status_code(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn rejects_private_webhook_addresses(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+
+        for url in [
+            "http://127.0.0.1:8080/hook",
+            "http://localhost/hook",
+            "http://10.0.0.1/hook",
+            "http://192.168.1.1/hook",
+            "http://169.254.169.254/latest/meta-data/",
+            "http://0.0.0.0/hook",
+            "http://[::1]/hook",
+            "http://[fd00:ec2::254]/hook",
+            "http://[::ffff:127.0.0.1]/hook",
+        ] {
+            let created = webhooks::Model::create(
+                &ctx.db,
+                user.user.id,
+                url,
+                &[WebhookEvent::SetCreated],
+                false,
+            )
+            .await;
+            assert!(created.is_err(), "{url} was accepted");
+        }
+        assert!(webhooks::is_public_ip("93.184.215.14".parse()?));
+        assert!(webhooks::is_public_ip(
+            "2606:2800:21f:cb07:6820:80da:af6b:8b2c".parse()?
+        ));
+
+        Ok(())
+    })
+    .await
+}
+
+#[sqlx::test]
+async fn does_not_follow_redirects(pool: PgPool) -> eyre::Result<()> {
+    testing::request::<App, _, _>(pool, |request, ctx| async move {
+        let (receiver, url) = Receiver::start(0).await?;
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let redirect_url = format!("http://{}/hook", listener.local_addr()?);
+        let app = Router::new().route(
+            "/hook",
+            post(move || async move { (StatusCode::TEMPORARY_REDIRECT, [("location", url)]) }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let (username, email) = get_random_user_email();
+        let user = prepare_data::init_user_login(&request, &ctx, &username, &email).await?;
+        let webhook = webhooks::Model::create(
+            &ctx.db,
+            user.user.id,
+            &redirect_url,
+            &[WebhookEvent::SetCreated],
+            true,
+        )
+        .await?;
+
+        let key = prepare_data::create_api_key(&ctx, &user.user, &[ApiScope::WriteSets]).await?;
+        request
+            .post("/api/v1/sets")
+            .authorization_bearer(&key)
+            .json(&prepare_data::set_payload().tracks(&[("Jeff Mills", "The Bells")]))
+            .await;
+
+        assert!(receiver.requests().is_empty());
+        let delivery = webhook_deliveries::Model::list_recent_by_webhook_id(&ctx.db, webhook.id)
+            .await?
+            .remove(0);
+        assert_eq!(delivery.response_status, Some(307));
+
+        Ok(())
+    })
+    .await
+}